@@ -1,13 +1,18 @@
-use std::env;
+use regex::Regex;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output, Stdio};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-const CODEX_DANGEROUS_FLAG: &str = "--dangerously-bypass-approvals-and-sandbox";
+use crate::llm::{LlmBackend, LlmOutput, LlmRequest};
+
+static NEXT_PANE_SCRIPT: AtomicU64 = AtomicU64::new(0);
+// Printed by the pane script once the exit code file is written.
+const LLM_PANE_DONE: &str = r"\[orc-llm\] exit=\d+";
 
 fn append_chat_log(project_root: &Path, role: &str, message: &str) {
     let debug_enabled = crate::load_app_config()
@@ -38,66 +43,13 @@ fn codex_exec_timeout_sec() -> u64 {
         .max(1)
 }
 
-fn run_command_with_timeout(
-    mut command: Command,
-    timeout_sec: u64,
-    timeout_label: &str,
-) -> Result<Output, String> {
-    command.stdout(Stdio::piped()).stderr(Stdio::piped());
-    let mut child = command
-        .spawn()
-        .map_err(|e| format!("failed to spawn {}: {}", timeout_label, e))?;
-    let started = Instant::now();
-    loop {
-        match child
-            .try_wait()
-            .map_err(|e| format!("failed while waiting {}: {}", timeout_label, e))?
-        {
-            Some(_) => {
-                return child
-                    .wait_with_output()
-                    .map_err(|e| format!("failed to collect output for {}: {}", timeout_label, e));
-            }
-            None => {
-                if started.elapsed() >= Duration::from_secs(timeout_sec) {
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    return Err(format!(
-                        "{} timed out after {}s",
-                        timeout_label, timeout_sec
-                    ));
-                }
-                thread::sleep(Duration::from_millis(200));
-            }
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-struct LlmExecResult {
-    success: bool,
-    stdout: String,
-    stderr: String,
-}
-
-fn should_use_tmux_for_llm() -> bool {
-    if !env_flag_true("ORC_USE_TMUX_PANES") {
+fn should_use_llm_pane() -> bool {
+    if !crate::terminal::worker_panes_enabled() {
         return false;
     }
-    let debug_enabled = crate::load_app_config()
+    crate::load_app_config()
         .as_ref()
-        .is_none_or(crate::config::AppConfig::debug_enabled);
-    debug_enabled && env::var("TMUX").map(|v| !v.trim().is_empty()).unwrap_or(false)
-}
-
-fn env_flag_true(name: &str) -> bool {
-    match env::var(name) {
-        Ok(v) => {
-            let lowered = v.trim().to_ascii_lowercase();
-            lowered == "1" || lowered == "true" || lowered == "yes" || lowered == "on"
-        }
-        Err(_) => false,
-    }
+        .is_none_or(crate::config::AppConfig::debug_enabled)
 }
 
 fn llm_retry_count() -> u32 {
@@ -111,20 +63,51 @@ fn quote_sh(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\"'\"'"))
 }
 
-fn run_llm_via_tmux(
+pub(crate) struct LlmPaneScript {
+    pub(crate) command: String,
+    stdout_path: PathBuf,
+    stderr_path: PathBuf,
+    code_path: PathBuf,
+}
+
+impl LlmPaneScript {
+    pub(crate) fn finished(&self) -> bool {
+        self.code_path.exists()
+    }
+
+    pub(crate) fn output(&self) -> Result<LlmOutput, String> {
+        let code_raw = fs::read_to_string(&self.code_path)
+            .map_err(|e| format!("failed to read {}: {}", self.code_path.display(), e))?;
+        let code = code_raw.trim().parse::<i32>().unwrap_or(1);
+        let stdout = fs::read_to_string(&self.stdout_path).unwrap_or_default();
+        let stderr = fs::read_to_string(&self.stderr_path).unwrap_or_default();
+        Ok(LlmOutput {
+            success: code == 0,
+            stdout,
+            stderr: stderr.trim().to_string(),
+            code: Some(code),
+        })
+    }
+}
+
+// Wraps an LLM command in a script that tees its output to logs and records
+// the exit code, so it can run in a terminal pane while we wait on the files.
+pub(crate) fn write_llm_pane_script(
     dir: &Path,
-    llm_bin: &str,
+    command: &Command,
     prompt: &str,
-    timeout_sec: u64,
-    add_yes_flag: bool,
-    add_dangerous_flag: bool,
-    timeout_label: &str,
-) -> Result<LlmExecResult, String> {
+    label: &str,
+) -> Result<LlmPaneScript, String> {
     let runtime = dir.join(".project").join("runtime");
     fs::create_dir_all(&runtime)
         .map_err(|e| format!("failed to create runtime dir {}: {}", runtime.display(), e))?;
     let stamp = crate::now_unix();
-    let token = format!("{}_{}", stamp, std::process::id());
+    let token = format!(
+        "{}_{}_{}",
+        stamp,
+        std::process::id(),
+        NEXT_PANE_SCRIPT.fetch_add(1, Ordering::Relaxed)
+    );
     let prompt_path = runtime.join(format!("tmux-llm-{}.prompt.txt", token));
     let script_path = runtime.join(format!("tmux-llm-{}.sh", token));
     let stdout_path = runtime.join(format!("tmux-llm-{}.stdout.log", token));
@@ -133,132 +116,165 @@ fn run_llm_via_tmux(
     fs::write(&prompt_path, prompt)
         .map_err(|e| format!("failed to write {}: {}", prompt_path.display(), e))?;
 
-    let mut flags = Vec::new();
-    if add_yes_flag {
-        flags.push("-y".to_string());
-    }
-    if add_dangerous_flag {
-        flags.push(CODEX_DANGEROUS_FLAG.to_string());
-    }
-    let flags_joined = if flags.is_empty() {
-        String::new()
-    } else {
-        format!(" {}", flags.join(" "))
-    };
+    let program = command.get_program().to_string_lossy().to_string();
+    let args = command
+        .get_args()
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            if arg == prompt {
+                format!("\"$(cat {})\"", quote_sh(&prompt_path.display().to_string()))
+            } else {
+                quote_sh(&arg)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
     let script = format!(
         "#!/usr/bin/env bash\n\
 cd {dir}\n\
-echo \"[orc-llm] start: {llm} exec\"\n\
+echo \"[orc-llm] start: {label}\"\n\
 echo \"[orc-llm] cwd: {dir_display}\"\n\
-{llm} exec{flags} \"$(cat {prompt})\" > >(tee {stdout}) 2> >(tee {stderr} >&2)\n\
+{llm} {args} > >(tee {stdout}) 2> >(tee {stderr} >&2)\n\
 status=$?\n\
-printf \"%s\" \"$status\" > {code}\n",
+wait\n\
+printf \"%s\" \"$status\" > {code}\n\
+echo \"[orc-llm] exit=$status\"\n",
         dir = quote_sh(&dir.display().to_string()),
         dir_display = dir.display(),
-        llm = quote_sh(llm_bin),
-        flags = flags_joined,
-        prompt = quote_sh(&prompt_path.display().to_string()),
+        label = label,
+        llm = quote_sh(&program),
+        args = args,
         stdout = quote_sh(&stdout_path.display().to_string()),
         stderr = quote_sh(&stderr_path.display().to_string()),
         code = quote_sh(&code_path.display().to_string()),
     );
     fs::write(&script_path, script)
         .map_err(|e| format!("failed to write {}: {}", script_path.display(), e))?;
+    Ok(LlmPaneScript {
+        command: format!("bash {}", quote_sh(&script_path.display().to_string())),
+        stdout_path,
+        stderr_path,
+        code_path,
+    })
+}
 
-    let script_cmd = format!("bash {}", quote_sh(&script_path.display().to_string()));
-    let pane_id = crate::tmux::split_window_run(&script_cmd)
-        .map_err(|e| format!("{} (tmux split/run failed: {})", timeout_label, e))?;
-    let _ = crate::tmux::rename_pane(&pane_id, "llm-debug");
+fn run_llm_in_pane(
+    dir: &Path,
+    command: &Command,
+    prompt: &str,
+    timeout_sec: u64,
+    timeout_label: &str,
+) -> Result<LlmOutput, String> {
+    let script = write_llm_pane_script(dir, command, prompt, timeout_label)?;
+    let terminal = crate::terminal::backend();
+    let pane = terminal.open("llm-debug", &script.command).map_err(|e| {
+        format!(
+            "{} ({} pane failed: {})",
+            timeout_label,
+            terminal.name(),
+            e
+        )
+    })?;
 
-    let started = Instant::now();
-    while !code_path.exists() {
-        if started.elapsed() >= Duration::from_secs(timeout_sec) {
-            let _ = crate::tmux::kill_pane(&pane_id);
-            return Err(format!("{} timed out after {}s", timeout_label, timeout_sec));
+    let timeout = Duration::from_secs(timeout_sec);
+    let done = Regex::new(LLM_PANE_DONE).map_err(|e| e.to_string())?;
+    let finished = match terminal.wait_for(&pane, &done, timeout) {
+        // A pane closed by its exit still leaves the code file behind.
+        Some(waited) => waited.is_ok() || script.finished(),
+        None => {
+            let started = Instant::now();
+            while !script.finished() && started.elapsed() < timeout {
+                thread::sleep(Duration::from_millis(200));
+            }
+            script.finished()
         }
-        thread::sleep(Duration::from_millis(200));
+    };
+    if !finished {
+        terminal.kill(&pane);
+        return Err(format!("{} timed out after {}s", timeout_label, timeout_sec));
     }
-    let _ = crate::tmux::kill_pane(&pane_id);
+    terminal.close(&pane);
+    script.output()
+}
 
-    let code_raw = fs::read_to_string(&code_path)
-        .map_err(|e| format!("failed to read {}: {}", code_path.display(), e))?;
-    let code = code_raw.trim().parse::<i32>().unwrap_or(1);
-    let stdout = fs::read_to_string(&stdout_path).unwrap_or_default();
-    let stderr = fs::read_to_string(&stderr_path).unwrap_or_default();
-    Ok(LlmExecResult {
-        success: code == 0,
-        stdout,
-        stderr: stderr.trim().to_string(),
-    })
+fn exec_backend_once(
+    backend: &dyn LlmBackend,
+    log_dir: &Path,
+    request: &LlmRequest<'_>,
+) -> Result<LlmOutput, String> {
+    if should_use_llm_pane() {
+        if let Some(command) = backend.command(request) {
+            return run_llm_in_pane(
+                log_dir,
+                &command,
+                request.prompt,
+                request.timeout_sec,
+                &backend.label(),
+            );
+        }
+    }
+    backend.exec(request)
 }
 
-pub(crate) fn run_codex_exec_capture_with_timeout(
-    prompt: &str,
-    timeout_sec: u64,
+fn run_backend_capture(
+    backend: &dyn LlmBackend,
+    log_dir: &Path,
+    request: &LlmRequest<'_>,
 ) -> Result<String, String> {
-    let cwd = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    append_chat_log(&cwd, "LLM_PROMPT", prompt);
-    let model_bin = crate::default_model_bin();
-    let dangerous = crate::model_supports_dangerous_flag(&model_bin);
+    append_chat_log(log_dir, "LLM_PROMPT", request.prompt);
     let total_attempts = llm_retry_count();
     let mut last_error = "unknown llm error".to_string();
     for attempt in 1..=total_attempts {
-        if should_use_tmux_for_llm() {
-            match run_llm_via_tmux(
-                &cwd,
-                &model_bin,
-                prompt,
-                timeout_sec,
-                false,
-                dangerous,
-                &format!("{} exec", model_bin),
-            ) {
-                Ok(result) => {
-                    if result.success {
-                        append_chat_log(&cwd, "LLM_RESPONSE", &result.stdout);
-                        return Ok(result.stdout);
+        match exec_backend_once(backend, log_dir, request) {
+            Ok(result) if result.success => {
+                append_chat_log(log_dir, "LLM_RESPONSE", &result.stdout);
+                return Ok(result.stdout);
+            }
+            Ok(result) if request.auto_yes && result.stderr.contains("unexpected argument '-y'") => {
+                let retry_request = request.clone().with_auto_yes(false);
+                match exec_backend_once(backend, log_dir, &retry_request) {
+                    Ok(retry) if retry.success => {
+                        append_chat_log(log_dir, "LLM_RESPONSE", &retry.stdout);
+                        return Ok(retry.stdout);
+                    }
+                    Ok(retry) => {
+                        last_error = retry.stderr;
+                    }
+                    Err(e) => {
+                        last_error = e;
                     }
-                    last_error = result.stderr;
-                }
-                Err(e) => {
-                    last_error = e;
                 }
             }
-        } else {
-            let mut command = Command::new(&model_bin);
-            command.arg("exec");
-            if dangerous {
-                command.arg(CODEX_DANGEROUS_FLAG);
+            Ok(result) => {
+                last_error = result.stderr;
             }
-            command.arg(prompt);
-            match run_command_with_timeout(
-                command,
-                timeout_sec,
-                &format!("{} exec", model_bin),
-            ) {
-                Ok(output) if output.status.success() => {
-                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                    append_chat_log(&cwd, "LLM_RESPONSE", &stdout);
-                    return Ok(stdout);
-                }
-                Ok(output) => {
-                    last_error = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                }
-                Err(e) => {
-                    last_error = e;
-                }
+            Err(e) => {
+                last_error = e;
             }
         }
         append_chat_log(
-            &cwd,
+            log_dir,
             "LLM_RETRY",
             &format!("attempt {}/{} failed: {}", attempt, total_attempts, last_error),
         );
     }
-    append_chat_log(&cwd, "LLM_ERROR", &last_error);
+    append_chat_log(log_dir, "LLM_ERROR", &last_error);
     Err(last_error)
 }
 
+pub(crate) fn run_codex_exec_capture_with_timeout(
+    prompt: &str,
+    timeout_sec: u64,
+) -> Result<String, String> {
+    let cwd = crate::context::project_root();
+    let backend = crate::llm::resolve_backend();
+    run_backend_capture(
+        backend.as_ref(),
+        &cwd,
+        &LlmRequest::new(prompt, timeout_sec).in_dir(&cwd),
+    )
+}
+
 pub(crate) fn run_codex_exec_capture(prompt: &str) -> Result<String, String> {
     run_codex_exec_capture_with_timeout(prompt, codex_exec_timeout_sec())
 }
@@ -272,178 +288,55 @@ pub(crate) fn run_codex_exec_capture_in_dir_with_timeout(
     prompt: &str,
     timeout_sec: u64,
 ) -> Result<String, String> {
-    append_chat_log(dir, "LLM_PROMPT", prompt);
-    let model_bin = crate::default_model_bin();
-    let dangerous = crate::model_supports_dangerous_flag(&model_bin);
-    let total_attempts = llm_retry_count();
-    let mut last_error = "unknown llm error".to_string();
-    for attempt in 1..=total_attempts {
-        if should_use_tmux_for_llm() {
-            match run_llm_via_tmux(
-                dir,
-                &model_bin,
-                prompt,
-                timeout_sec,
-                false,
-                dangerous,
-                &format!("{} exec in {}", model_bin, dir.display()),
-            ) {
-                Ok(result) => {
-                    if result.success {
-                        append_chat_log(dir, "LLM_RESPONSE", &result.stdout);
-                        return Ok(result.stdout);
-                    }
-                    last_error = result.stderr;
-                }
-                Err(e) => {
-                    last_error = e;
-                }
-            }
-        } else {
-            let mut command = Command::new(&model_bin);
-            command.current_dir(dir).arg("exec");
-            if dangerous {
-                command.arg(CODEX_DANGEROUS_FLAG);
-            }
-            command.arg(prompt);
-            match run_command_with_timeout(
-                command,
-                timeout_sec,
-                &format!("{} exec in {}", model_bin, dir.display()),
-            ) {
-                Ok(output) if output.status.success() => {
-                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                    append_chat_log(dir, "LLM_RESPONSE", &stdout);
-                    return Ok(stdout);
-                }
-                Ok(output) => {
-                    last_error = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                }
-                Err(e) => {
-                    last_error = e;
-                }
-            }
-        }
-        append_chat_log(
-            dir,
-            "LLM_RETRY",
-            &format!("attempt {}/{} failed: {}", attempt, total_attempts, last_error),
-        );
-    }
-    append_chat_log(dir, "LLM_ERROR", &last_error);
-    Err(last_error)
+    let backend = crate::llm::resolve_backend();
+    run_backend_capture(
+        backend.as_ref(),
+        dir,
+        &LlmRequest::new(prompt, timeout_sec).in_dir(dir),
+    )
 }
 
 pub(crate) fn run_llm_exec_capture(llm: &str, prompt: &str) -> Result<String, String> {
-    let cwd = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    append_chat_log(&cwd, "LLM_PROMPT", prompt);
+    let cwd = crate::context::project_root();
     let timeout_sec = codex_exec_timeout_sec().max(30);
-    let use_dangerous = crate::model_supports_dangerous_flag(llm);
-    let total_attempts = llm_retry_count();
-    let mut last_error = "unknown llm error".to_string();
-    for attempt in 1..=total_attempts {
-        if should_use_tmux_for_llm() {
-            match run_llm_via_tmux(
-                &cwd,
-                llm,
-                prompt,
-                timeout_sec,
-                true,
-                use_dangerous,
-                &format!("{} exec -y", llm),
-            ) {
-                Ok(result) if result.success => {
-                    append_chat_log(&cwd, "LLM_RESPONSE", &result.stdout);
-                    return Ok(result.stdout);
-                }
-                Ok(result) if result.stderr.contains("unexpected argument '-y'") => {
-                    match run_llm_via_tmux(
-                        &cwd,
-                        llm,
-                        prompt,
-                        timeout_sec,
-                        false,
-                        use_dangerous,
-                        &format!("{} exec", llm),
-                    ) {
-                        Ok(retry) if retry.success => {
-                            append_chat_log(&cwd, "LLM_RESPONSE", &retry.stdout);
-                            return Ok(retry.stdout);
-                        }
-                        Ok(retry) => {
-                            last_error = retry.stderr;
-                        }
-                        Err(e) => {
-                            last_error = e;
-                        }
-                    }
-                }
-                Ok(result) => {
-                    last_error = result.stderr;
-                }
-                Err(e) => {
-                    last_error = e;
-                }
-            }
-        } else {
-            let mut command = Command::new(llm);
-            command.arg("exec").arg("-y");
-            if use_dangerous {
-                command.arg(CODEX_DANGEROUS_FLAG);
-            }
-            command.arg(prompt);
-            match run_command_with_timeout(
-                command,
-                timeout_sec,
-                &format!("{} exec -y", llm),
-            ) {
-                Ok(output) if output.status.success() => {
-                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                    append_chat_log(&cwd, "LLM_RESPONSE", &stdout);
-                    return Ok(stdout);
-                }
-                Ok(output) => {
-                    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-                    if stderr.contains("unexpected argument '-y'") {
-                        let mut retry_command = Command::new(llm);
-                        retry_command.arg("exec");
-                        if use_dangerous {
-                            retry_command.arg(CODEX_DANGEROUS_FLAG);
-                        }
-                        retry_command.arg(prompt);
-                        match run_command_with_timeout(
-                            retry_command,
-                            timeout_sec,
-                            &format!("{} exec", llm),
-                        ) {
-                            Ok(retry) if retry.status.success() => {
-                                let stdout = String::from_utf8_lossy(&retry.stdout).to_string();
-                                append_chat_log(&cwd, "LLM_RESPONSE", &stdout);
-                                return Ok(stdout);
-                            }
-                            Ok(retry) => {
-                                last_error =
-                                    String::from_utf8_lossy(&retry.stderr).trim().to_string();
-                            }
-                            Err(e) => {
-                                last_error = e;
-                            }
-                        }
-                    } else {
-                        last_error = stderr;
-                    }
-                }
-                Err(e) => {
-                    last_error = e;
-                }
-            }
-        }
-        append_chat_log(
-            &cwd,
-            "LLM_RETRY",
-            &format!("attempt {}/{} failed: {}", attempt, total_attempts, last_error),
-        );
+    let backend = crate::llm::backend_for_model(llm);
+    run_backend_capture(
+        backend.as_ref(),
+        &cwd,
+        &LlmRequest::new(prompt, timeout_sec)
+            .in_dir(&cwd)
+            .with_auto_yes(true),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::{self, RecordingTerminal, TerminalEvent};
+    use std::sync::Arc;
+
+    #[test]
+    fn llm_pane_collects_output_through_the_terminal() {
+        let dir = std::env::temp_dir().join(format!("orc-llm-pane-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create dir");
+        let recorder = Arc::new(RecordingTerminal::running());
+        let _guard = terminal::override_backend(recorder.clone());
+        let mut command = Command::new("echo");
+        command.arg("it's a prompt");
+
+        let output =
+            run_llm_in_pane(&dir, &command, "it's a prompt", 30, "echo exec").expect("run");
+        assert!(output.success);
+        assert_eq!(output.code, Some(0));
+        assert_eq!(output.stdout.trim(), "it's a prompt");
+
+        let events = recorder.events();
+        assert!(matches!(
+            &events[0],
+            TerminalEvent::Open { title, .. } if title == "llm-debug"
+        ));
+        assert_eq!(events[1..], [TerminalEvent::Close("rec:0".to_string())]);
+        let _ = fs::remove_dir_all(&dir);
     }
-    append_chat_log(&cwd, "LLM_ERROR", &last_error);
-    Err(last_error)
 }
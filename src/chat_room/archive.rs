@@ -0,0 +1,290 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::ChatMessage;
+
+// Upper bound on how many expired messages may pile up in the live log before
+// an append moves them out; keeps segment count proportional to traffic.
+const ARCHIVE_BATCH: usize = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub(crate) struct Retention {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_messages: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_age_sec: Option<u64>,
+}
+
+impl Retention {
+    pub(crate) fn is_unset(&self) -> bool {
+        self.max_messages.is_none() && self.max_age_sec.is_none()
+    }
+
+    fn min_batch(&self) -> usize {
+        self.max_messages
+            .map_or(ARCHIVE_BATCH, |max| (max / 10).clamp(1, ARCHIVE_BATCH))
+    }
+
+    // Length of the log prefix that falls outside the retention window.
+    fn expired_prefix(&self, messages: &[ChatMessage], now: u64) -> usize {
+        let by_count = self
+            .max_messages
+            .map_or(0, |max| messages.len().saturating_sub(max));
+        let by_age = self.max_age_sec.map_or(0, |max_age| {
+            let cutoff = now.saturating_sub(max_age);
+            messages
+                .iter()
+                .take_while(|m| message_time(m).is_some_and(|t| t < cutoff))
+                .count()
+        });
+        by_count.max(by_age)
+    }
+}
+
+// `created_at` is `<unix seconds>Z`.
+pub(crate) fn message_time(message: &ChatMessage) -> Option<u64> {
+    message.created_at.trim().trim_end_matches('Z').parse().ok()
+}
+
+pub(crate) fn archive_dir(name: &str) -> PathBuf {
+    super::temp_dir().join(format!("{}.archive", name))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Segment {
+    pub(crate) path: PathBuf,
+    pub(crate) seq: u64,
+    pub(crate) from: u64,
+    pub(crate) to: u64,
+}
+
+// Segment files are `<seq>-<from>-<to>.jsonl.gz` so time-range queries can
+// skip segments without decompressing them.
+fn parse_segment(path: &Path) -> Option<Segment> {
+    let stem = path.file_name()?.to_str()?.strip_suffix(".jsonl.gz")?;
+    let mut parts = stem.split('-');
+    let segment = Segment {
+        path: path.to_path_buf(),
+        seq: parts.next()?.parse().ok()?,
+        from: parts.next()?.parse().ok()?,
+        to: parts.next()?.parse().ok()?,
+    };
+    parts.next().is_none().then_some(segment)
+}
+
+pub(crate) fn segments(name: &str) -> Vec<Segment> {
+    let Ok(entries) = fs::read_dir(archive_dir(name)) else {
+        return Vec::new();
+    };
+    let mut out: Vec<Segment> = entries
+        .flatten()
+        .filter_map(|entry| parse_segment(&entry.path()))
+        .collect();
+    out.sort_by_key(|s| s.seq);
+    out
+}
+
+pub(crate) fn read_segment(segment: &Segment) -> Result<Vec<ChatMessage>, String> {
+    let file = fs::File::open(&segment.path)
+        .map_err(|e| format!("failed to open {}: {}", segment.path.display(), e))?;
+    let mut raw = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut raw)
+        .map_err(|e| format!("failed to read {}: {}", segment.path.display(), e))?;
+    Ok(super::parse_lines(&raw))
+}
+
+fn write_segment(name: &str, messages: &[ChatMessage]) -> Result<Segment, String> {
+    let dir = archive_dir(name);
+    fs::create_dir_all(&dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let seq = segments(name).last().map_or(1, |s| s.seq + 1);
+    let times: Vec<u64> = messages.iter().filter_map(message_time).collect();
+    let from = times.iter().copied().min().unwrap_or(0);
+    let to = times.iter().copied().max().unwrap_or(0);
+    let path = dir.join(format!("{:06}-{}-{}.jsonl.gz", seq, from, to));
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for message in messages {
+        encoder
+            .write_all(super::encode_line(message)?.as_bytes())
+            .map_err(|e| format!("archive encode error: {}", e))?;
+    }
+    let raw = encoder
+        .finish()
+        .map_err(|e| format!("archive encode error: {}", e))?;
+    super::write_atomic(&path, &raw)?;
+    Ok(Segment {
+        path,
+        seq,
+        from,
+        to,
+    })
+}
+
+// Moves messages outside the retention window into a new segment. Without
+// `force`, waits until a batch has built up.
+pub(crate) fn enforce(
+    name: &str,
+    retention: &Retention,
+    now: u64,
+    force: bool,
+) -> Result<usize, String> {
+    if retention.is_unset() {
+        return Ok(0);
+    }
+    let _guard = super::acquire_lock(name)?;
+    let mut archived = 0usize;
+    super::rewrite_log(name, |raw| {
+        let messages = super::parse_lines(raw);
+        let expired = retention.expired_prefix(&messages, now);
+        if expired == 0 || (!force && expired < retention.min_batch()) {
+            return Ok(None);
+        }
+        write_segment(name, &messages[..expired])?;
+        archived = expired;
+        let mut out = String::new();
+        for message in &messages[expired..] {
+            out.push_str(&super::encode_line(message)?);
+        }
+        Ok(Some(out))
+    })?;
+    Ok(archived)
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HistoryFilter {
+    pub(crate) sender: Option<String>,
+    pub(crate) receiver: Option<String>,
+    pub(crate) command: Option<String>,
+    pub(crate) since: Option<u64>,
+    pub(crate) until: Option<u64>,
+}
+
+impl HistoryFilter {
+    fn overlaps(&self, segment: &Segment) -> bool {
+        // Segments whose messages had no timestamps carry 0 and always match.
+        if segment.to == 0 {
+            return true;
+        }
+        self.since.is_none_or(|since| segment.to >= since)
+            && self.until.is_none_or(|until| segment.from <= until)
+    }
+
+    pub(crate) fn matches(&self, message: &ChatMessage) -> bool {
+        let time = message_time(message);
+        self.sender.as_ref().is_none_or(|s| &message.sender_id == s)
+            && self
+                .receiver
+                .as_ref()
+                .is_none_or(|r| message.receiver.as_ref() == Some(r))
+            && self
+                .command
+                .as_deref()
+                .is_none_or(|c| message.command.split_whitespace().next() == Some(c))
+            && self
+                .since
+                .is_none_or(|since| time.is_some_and(|t| t >= since))
+            && self
+                .until
+                .is_none_or(|until| time.is_some_and(|t| t <= until))
+    }
+}
+
+// Archived segments first, then the live log, in append order.
+pub(crate) fn history(name: &str, filter: &HistoryFilter) -> Result<Vec<ChatMessage>, String> {
+    let mut out = Vec::new();
+    for segment in segments(name) {
+        if filter.overlaps(&segment) {
+            out.extend(
+                read_segment(&segment)?
+                    .into_iter()
+                    .filter(|m| filter.matches(m)),
+            );
+        }
+    }
+    out.extend(
+        super::read_all(name)?
+            .into_iter()
+            .filter(|m| filter.matches(m)),
+    );
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, sender: &str, at: u64) -> ChatMessage {
+        ChatMessage {
+            message_id: id.to_string(),
+            command: format!("say {}", id),
+            sender_id: sender.to_string(),
+            created_at: format!("{}Z", at),
+            ..ChatMessage::default()
+        }
+    }
+
+    #[test]
+    fn retention_archives_in_batches_and_history_spans_segments() {
+        let name = format!("test-archive-{}", std::process::id());
+        for i in 0..12u64 {
+            let sender = if i % 2 == 0 { "even" } else { "odd" };
+            super::super::append(&name, &message(&format!("m{}", i), sender, 1000 + i))
+                .expect("append");
+        }
+        let mut cursor = super::super::ChatCursor::from_start(&name);
+        assert_eq!(cursor.read_new().expect("initial").len(), 12);
+        let by_count = Retention {
+            max_messages: Some(10),
+            max_age_sec: None,
+        };
+        // 2 over the limit, batch is 1: archived on the next enforce.
+        assert_eq!(enforce(&name, &by_count, 2000, false).expect("enforce"), 2);
+        assert_eq!(super::super::read_all(&name).expect("live").len(), 10);
+        super::super::append(&name, &message("m12", "odd", 1012)).expect("append");
+        let fresh = cursor.read_new().expect("after rewrite");
+        assert_eq!(fresh, vec![message("m12", "odd", 1012)]);
+
+        let by_age = Retention {
+            max_messages: None,
+            max_age_sec: Some(100),
+        };
+        // Cutoff 1005: m2..m4 expired, below the default batch unless forced.
+        assert_eq!(enforce(&name, &by_age, 1105, false).expect("lazy"), 0);
+        assert_eq!(enforce(&name, &by_age, 1105, true).expect("forced"), 3);
+
+        let segs = segments(&name);
+        assert_eq!(segs.len(), 2);
+        assert_eq!((segs[0].seq, segs[0].from, segs[0].to), (1, 1000, 1001));
+        assert_eq!((segs[1].from, segs[1].to), (1002, 1004));
+
+        let all = history(&name, &HistoryFilter::default()).expect("history");
+        let ids: Vec<String> = all.iter().map(|m| m.message_id.clone()).collect();
+        let expected: Vec<String> = (0..13).map(|i| format!("m{}", i)).collect();
+        assert_eq!(ids, expected);
+
+        let filter = HistoryFilter {
+            sender: Some("even".to_string()),
+            command: Some("say".to_string()),
+            since: Some(1002),
+            until: Some(1006),
+            ..HistoryFilter::default()
+        };
+        assert!(!filter.overlaps(&segs[0]));
+        let ids: Vec<String> = history(&name, &filter)
+            .expect("filtered")
+            .into_iter()
+            .map(|m| m.message_id)
+            .collect();
+        assert_eq!(ids, vec!["m2", "m4", "m6"]);
+
+        let _ = fs::remove_dir_all(archive_dir(&name));
+        let _ = fs::remove_file(super::super::log_path(&name));
+        let _ = fs::remove_file(super::super::lock_path(&name));
+    }
+}
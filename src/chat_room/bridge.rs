@@ -0,0 +1,237 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{protocol, ChatCursor, ChatMessage, Doorbell};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone)]
+pub(crate) struct BridgeOptions {
+    // Pane output is considered finished once unchanged for this long.
+    pub(crate) settle: Duration,
+    pub(crate) timeout: Duration,
+    // Completion marker (e.g. the pane's prompt); ends the wait as soon as
+    // new output matches, without waiting to settle.
+    pub(crate) until: Option<Regex>,
+}
+
+impl Default for BridgeOptions {
+    fn default() -> Self {
+        Self {
+            settle: Duration::from_secs(3),
+            timeout: Duration::from_secs(120),
+            until: None,
+        }
+    }
+}
+
+// Only addressed messages are typed into panes; a pane never receives its own.
+pub(crate) fn target_pane<'a>(
+    message: &ChatMessage,
+    panes: &'a HashMap<String, String>,
+) -> Option<&'a String> {
+    let receiver = message.receiver.as_deref()?;
+    if receiver == message.sender_id {
+        return None;
+    }
+    panes.get(receiver)
+}
+
+pub(crate) fn render(room: &str, message: &ChatMessage) -> String {
+    let mut text = format!(
+        "[chat:{} from {}] {}",
+        room, message.sender_id, message.command
+    );
+    if let Some(data) = message.data.as_deref().filter(|d| !d.trim().is_empty()) {
+        text.push('\n');
+        text.push_str(data);
+    }
+    text
+}
+
+// The pane echoes what was typed, usually after its prompt; drop those
+// leading lines so neither `until` nor the reply sees our own message.
+fn strip_echo(output: &str, sent: &str) -> String {
+    let mut sent_lines = sent
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .peekable();
+    let mut lines = output.lines().peekable();
+    while let (Some(line), Some(expected)) = (lines.peek(), sent_lines.peek()) {
+        if !line.trim().is_empty() && !line.trim_end().ends_with(expected) {
+            break;
+        }
+        if !line.trim().is_empty() {
+            sent_lines.next();
+        }
+        lines.next();
+    }
+    lines
+        .skip_while(|line| line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn wait_for_output(
+    pane: &str,
+    from: i64,
+    sent: &str,
+    options: &BridgeOptions,
+) -> Result<String, String> {
+    let started = Instant::now();
+    let mut last = String::new();
+    let mut changed_at = Instant::now();
+    while started.elapsed() < options.timeout {
+        thread::sleep(POLL_INTERVAL);
+        let current = strip_echo(&crate::tmux::capture_from(pane, from)?, sent);
+        if let Some(until) = &options.until {
+            if crate::tmux::wait::find_match(&current, until).is_some() {
+                return Ok(current);
+            }
+        }
+        if current != last {
+            last = current;
+            changed_at = Instant::now();
+        } else if !last.trim().is_empty() && changed_at.elapsed() >= options.settle {
+            break;
+        }
+    }
+    Ok(last)
+}
+
+fn deliver(
+    room: &str,
+    pane: &str,
+    message: &ChatMessage,
+    options: &BridgeOptions,
+) -> Result<(), String> {
+    if !crate::tmux::pane_exists(pane) {
+        return Err(format!("pane {} is gone", pane));
+    }
+    let text = render(room, message);
+    let from = crate::tmux::pane_line(pane)?;
+    crate::tmux::send::send_text(
+        pane,
+        &text,
+        crate::tmux::send::SendMode::Auto,
+        crate::tmux::SendOption::Enter,
+    )?;
+    // Replies are delivered but not answered, so two bridged panes cannot
+    // keep replying to each other.
+    if message.command == "reply" {
+        return Ok(());
+    }
+    let output = wait_for_output(pane, from, &text, options)?;
+    let result = if output.trim().is_empty() {
+        Err(format!(
+            "no output from pane {} within {}s",
+            pane,
+            options.timeout.as_secs()
+        ))
+    } else {
+        Ok(output)
+    };
+    let receiver = message.receiver.as_deref().unwrap_or_default();
+    let mut reply = protocol::reply_to(message, receiver, result);
+    reply.message_id = crate::generate_chat_id_8();
+    reply.created_at = crate::now_chat_timestamp();
+    super::append(room, &reply)
+}
+
+// One worker per pane keeps deliveries to a pane in order while a slow pane
+// does not hold up the others.
+fn spawn_worker(room: &str, pane: &str, options: BridgeOptions) -> mpsc::Sender<ChatMessage> {
+    let (tx, rx) = mpsc::channel::<ChatMessage>();
+    let room = room.to_string();
+    let pane = pane.to_string();
+    thread::spawn(move || {
+        for message in rx {
+            match deliver(&room, &pane, &message, &options) {
+                Ok(()) => println!(
+                    "[bridge] {} -> pane {} ({})",
+                    message.message_id, pane, message.command
+                ),
+                Err(e) => eprintln!("[bridge] {} -> pane {}: {}", message.message_id, pane, e),
+            }
+        }
+    });
+    tx
+}
+
+// `panes` maps sender ids to tmux pane ids; it is re-read on every wakeup so
+// panes joining later are picked up.
+pub(crate) fn run(
+    room: &str,
+    panes: impl Fn() -> Result<HashMap<String, String>, String>,
+    options: BridgeOptions,
+    wake_interval: Duration,
+) -> Result<(), String> {
+    let bell = Doorbell::bind(room);
+    let mut cursor = ChatCursor::at_end(room);
+    let mut workers: HashMap<String, mpsc::Sender<ChatMessage>> = HashMap::new();
+    loop {
+        let messages = cursor.read_new()?;
+        if !messages.is_empty() {
+            let panes = panes()?;
+            for message in messages {
+                let Some(pane) = target_pane(&message, &panes) else {
+                    continue;
+                };
+                let worker = workers
+                    .entry(pane.clone())
+                    .or_insert_with(|| spawn_worker(room, pane, options.clone()));
+                if let Err(mpsc::SendError(message)) = worker.send(message) {
+                    let worker = spawn_worker(room, pane, options.clone());
+                    let _ = worker.send(message);
+                    workers.insert(pane.clone(), worker);
+                }
+            }
+        }
+        bell.wait(wake_interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_addressed_messages_reach_known_panes() {
+        let panes = HashMap::from([
+            ("a1".to_string(), "%1".to_string()),
+            ("b2".to_string(), "%2".to_string()),
+        ]);
+        let message = |sender: &str, receiver: Option<&str>| ChatMessage {
+            message_id: "m".to_string(),
+            command: "review login".to_string(),
+            data: Some("line one\nline two".to_string()),
+            receiver: receiver.map(str::to_string),
+            sender_id: sender.to_string(),
+            created_at: String::new(),
+        };
+        assert_eq!(
+            target_pane(&message("a1", Some("b2")), &panes).map(String::as_str),
+            Some("%2")
+        );
+        assert_eq!(target_pane(&message("a1", None), &panes), None);
+        assert_eq!(target_pane(&message("a1", Some("a1")), &panes), None);
+        assert_eq!(target_pane(&message("a1", Some("zz")), &panes), None);
+        assert_eq!(
+            render("room", &message("a1", Some("b2"))),
+            "[chat:room from a1] review login\nline one\nline two"
+        );
+    }
+
+    #[test]
+    fn typed_message_echo_is_not_part_of_the_reply() {
+        let sent = "[chat:room from a1] review login\nline one";
+        let captured = "$ [chat:room from a1] review login\n> line one\n\nlooks good\n$ ";
+        assert_eq!(strip_echo(captured, sent), "looks good\n$ ");
+        assert_eq!(strip_echo("looks good", sent), "looks good");
+        assert_eq!(strip_echo("$ [chat:room from a1] review login\n", sent), "");
+    }
+}
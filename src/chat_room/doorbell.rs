@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+static NEXT_BELL: AtomicU64 = AtomicU64::new(0);
+
+// Subscribers bind a datagram socket under a per-room directory; senders ring
+// every socket there after appending. Socket paths are capped at ~108 bytes,
+// so the directory lives in the system temp dir keyed by a hash of the room log.
+fn bell_dir(name: &str) -> PathBuf {
+    let key = super::log_path(name).display().to_string();
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in key.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    std::env::temp_dir().join(format!("orc-chat-{:016x}", hash))
+}
+
+pub(crate) struct Doorbell {
+    #[cfg(unix)]
+    socket: Option<std::os::unix::net::UnixDatagram>,
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl Doorbell {
+    pub(crate) fn bind(name: &str) -> Self {
+        use std::os::unix::net::UnixDatagram;
+        let dir = bell_dir(name);
+        let path = dir.join(format!(
+            "{}-{}.sock",
+            std::process::id(),
+            NEXT_BELL.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::create_dir_all(&dir);
+        let _ = std::fs::remove_file(&path);
+        let socket = UnixDatagram::bind(&path).ok();
+        Self { socket, path }
+    }
+
+    // Returns true when rung; false on timeout, which callers treat as a poll tick.
+    pub(crate) fn wait(&self, timeout: Duration) -> bool {
+        let Some(socket) = &self.socket else {
+            std::thread::sleep(timeout);
+            return false;
+        };
+        let mut buf = [0u8; 16];
+        let _ = socket.set_read_timeout(Some(timeout));
+        if socket.recv(&mut buf).is_err() {
+            return false;
+        }
+        // Collapse a burst of rings into one wakeup.
+        let _ = socket.set_nonblocking(true);
+        while socket.recv(&mut buf).is_ok() {}
+        let _ = socket.set_nonblocking(false);
+        true
+    }
+}
+
+#[cfg(not(unix))]
+impl Doorbell {
+    pub(crate) fn bind(name: &str) -> Self {
+        let _ = NEXT_BELL.fetch_add(1, Ordering::Relaxed);
+        Self {
+            path: bell_dir(name),
+        }
+    }
+
+    pub(crate) fn wait(&self, timeout: Duration) -> bool {
+        std::thread::sleep(timeout);
+        false
+    }
+}
+
+impl Drop for Doorbell {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if self.socket.is_some() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn ring(name: &str) {
+    use std::os::unix::net::UnixDatagram;
+    let Ok(entries) = std::fs::read_dir(bell_dir(name)) else {
+        return;
+    };
+    let Ok(sender) = UnixDatagram::unbound() else {
+        return;
+    };
+    let _ = sender.set_nonblocking(true);
+    for path in entries.flatten().map(|entry| entry.path()) {
+        match sender.send_to(b"!", &path) {
+            Ok(_) => {}
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::ConnectionRefused | std::io::ErrorKind::NotFound
+                ) =>
+            {
+                let _ = std::fs::remove_file(&path);
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn ring(_name: &str) {}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn ring_wakes_subscribers_and_prunes_dead_sockets() {
+        let name = format!("test-bell-{}", std::process::id());
+        let dead = bell_dir(&name).join("999999999.sock");
+        let bell = Doorbell::bind(&name);
+        std::fs::write(&dead, "").expect("write dead socket");
+
+        assert!(!bell.wait(Duration::from_millis(20)));
+        ring(&name);
+        ring(&name);
+        let started = Instant::now();
+        assert!(bell.wait(Duration::from_secs(5)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(!bell.wait(Duration::from_millis(20)));
+        assert!(!dead.exists());
+
+        let dir = bell_dir(&name);
+        drop(bell);
+        let _ = std::fs::remove_dir(dir);
+    }
+}
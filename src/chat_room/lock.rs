@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const LOCK_TIMEOUT_SEC: u64 = 15;
+// An owner-less lock is a writer that died between create and write.
+const EMPTY_LOCK_GRACE_SEC: u64 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(super) struct LockOwner {
+    pub(super) pid: u32,
+    pub(super) host: String,
+    #[serde(default)]
+    pub(super) acquired_at: u64,
+}
+
+pub(super) struct RoomLock {
+    path: PathBuf,
+    raw: String,
+}
+
+impl Drop for RoomLock {
+    fn drop(&mut self) {
+        // Leave it alone if someone broke our lock and took it over.
+        if fs::read_to_string(&self.path).is_ok_and(|raw| raw == self.raw) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pid_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pid_alive(_pid: u32) -> bool {
+    true
+}
+
+pub(super) fn local_host() -> String {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+fn lock_age_sec(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+        .map_or(0, |d| d.as_secs())
+}
+
+pub(super) fn read_owner(path: &Path) -> Option<LockOwner> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_yaml::from_str(&raw).ok()
+}
+
+// Only owners on this host can be checked; remote owners wait for the timeout.
+pub(super) fn stale_reason(path: &Path) -> Option<String> {
+    match read_owner(path) {
+        Some(owner) if owner.host == local_host() && !pid_alive(owner.pid) => {
+            Some(format!("owner pid {} on {} is gone", owner.pid, owner.host))
+        }
+        Some(_) => None,
+        None if lock_age_sec(path) >= EMPTY_LOCK_GRACE_SEC => {
+            Some("lock has no readable owner".to_string())
+        }
+        None => None,
+    }
+}
+
+// Removes the lock only if it still holds what we judged stale.
+pub(super) fn break_lock(path: &Path) -> Option<String> {
+    let before = fs::read_to_string(path).ok()?;
+    stale_reason(path)?;
+    remove_if_unchanged(path, &before)
+}
+
+// Breakers serialize on a sidecar flock, which makes compare-and-remove one
+// step: while the file still holds the stale owner nobody can create a new
+// lock, and only breakers remove it. A waiter that judged the same owner
+// stale finds the successor's lock here and leaves it alone.
+fn remove_if_unchanged(path: &Path, before: &str) -> Option<String> {
+    let guard = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(break_guard_path(path))
+        .ok()?;
+    guard.lock().ok()?;
+    if fs::read_to_string(path).ok()? != before {
+        return None;
+    }
+    let reason = stale_reason(path)?;
+    fs::remove_file(path).ok()?;
+    Some(reason)
+}
+
+pub(super) fn break_guard_path(path: &Path) -> PathBuf {
+    path.with_extension("lock.break")
+}
+
+fn describe_owner(path: &Path) -> String {
+    match read_owner(path) {
+        Some(owner) => format!("pid {} on {}", owner.pid, owner.host),
+        None => "unknown owner".to_string(),
+    }
+}
+
+pub(super) fn acquire(path: &Path) -> Result<RoomLock, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    let owner = LockOwner {
+        pid: std::process::id(),
+        host: local_host(),
+        acquired_at: crate::now_unix_secs(),
+    };
+    let raw =
+        serde_yaml::to_string(&owner).map_err(|e| format!("lock yaml encode error: {}", e))?;
+    let started = SystemTime::now();
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                let lock = RoomLock {
+                    path: path.to_path_buf(),
+                    raw: raw.clone(),
+                };
+                file.write_all(raw.as_bytes())
+                    .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+                return Ok(lock);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                if let Some(reason) = break_lock(path) {
+                    eprintln!(
+                        "chat room: removed stale lock {} ({})",
+                        path.display(),
+                        reason
+                    );
+                    continue;
+                }
+                let elapsed = started.elapsed().map(|d| d.as_secs()).unwrap_or(0);
+                if elapsed >= LOCK_TIMEOUT_SEC {
+                    return Err(format!(
+                        "chat room lock timeout: {} held by {}",
+                        path.display(),
+                        describe_owner(path)
+                    ));
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => {
+                return Err(format!(
+                    "failed to create chat room lock {}: {}",
+                    path.display(),
+                    e
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_owner_lock_is_broken_and_live_one_is_kept() {
+        let dir = crate::source_root().join(".temp");
+        fs::create_dir_all(&dir).expect("create .temp");
+        let path = dir.join(format!("test-lock-{}.lock", std::process::id()));
+        let dead = LockOwner {
+            pid: u32::MAX - 1,
+            host: local_host(),
+            acquired_at: 0,
+        };
+        fs::write(&path, serde_yaml::to_string(&dead).expect("encode")).expect("write");
+
+        let lock = acquire(&path).expect("break stale lock");
+        assert_eq!(read_owner(&path).map(|o| o.pid), Some(std::process::id()));
+        assert!(stale_reason(&path).is_none());
+        assert!(break_lock(&path).is_none());
+        drop(lock);
+        assert!(!path.exists());
+
+        let remote = LockOwner {
+            host: format!("{}-elsewhere", local_host()),
+            ..dead
+        };
+        fs::write(&path, serde_yaml::to_string(&remote).expect("encode")).expect("write");
+        assert!(stale_reason(&path).is_none());
+        let _ = fs::remove_file(&path);
+
+        // A second waiter that judged the dead owner stale must not remove
+        // the lock the first waiter took after breaking it.
+        let stale_raw = serde_yaml::to_string(&dead).expect("encode");
+        let fresh = acquire(&path).expect("fresh lock");
+        assert!(remove_if_unchanged(&path, &stale_raw).is_none());
+        assert_eq!(read_owner(&path).map(|o| o.pid), Some(std::process::id()));
+        drop(fresh);
+        assert!(!path.exists());
+        let _ = fs::remove_file(break_guard_path(&path));
+    }
+}
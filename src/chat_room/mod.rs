@@ -0,0 +1,626 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+mod archive;
+pub(crate) mod bridge;
+mod doorbell;
+mod lock;
+pub(crate) mod protocol;
+
+pub(crate) use archive::{history, HistoryFilter, Retention};
+pub(crate) use bridge::BridgeOptions;
+pub(crate) use doorbell::{ring, Doorbell};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub(crate) struct ChatMessage {
+    pub(crate) message_id: String,
+    pub(crate) command: String,
+    #[serde(default)]
+    pub(crate) data: Option<String>,
+    #[serde(default)]
+    pub(crate) receiver: Option<String>,
+    pub(crate) sender_id: String,
+    #[serde(default)]
+    pub(crate) created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct ChatUser {
+    pub(crate) user_id: String,
+    #[serde(default = "default_chat_user_role")]
+    pub(crate) role: String,
+}
+
+// Room header. Messages live in the append-only `<name>.messages.jsonl` log;
+// `messages` is only read to migrate rooms written by older versions.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct ChatRoomDoc {
+    #[serde(default)]
+    pub(crate) room_name: String,
+    #[serde(default)]
+    pub(crate) users: Vec<ChatUser>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) messages: Vec<ChatMessage>,
+    #[serde(default, skip_serializing_if = "Retention::is_unset")]
+    pub(crate) retention: Retention,
+}
+
+fn default_chat_user_role() -> String {
+    "user".to_string()
+}
+
+pub(crate) fn temp_dir() -> PathBuf {
+    crate::source_root().join(".temp")
+}
+
+pub(crate) fn room_path(name: &str) -> PathBuf {
+    temp_dir().join(format!("{}.yaml", name))
+}
+
+pub(crate) fn log_path(name: &str) -> PathBuf {
+    temp_dir().join(format!("{}.messages.jsonl", name))
+}
+
+fn lock_path(name: &str) -> PathBuf {
+    temp_dir().join(format!("{}.lock", name))
+}
+
+fn acquire_lock(name: &str) -> Result<lock::RoomLock, String> {
+    lock::acquire(&lock_path(name))
+}
+
+fn backup_path(name: &str) -> PathBuf {
+    temp_dir().join(format!("{}.yaml.bak", name))
+}
+
+fn read_header(path: &Path, name: &str) -> Result<Option<ChatRoomDoc>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    if raw.trim().is_empty() {
+        return Ok(None);
+    }
+    let mut doc: ChatRoomDoc = serde_yaml::from_str(&raw).map_err(|e| {
+        format!(
+            "failed to parse {}: {} (run `orc chat repair -n {}`)",
+            path.display(),
+            e,
+            name
+        )
+    })?;
+    if doc.room_name.trim().is_empty() {
+        doc.room_name = name.to_string();
+    }
+    Ok(Some(doc))
+}
+
+fn write_header(path: &Path, doc: &ChatRoomDoc) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    let raw =
+        serde_yaml::to_string(doc).map_err(|e| format!("chat room yaml encode error: {}", e))?;
+    write_atomic(path, raw.as_bytes())?;
+    // Last known-good header for `chat repair`.
+    write_atomic(&path.with_extension("yaml.bak"), raw.as_bytes())
+}
+
+fn write_atomic(path: &Path, raw: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+    let mut file = fs::File::create(&tmp)
+        .map_err(|e| format!("failed to write {}: {}", tmp.display(), e))?;
+    file.write_all(raw)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("failed to replace {}: {}", path.display(), e)
+    })
+}
+
+fn encode_line(message: &ChatMessage) -> Result<String, String> {
+    let mut line =
+        serde_json::to_string(message).map_err(|e| format!("chat message encode error: {}", e))?;
+    line.push('\n');
+    Ok(line)
+}
+
+fn append_lines(path: &Path, raw: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    file.write_all(raw.as_bytes())
+        .map_err(|e| format!("failed to append {}: {}", path.display(), e))
+}
+
+fn ends_mid_line(path: &Path) -> bool {
+    let mut last = [0u8; 1];
+    fs::File::open(path)
+        .and_then(|mut file| {
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)
+        })
+        .is_ok_and(|_| last[0] != b'\n')
+}
+
+// Creates the room header when missing and moves messages of legacy
+// single-file rooms into the log.
+pub(crate) fn open_room(name: &str) -> Result<ChatRoomDoc, String> {
+    let path = room_path(name);
+    if let Some(doc) = read_header(&path, name)? {
+        if doc.messages.is_empty() {
+            return Ok(doc);
+        }
+    }
+    let _guard = acquire_lock(name)?;
+    let mut doc = read_header(&path, name)?.unwrap_or_else(|| ChatRoomDoc {
+        room_name: name.to_string(),
+        ..ChatRoomDoc::default()
+    });
+    if !doc.messages.is_empty() {
+        let mut raw = String::new();
+        for message in &doc.messages {
+            raw.push_str(&encode_line(message)?);
+        }
+        let log = log_path(name);
+        let existing = fs::read_to_string(&log).unwrap_or_default();
+        if existing.is_empty() {
+            append_lines(&log, &raw)?;
+        } else {
+            // Legacy messages predate anything already in the log.
+            write_atomic(&log, format!("{}{}", raw, existing).as_bytes())?;
+        }
+        doc.messages.clear();
+    }
+    write_header(&path, &doc)?;
+    Ok(doc)
+}
+
+pub(crate) fn join(name: &str, user_id: &str) -> Result<ChatRoomDoc, String> {
+    let doc = open_room(name)?;
+    if doc.users.iter().any(|u| u.user_id == user_id) {
+        return Ok(doc);
+    }
+    let _guard = acquire_lock(name)?;
+    let path = room_path(name);
+    let mut doc = read_header(&path, name)?.unwrap_or(doc);
+    if !doc.users.iter().any(|u| u.user_id == user_id) {
+        doc.users.push(ChatUser {
+            user_id: user_id.to_string(),
+            role: default_chat_user_role(),
+        });
+        write_header(&path, &doc)?;
+    }
+    Ok(doc)
+}
+
+const RETENTION_CHECK_BYTES: u64 = 16 * 1024;
+
+// One O_APPEND write per message; readers only ever see whole lines. A log
+// left mid-line by a crashed writer gets a line break first, so the torn
+// line does not swallow this message too.
+pub(crate) fn append(name: &str, message: &ChatMessage) -> Result<(), String> {
+    let path = log_path(name);
+    let line = encode_line(message)?;
+    let crossed = loop {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+        // Shared with other appenders, exclusive against `rewrite_log`.
+        file.lock_shared()
+            .map_err(|e| format!("failed to lock {}: {}", path.display(), e))?;
+        if !same_file(&file, &path) {
+            // Rewritten while we waited; the old inode is no longer the log.
+            continue;
+        }
+        let mut raw = line.clone();
+        if ends_mid_line(&path) {
+            raw.insert(0, '\n');
+        }
+        file.write_all(raw.as_bytes())
+            .map_err(|e| format!("failed to append {}: {}", path.display(), e))?;
+        let after = file.metadata().map_or(0, |m| m.len());
+        break after.saturating_sub(raw.len() as u64) / RETENTION_CHECK_BYTES
+            < after / RETENTION_CHECK_BYTES;
+    };
+    ring(name);
+    // Retention is only checked each time the log grows past another
+    // RETENTION_CHECK_BYTES, so appends stay O(1).
+    if crossed {
+        if let Ok(Some(doc)) = read_header(&room_path(name), name) {
+            archive::enforce(name, &doc.retention, crate::now_unix_secs(), false)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn file_identity(meta: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_identity(_meta: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+fn same_file(file: &fs::File, path: &Path) -> bool {
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(a), Ok(b)) => file_identity(&a) == file_identity(&b),
+        _ => false,
+    }
+}
+
+fn parse_lines(raw: &[u8]) -> Vec<ChatMessage> {
+    String::from_utf8_lossy(raw)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<ChatMessage>(line).ok())
+        .collect()
+}
+
+// Replaces the log with what `f` returns (if anything) while holding an
+// exclusive lock, so no append lands on the inode being replaced.
+fn rewrite_log(
+    name: &str,
+    f: impl FnOnce(&[u8]) -> Result<Option<String>, String>,
+) -> Result<(), String> {
+    let path = log_path(name);
+    let mut file = match OpenOptions::new().read(true).write(true).open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("failed to open {}: {}", path.display(), e)),
+    };
+    file.lock()
+        .map_err(|e| format!("failed to lock {}: {}", path.display(), e))?;
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    if let Some(out) = f(&raw)? {
+        write_atomic(&path, out.as_bytes())?;
+    }
+    Ok(())
+}
+
+// Stores the room's retention and archives whatever already falls outside it.
+pub(crate) fn set_retention(name: &str, retention: Retention) -> Result<usize, String> {
+    open_room(name)?;
+    {
+        let _guard = acquire_lock(name)?;
+        let path = room_path(name);
+        let mut doc = read_header(&path, name)?.unwrap_or_default();
+        doc.retention = retention;
+        write_header(&path, &doc)?;
+    }
+    archive::enforce(name, &retention, crate::now_unix_secs(), true)
+}
+
+pub(crate) fn read_all(name: &str) -> Result<Vec<ChatMessage>, String> {
+    let mut cursor = ChatCursor::from_start(name);
+    cursor.read_new()
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct RepairReport {
+    pub(crate) header: &'static str,
+    pub(crate) kept: usize,
+    pub(crate) dropped: usize,
+    pub(crate) lock: Option<String>,
+}
+
+// Drops torn or garbage log lines and restores an unreadable header from its
+// backup, or rebuilds it from the log when no backup parses either.
+pub(crate) fn repair(name: &str) -> Result<RepairReport, String> {
+    if !room_path(name).exists() && !log_path(name).exists() {
+        return Err(format!("chat room not found: {}", name));
+    }
+    let mut report = RepairReport {
+        lock: lock::break_lock(&lock_path(name)),
+        ..RepairReport::default()
+    };
+    let _guard = acquire_lock(name)?;
+
+    let mut messages = Vec::new();
+    rewrite_log(name, |raw| {
+        let mut out = String::new();
+        for line in String::from_utf8_lossy(raw).lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ChatMessage>(line) {
+                Ok(message) => {
+                    out.push_str(&encode_line(&message)?);
+                    messages.push(message);
+                }
+                Err(_) => report.dropped += 1,
+            }
+        }
+        report.kept = messages.len();
+        let clean = report.dropped == 0 && (raw.is_empty() || raw.ends_with(b"\n"));
+        Ok((!clean).then_some(out))
+    })?;
+
+    let path = room_path(name);
+    report.header = match read_header(&path, name) {
+        Ok(Some(_)) => "ok",
+        Ok(None) | Err(_) => {
+            let backup = read_header(&backup_path(name), name).ok().flatten();
+            let restored = backup.is_some();
+            let mut doc = backup.unwrap_or_else(|| ChatRoomDoc {
+                room_name: name.to_string(),
+                ..ChatRoomDoc::default()
+            });
+            for message in &messages {
+                if !doc.users.iter().any(|u| u.user_id == message.sender_id) {
+                    doc.users.push(ChatUser {
+                        user_id: message.sender_id.clone(),
+                        role: default_chat_user_role(),
+                    });
+                }
+            }
+            write_header(&path, &doc)?;
+            if restored {
+                "restored from backup"
+            } else {
+                "rebuilt from log"
+            }
+        }
+    };
+    Ok(report)
+}
+
+const TAIL_WINDOW: u64 = 64 * 1024;
+
+// Offset just past the last complete line and the id of the last message up
+// to it, reading back from the end only as far as needed.
+fn tail_position(path: &Path) -> (u64, Option<String>) {
+    let Ok(mut file) = fs::File::open(path) else {
+        return (0, None);
+    };
+    let len = file.metadata().map_or(0, |m| m.len());
+    let mut window = TAIL_WINDOW;
+    loop {
+        let start = len.saturating_sub(window);
+        let mut raw = Vec::new();
+        let read = file
+            .seek(SeekFrom::Start(start))
+            .and_then(|_| (&mut file).take(len - start).read_to_end(&mut raw));
+        if read.is_err() {
+            return (len, None);
+        }
+        let complete = raw
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(&raw[..0], |end| &raw[..=end]);
+        let end = start + complete.len() as u64;
+        // A window that starts mid-file may open inside a line.
+        let lines = if start == 0 {
+            complete
+        } else {
+            complete
+                .iter()
+                .position(|b| *b == b'\n')
+                .map_or(&complete[..0], |first| &complete[first + 1..])
+        };
+        if let Some(last) = parse_lines(lines).pop() {
+            return (end, Some(last.message_id));
+        }
+        if start == 0 {
+            return (end, None);
+        }
+        window = window.saturating_mul(2);
+    }
+}
+
+// Tracks the log inode and the last delivered message, so a log rewritten by
+// retention or repair resumes after that message instead of replaying it.
+pub(crate) struct ChatCursor {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+    identity: Option<(u64, u64)>,
+    last_id: Option<String>,
+}
+
+impl ChatCursor {
+    pub(crate) fn from_start(name: &str) -> Self {
+        Self {
+            path: log_path(name),
+            offset: 0,
+            partial: Vec::new(),
+            identity: None,
+            last_id: None,
+        }
+    }
+
+    // Starts after the last complete line and remembers its message, so a
+    // rewrite before the first read does not replay the retained log.
+    pub(crate) fn at_end(name: &str) -> Self {
+        let path = log_path(name);
+        let identity = fs::metadata(&path).ok().as_ref().and_then(file_identity);
+        let (offset, last_id) = tail_position(&path);
+        Self {
+            offset,
+            identity,
+            path,
+            partial: Vec::new(),
+            last_id,
+        }
+    }
+
+    pub(crate) fn read_new(&mut self) -> Result<Vec<ChatMessage>, String> {
+        let mut file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("failed to open {}: {}", self.path.display(), e)),
+        };
+        let meta = file
+            .metadata()
+            .map_err(|e| format!("failed to stat {}: {}", self.path.display(), e))?;
+        let identity = file_identity(&meta);
+        let rewritten = meta.len() < self.offset
+            || (self.identity.is_some() && self.identity != identity);
+        self.identity = identity;
+        if rewritten {
+            self.offset = 0;
+            self.partial.clear();
+        }
+        file.seek(SeekFrom::Start(self.offset))
+            .map_err(|e| format!("failed to seek {}: {}", self.path.display(), e))?;
+        let read = file
+            .read_to_end(&mut self.partial)
+            .map_err(|e| format!("failed to read {}: {}", self.path.display(), e))?;
+        self.offset += read as u64;
+        let Some(end) = self.partial.iter().rposition(|b| *b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        let mut messages = parse_lines(&complete);
+        if rewritten {
+            // Rewrites only drop a prefix; if the last seen message is gone,
+            // everything left is new.
+            if let Some(pos) = self
+                .last_id
+                .as_ref()
+                .and_then(|last| messages.iter().position(|m| &m.message_id == last))
+            {
+                messages.drain(..=pos);
+            }
+        }
+        if let Some(last) = messages.last() {
+            self.last_id = Some(last.message_id.clone());
+        }
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str) -> ChatMessage {
+        ChatMessage {
+            message_id: id.to_string(),
+            command: format!("cmd-{}", id),
+            sender_id: "s1".to_string(),
+            ..ChatMessage::default()
+        }
+    }
+
+    #[test]
+    fn legacy_room_is_migrated_and_cursor_reads_only_new_lines() {
+        let name = format!("test-room-{}", std::process::id());
+        let header = room_path(&name);
+        fs::create_dir_all(temp_dir()).expect("create .temp");
+        let legacy = ChatRoomDoc {
+            room_name: String::new(),
+            users: Vec::new(),
+            messages: vec![message("old1"), message("old2")],
+            retention: Retention::default(),
+        };
+        write_header(&header, &legacy).expect("write legacy room");
+
+        let room = join(&name, "u1").expect("join");
+        assert_eq!(room.room_name, name);
+        assert!(room.messages.is_empty());
+        let ids: Vec<String> = read_all(&name)
+            .expect("read all")
+            .into_iter()
+            .map(|m| m.message_id)
+            .collect();
+        assert_eq!(ids, vec!["old1", "old2"]);
+
+        let mut cursor = ChatCursor::at_end(&name);
+        assert!(cursor.read_new().expect("empty").is_empty());
+        append(&name, &message("new1")).expect("append");
+        append_lines(&log_path(&name), "{\"message_id\":\"half").expect("partial");
+        let fresh = cursor.read_new().expect("read new");
+        assert_eq!(fresh, vec![message("new1")]);
+        append_lines(
+            &log_path(&name),
+            "\",\"command\":\"x\",\"sender_id\":\"s2\"}\n",
+        )
+        .expect("finish line");
+        assert_eq!(cursor.read_new().expect("rest")[0].message_id, "half");
+
+        let _ = fs::remove_file(header);
+        let _ = fs::remove_file(backup_path(&name));
+        let _ = fs::remove_file(log_path(&name));
+    }
+
+    #[test]
+    fn cursor_at_end_survives_a_rewrite_before_its_first_read() {
+        let name = format!("test-cursor-rewrite-{}", std::process::id());
+        for id in ["m1", "m2", "m3"] {
+            append(&name, &message(id)).expect("append");
+        }
+        let mut cursor = ChatCursor::at_end(&name);
+        let retention = Retention {
+            max_messages: Some(2),
+            max_age_sec: None,
+        };
+        assert_eq!(set_retention(&name, retention).expect("retention"), 1);
+        append(&name, &message("m4")).expect("append after rewrite");
+        assert_eq!(cursor.read_new().expect("read new"), vec![message("m4")]);
+
+        let _ = fs::remove_dir_all(archive::archive_dir(&name));
+        for path in [room_path(&name), backup_path(&name), log_path(&name)] {
+            let _ = fs::remove_file(path);
+        }
+        let _ = fs::remove_file(lock_path(&name));
+    }
+
+    #[test]
+    fn repair_drops_torn_lines_and_restores_truncated_header() {
+        let name = format!("test-repair-{}", std::process::id());
+        join(&name, "u1").expect("join");
+        append(&name, &message("m1")).expect("append");
+        append_lines(&log_path(&name), "{\"message_id\":\"torn").expect("torn write");
+        append(&name, &message("m2")).expect("append after torn line");
+        let ids = |name: &str| -> Vec<String> {
+            read_all(name)
+                .expect("read all")
+                .into_iter()
+                .map(|m| m.message_id)
+                .collect()
+        };
+        assert_eq!(ids(&name), vec!["m1", "m2"]);
+
+        fs::write(room_path(&name), "room_name: x\nusers:\n  - user_id: [").expect("truncate");
+        assert!(open_room(&name).is_err());
+        let report = repair(&name).expect("repair");
+        assert_eq!(report.header, "restored from backup");
+        assert_eq!((report.kept, report.dropped), (2, 1));
+        assert_eq!(open_room(&name).expect("reopen").users[0].user_id, "u1");
+        assert_eq!(ids(&name), vec!["m1", "m2"]);
+
+        fs::remove_file(room_path(&name)).expect("remove header");
+        fs::remove_file(backup_path(&name)).expect("remove backup");
+        let report = repair(&name).expect("rebuild");
+        assert_eq!(report.header, "rebuilt from log");
+        assert_eq!(report.dropped, 0);
+        assert_eq!(open_room(&name).expect("reopen").users[0].user_id, "s1");
+
+        for path in [room_path(&name), backup_path(&name), log_path(&name)] {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use serde::{Deserialize, Serialize};
+
+use super::ChatMessage;
+
+const REPLY_OUTPUT_LIMIT: usize = 4000;
+// Subcommands an agent may trigger in another agent's checkout via `run_orc`.
+const RUN_ORC_ALLOWED: [&str; 9] = [
+    "create_input_md",
+    "create_code_draft",
+    "add_code_draft",
+    "add_code_draft_item",
+    "impl_code_draft",
+    "check_code_draft",
+    "check_task",
+    "check_draft",
+    "test",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TaskPayload {
+    pub(crate) task: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RunOrcPayload {
+    pub(crate) subcommand: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ReplyPayload {
+    pub(crate) in_reply_to: String,
+    pub(crate) ok: bool,
+    #[serde(default)]
+    pub(crate) output: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AgentCommand {
+    ClaimTask(TaskPayload),
+    TaskDone(TaskPayload),
+    RequestReview(TaskPayload),
+    RunOrc(RunOrcPayload),
+    Reply(ReplyPayload),
+}
+
+impl AgentCommand {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            AgentCommand::ClaimTask(_) => "claim_task",
+            AgentCommand::TaskDone(_) => "task_done",
+            AgentCommand::RequestReview(_) => "request_review",
+            AgentCommand::RunOrc(_) => "run_orc",
+            AgentCommand::Reply(_) => "reply",
+        }
+    }
+
+    fn data(&self) -> Result<String, String> {
+        let encoded = match self {
+            AgentCommand::ClaimTask(p)
+            | AgentCommand::TaskDone(p)
+            | AgentCommand::RequestReview(p) => serde_json::to_string(p),
+            AgentCommand::RunOrc(p) => serde_json::to_string(p),
+            AgentCommand::Reply(p) => serde_json::to_string(p),
+        };
+        encoded.map_err(|e| format!("agent command encode error: {}", e))
+    }
+
+    // `None` for free-form chat; `Some(Err)` for a known command with a bad payload.
+    pub(crate) fn parse(message: &ChatMessage) -> Option<Result<Self, String>> {
+        let mut words = message.command.split_whitespace();
+        let kind = words.next()?;
+        let inline: Vec<String> = words.map(str::to_string).collect();
+        let data = message
+            .data
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty());
+        let parsed = match kind {
+            "claim_task" => task_payload(kind, &inline, data).map(AgentCommand::ClaimTask),
+            "task_done" => task_payload(kind, &inline, data).map(AgentCommand::TaskDone),
+            "request_review" => task_payload(kind, &inline, data).map(AgentCommand::RequestReview),
+            "run_orc" => run_orc_payload(&inline, data).map(AgentCommand::RunOrc),
+            "reply" => data
+                .ok_or_else(|| "reply requires --data".to_string())
+                .and_then(|d| {
+                    serde_json::from_str::<ReplyPayload>(d)
+                        .map_err(|e| format!("invalid reply data: {}", e))
+                })
+                .map(AgentCommand::Reply),
+            _ => return None,
+        };
+        Some(parsed)
+    }
+}
+
+fn task_payload(kind: &str, inline: &[String], data: Option<&str>) -> Result<TaskPayload, String> {
+    if let Some(raw) = data.filter(|d| d.starts_with('{')) {
+        let payload = serde_json::from_str::<TaskPayload>(raw)
+            .map_err(|e| format!("invalid {} data: {}", kind, e))?;
+        if payload.task.trim().is_empty() {
+            return Err(format!("{} requires a task", kind));
+        }
+        return Ok(payload);
+    }
+    let task = inline.join(" ");
+    let (task, note) = if task.is_empty() {
+        (data.unwrap_or("").to_string(), None)
+    } else {
+        (task, data.map(str::to_string))
+    };
+    if task.trim().is_empty() {
+        return Err(format!("{} requires a task: `{} <task>`", kind, kind));
+    }
+    Ok(TaskPayload { task, note })
+}
+
+fn run_orc_payload(inline: &[String], data: Option<&str>) -> Result<RunOrcPayload, String> {
+    let payload = match data.filter(|d| d.starts_with('{')) {
+        Some(raw) => serde_json::from_str::<RunOrcPayload>(raw)
+            .map_err(|e| format!("invalid run_orc data: {}", e))?,
+        None => {
+            let mut words: Vec<String> = inline.to_vec();
+            if let Some(extra) = data {
+                words.extend(extra.split_whitespace().map(str::to_string));
+            }
+            if words.is_empty() {
+                return Err(
+                    "run_orc requires a subcommand: `run_orc <subcommand> [args]`".to_string(),
+                );
+            }
+            RunOrcPayload {
+                subcommand: words.remove(0),
+                args: words,
+            }
+        }
+    };
+    if !RUN_ORC_ALLOWED.contains(&payload.subcommand.as_str()) {
+        return Err(format!(
+            "run_orc subcommand not allowed: {} (allowed: {})",
+            payload.subcommand,
+            RUN_ORC_ALLOWED.join(", ")
+        ));
+    }
+    Ok(payload)
+}
+
+// Rewrites typed commands into canonical `command = kind`, `data = json` form.
+pub(crate) fn normalize(message: &mut ChatMessage) -> Result<(), String> {
+    let Some(parsed) = AgentCommand::parse(message) else {
+        return Ok(());
+    };
+    let command = parsed?;
+    message.command = command.kind().to_string();
+    message.data = Some(command.data()?);
+    Ok(())
+}
+
+// The first `claim_task` after the task's last `task_done` owns it; every
+// agent replaying the same log agrees on the owner.
+pub(crate) fn claim_owner(history: &[ChatMessage], task: &str) -> Option<String> {
+    let mut owner: Option<String> = None;
+    for message in history {
+        match AgentCommand::parse(message) {
+            Some(Ok(AgentCommand::ClaimTask(p))) if p.task == task && owner.is_none() => {
+                owner = Some(message.sender_id.clone());
+            }
+            Some(Ok(AgentCommand::TaskDone(p)))
+                if p.task == task && owner.as_deref() == Some(message.sender_id.as_str()) =>
+            {
+                owner = None;
+            }
+            _ => {}
+        }
+    }
+    owner
+}
+
+// Replays archived segments too; a claim moved out by retention still holds.
+pub(crate) fn room_claim_owner(room: &str, task: &str) -> Result<Option<String>, String> {
+    let filter = super::HistoryFilter::default();
+    Ok(claim_owner(&super::history(room, &filter)?, task))
+}
+
+pub(crate) fn reply_to(
+    message: &ChatMessage,
+    self_id: &str,
+    result: Result<String, String>,
+) -> ChatMessage {
+    let (ok, output) = match result {
+        Ok(output) => (true, output),
+        Err(e) => (false, e),
+    };
+    let output = if output.chars().count() > REPLY_OUTPUT_LIMIT {
+        let tail: String = output
+            .chars()
+            .rev()
+            .take(REPLY_OUTPUT_LIMIT)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        format!("...{}", tail)
+    } else {
+        output
+    };
+    let reply = AgentCommand::Reply(ReplyPayload {
+        in_reply_to: message.message_id.clone(),
+        ok,
+        output,
+    });
+    ChatMessage {
+        message_id: String::new(),
+        command: reply.kind().to_string(),
+        data: reply.data().ok(),
+        receiver: Some(message.sender_id.clone()),
+        sender_id: self_id.to_string(),
+        created_at: String::new(),
+    }
+}
+
+pub(crate) async fn handle(
+    room: &str,
+    command: &AgentCommand,
+    message: &ChatMessage,
+) -> Option<Result<String, String>> {
+    match command {
+        AgentCommand::ClaimTask(p) => Some(match room_claim_owner(room, &p.task) {
+            Ok(Some(owner)) if owner == message.sender_id => {
+                Ok(format!("task claimed: {} by {}", p.task, owner))
+            }
+            Ok(Some(owner)) => Err(format!("task already claimed: {} by {}", p.task, owner)),
+            Ok(None) => Err(format!("task not claimable: {}", p.task)),
+            Err(e) => Err(e),
+        }),
+        AgentCommand::TaskDone(p) => Some(Ok(format!("task done acknowledged: {}", p.task))),
+        AgentCommand::RequestReview(p) => {
+            let profile = match crate::cli::default_profile() {
+                Ok(profile) => profile,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(
+                profile
+                    .feedback_service()
+                    .check(true)
+                    .map(|out| format!("review for {}:\n{}", p.task, out)),
+            )
+        }
+        AgentCommand::RunOrc(p) => {
+            let profile = match crate::cli::default_profile() {
+                Ok(profile) => profile,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(
+                crate::cli::run_profile_command(profile.as_ref(), &p.subcommand, &p.args)
+                    .await
+                    .unwrap_or_else(|| Err(format!("unknown command: {}", p.subcommand))),
+            )
+        }
+        AgentCommand::Reply(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(sender: &str, command: &str, data: Option<&str>) -> ChatMessage {
+        ChatMessage {
+            message_id: format!("{}-{}", sender, command),
+            command: command.to_string(),
+            data: data.map(str::to_string),
+            sender_id: sender.to_string(),
+            ..ChatMessage::default()
+        }
+    }
+
+    #[test]
+    fn typed_commands_normalize_and_replay_claims() {
+        let mut claim = msg("a1", "claim_task login", None);
+        normalize(&mut claim).expect("normalize claim");
+        assert_eq!(claim.command, "claim_task");
+        assert_eq!(claim.data.as_deref(), Some(r#"{"task":"login"}"#));
+
+        let mut run = msg("a1", "run_orc check_code_draft -a", None);
+        normalize(&mut run).expect("normalize run_orc");
+        assert_eq!(
+            AgentCommand::parse(&run),
+            Some(Ok(AgentCommand::RunOrc(RunOrcPayload {
+                subcommand: "check_code_draft".to_string(),
+                args: vec!["-a".to_string()],
+            })))
+        );
+        assert!(normalize(&mut msg("a1", "run_orc serve-web-api", None)).is_err());
+        assert!(normalize(&mut msg("a1", "claim_task", None)).is_err());
+        let mut chatter = msg("a1", "hello there", Some("x"));
+        normalize(&mut chatter).expect("free text");
+        assert_eq!(chatter.command, "hello there");
+
+        let history = vec![
+            claim.clone(),
+            msg("b2", "claim_task", Some(r#"{"task":"login"}"#)),
+            msg("b2", "task_done login", None),
+        ];
+        assert_eq!(claim_owner(&history, "login").as_deref(), Some("a1"));
+        let mut history = history;
+        history.push(msg("a1", "task_done login", Some("shipped")));
+        history.push(msg("b2", "claim_task login", None));
+        assert_eq!(claim_owner(&history, "login").as_deref(), Some("b2"));
+
+        let room = format!("test-claims-{}", std::process::id());
+        super::super::append(&room, &claim).expect("append claim");
+        super::super::append(&room, &msg("c3", "hello", None)).expect("append chatter");
+        let retention = super::super::Retention {
+            max_messages: Some(1),
+            max_age_sec: None,
+        };
+        super::super::set_retention(&room, retention).expect("archive claim");
+        assert_eq!(super::super::read_all(&room).expect("live").len(), 1);
+        assert_eq!(
+            room_claim_owner(&room, "login").expect("owner").as_deref(),
+            Some("a1")
+        );
+        let _ = std::fs::remove_dir_all(super::super::archive::archive_dir(&room));
+        for path in [
+            super::super::room_path(&room),
+            super::super::backup_path(&room),
+            super::super::log_path(&room),
+            super::super::lock_path(&room),
+        ] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        let reply = reply_to(&claim, "b2", Err("busy".to_string()));
+        assert_eq!(reply.receiver.as_deref(), Some("a1"));
+        assert_eq!(
+            AgentCommand::parse(&reply),
+            Some(Ok(AgentCommand::Reply(ReplyPayload {
+                in_reply_to: claim.message_id.clone(),
+                ok: false,
+                output: "busy".to_string(),
+            })))
+        );
+    }
+}
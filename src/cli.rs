@@ -41,9 +41,16 @@ pub fn print_usage(program: &str) {
         "open-ui [-w|--web]",
         "serve-web-api [--addr <host:port>]",
         "auto <message> | auto -f",
-        "send-tmux <pane_id> <msg...> [enter|raw]",
+        "resume [<job_id>]",
+        "send-tmux <pane_id> (<msg...> | --file <path> | --stdin) [--paste|--literal|--keys] [enter|raw]",
+        "tmux-attach [<project>]",
+        "tmux-wait <pane_id> --match <regex> [--timeout <n>[s|m]] [--new]",
         "chat -n <name> [--background] [-m <message>] [-i <receiver_id>] [--data <data>]",
-        "chat-wait -n <name> -a <true|false> [-c <count>]",
+        "chat repair -n <name>",
+        "chat bridge -n <name> [--settle <n>[s|m]] [--timeout <n>[s|m]] [--until <regex>] [--background]",
+        "chat history -n <name> [--sender <id>] [--receiver <id>] [--command <cmd>] [--since <time>] [--until <time>] [--json] [-o <path>]",
+        "chat retention -n <name> [--max-messages <n>] [--max-age <n>[s|m|h|d]] [--clear]",
+        "chat-wait -n <name> -a <true|false> [-c <count>] [--serve]",
     ];
     commands.sort_unstable();
 
@@ -60,6 +67,10 @@ fn resolve_default_profile_name() -> String {
         })
 }
 
+pub(crate) fn default_profile() -> Result<Box<dyn super::profile::Profile>, String> {
+    super::profile::resolve_profile(&resolve_default_profile_name())
+}
+
 fn resolve_profile_and_command_index(args: &[String]) -> (String, usize) {
     if args.len() >= 3 && super::profile::is_known_profile_name(args[1].as_str()) {
         return (args[1].clone(), 2);
@@ -79,52 +90,11 @@ pub async fn execute_cli(args: &[String]) -> Result<String, String> {
     let command = args[command_idx].as_str();
     let tail = &args[(command_idx + 1)..];
 
+    if let Some(result) = run_profile_command(profile.as_ref(), command, tail).await {
+        return result;
+    }
+
     match command {
-        "init_code_project" => profile.project_service().create(tail),
-        "init_code_plan" => profile.plan_service().create(tail),
-        "add_code_plan" => profile.plan_service().add_feature(tail),
-        "create_input_md" => {
-            if !tail.is_empty() {
-                return Err("create_input_md does not accept arguments".to_string());
-            }
-            profile.plan_service().create_input()
-        }
-        "create_code_draft" => {
-            if !tail.is_empty() {
-                return Err("create_code_draft does not accept arguments".to_string());
-            }
-            profile.plan_service().create_draft()
-        }
-        "add_code_draft" => profile.draft_service().add(tail),
-        "add_code_draft_item" => profile.draft_service().move_item_to_drafts_yaml(tail),
-        "impl_code_draft" => {
-            if !tail.is_empty() {
-                return Err("impl_code_draft does not accept arguments".to_string());
-            }
-            profile.draft_service().run_parallel().await
-        }
-        "check_code_draft" => {
-            let auto_yes = tail.first().is_some_and(|v| v == "-a");
-            profile.feedback_service().check(auto_yes)
-        }
-        "check_task" => {
-            if !tail.is_empty() {
-                return Err("check_task does not accept arguments".to_string());
-            }
-            profile.feedback_service().decide_policy()
-        }
-        "test" => {
-            if !tail.is_empty() {
-                return Err("test does not accept arguments".to_string());
-            }
-            profile.feedback_service().check(false)
-        }
-        "check_draft" => {
-            if !tail.is_empty() {
-                return Err("check_draft does not accept arguments".to_string());
-            }
-            profile.feedback_service().check_draft()
-        }
         "open-ui" => {
             if tail.is_empty() {
                 super::tui::open_ui()
@@ -165,23 +135,19 @@ pub async fn execute_cli(args: &[String]) -> Result<String, String> {
             }
             profile.project_service().auto_message(&tail.join(" "))
         }
-        "send-tmux" => {
-            if tail.len() < 2 {
-                return Err("send-tmux requires <pane_id> <msg...> [enter|raw]".to_string());
-            }
-            let pane_id = &tail[0];
-            let (msg_slice, option) = match tail.last().map(String::as_str) {
-                Some("enter" | "raw") if tail.len() >= 3 => {
-                    (&tail[1..tail.len() - 1], tail[tail.len() - 1].as_str())
-                }
-                _ => (&tail[1..], "enter"),
+        "resume" => super::code::resume_code_job(tail).await,
+        "send-tmux" => super::tmux::send::send_tmux_command(tail),
+        "tmux-attach" => {
+            let session = match tail {
+                [] => super::tmux::layout::project_session_name(&super::context::project_root()),
+                [name] if name.starts_with("orc-") => name.clone(),
+                [name] => super::tmux::layout::find_project_session(name)?,
+                _ => return Err("tmux-attach accepts at most one <project>".to_string()),
             };
-            if msg_slice.is_empty() {
-                return Err("send-tmux requires non-empty message".to_string());
-            }
-            let msg = msg_slice.join(" ");
-            super::tmux::tsend(pane_id, &msg, option)
+            super::tmux::layout::attach(&session)?;
+            Ok(format!("tmux session detached: {}", session))
         }
+        "tmux-wait" => tmux_wait_command(tail),
         "chat" => {
             if tail.len() < 2 {
                 return Err(
@@ -193,10 +159,100 @@ pub async fn execute_cli(args: &[String]) -> Result<String, String> {
         }
         "chat-wait" => {
             if tail.len() < 2 {
-                return Err("chat-wait requires -n <name> -a <true|false> (optional: -c <count>)".to_string());
+                return Err("chat-wait requires -n <name> -a <true|false> (optional: -c <count> --serve)".to_string());
             }
             super::chat_wait_command(tail).await
         }
         _ => Err(format!("unknown command: {}", command)),
     }
 }
+
+// Blocks until the pane prints something matching `--match`; the error on
+// timeout gives scripts a non-zero exit.
+fn tmux_wait_command(tail: &[String]) -> Result<String, String> {
+    let usage = "tmux-wait requires <pane_id> --match <regex> (optional: --timeout <n>[s|m] --new)";
+    let Some((pane_id, rest)) = tail.split_first().filter(|(p, _)| !p.starts_with('-')) else {
+        return Err(usage.to_string());
+    };
+    let mut pattern = None;
+    let mut timeout_sec = 60u64;
+    let mut only_new = false;
+    let mut i = 0usize;
+    while i < rest.len() {
+        match rest[i].as_str() {
+            "--match" | "-m" => {
+                let raw = rest.get(i + 1).ok_or("tmux-wait: --match requires value")?;
+                pattern = Some(
+                    regex::Regex::new(raw)
+                        .map_err(|e| format!("tmux-wait: invalid --match: {}", e))?,
+                );
+                i += 2;
+            }
+            "--timeout" | "-t" => {
+                let raw = rest.get(i + 1).ok_or("tmux-wait: --timeout requires value")?;
+                timeout_sec = super::parse_duration_sec(raw)
+                    .ok_or_else(|| format!("tmux-wait: invalid --timeout: {}", raw))?;
+                i += 2;
+            }
+            "--new" => {
+                only_new = true;
+                i += 1;
+            }
+            other => return Err(format!("tmux-wait: unknown arg {}", other)),
+        }
+    }
+    let pattern = pattern.ok_or(usage)?;
+    let found = super::tmux::wait::wait_for(
+        pane_id,
+        &pattern,
+        std::time::Duration::from_secs(timeout_sec),
+        only_new,
+    )?;
+    Ok(found.line)
+}
+
+fn no_args(command: &str, tail: &[String]) -> Result<(), String> {
+    if tail.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} does not accept arguments", command))
+    }
+}
+
+// Profile-service commands; also reachable from chat `run_orc` requests.
+pub(crate) async fn run_profile_command(
+    profile: &dyn super::profile::Profile,
+    command: &str,
+    tail: &[String],
+) -> Option<Result<String, String>> {
+    let result = match command {
+        "init_code_project" => profile.project_service().create(tail),
+        "init_code_plan" => profile.plan_service().create(tail),
+        "add_code_plan" => profile.plan_service().add_feature(tail),
+        "create_input_md" => {
+            no_args(command, tail).and_then(|_| profile.plan_service().create_input())
+        }
+        "create_code_draft" => {
+            no_args(command, tail).and_then(|_| profile.plan_service().create_draft())
+        }
+        "add_code_draft" => profile.draft_service().add(tail),
+        "add_code_draft_item" => profile.draft_service().move_item_to_drafts_yaml(tail),
+        "impl_code_draft" => match no_args(command, tail) {
+            Ok(()) => profile.parallel_runner().run_draft_items().await,
+            Err(e) => Err(e),
+        },
+        "check_code_draft" => {
+            let auto_yes = tail.first().is_some_and(|v| v == "-a");
+            profile.feedback_service().check(auto_yes)
+        }
+        "check_task" => {
+            no_args(command, tail).and_then(|_| profile.feedback_service().decide_policy())
+        }
+        "test" => no_args(command, tail).and_then(|_| profile.feedback_service().check(false)),
+        "check_draft" => {
+            no_args(command, tail).and_then(|_| profile.feedback_service().check_draft())
+        }
+        _ => return None,
+    };
+    Some(result)
+}
@@ -9,9 +9,10 @@ use std::process::{Command, Stdio};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+use crate::jobs::{self, JobJournal, JobKind, JobState};
+
 const MODE_LIST: [&str; 4] = ["project", "plan", "draft", "report"];
 const CODE_SUBCOMMAND_TIMEOUT_SEC: u64 = 600;
-const IMPL_DRAFT_LLM_TIMEOUT_SEC: u64 = 240;
 const AUTO_RETRY_MAX: usize = 0;
 const AUTO_RETRY_SLEEP_SEC: u64 = 2;
 
@@ -56,6 +57,8 @@ struct DraftItemDoc {
     constraints: Vec<String>,
     #[serde(default)]
     check: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    verification: Vec<crate::verify::ConstraintResult>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -81,7 +84,7 @@ struct InputFeatureObject {
 
 pub(crate) fn init_code_project(args: &[String]) -> Result<String, String> {
     let opts = parse_common_opts(args);
-    let cwd = env::current_dir().map_err(|e| format!("failed to read cwd: {}", e))?;
+    let cwd = crate::context::project_root();
     let default_name = cwd
         .file_name()
         .and_then(|v| v.to_str())
@@ -157,7 +160,7 @@ pub(crate) fn init_code_project(args: &[String]) -> Result<String, String> {
 }
 
 pub(crate) fn load_code_project() -> Result<String, String> {
-    let cwd = env::current_dir().map_err(|e| format!("failed to read cwd: {}", e))?;
+    let cwd = crate::context::project_root();
     let name = cwd
         .file_name()
         .and_then(|v| v.to_str())
@@ -183,7 +186,7 @@ pub(crate) fn load_code_project() -> Result<String, String> {
 }
 
 pub(crate) fn detail_code_project() -> Result<String, String> {
-    let path = Path::new(crate::PROJECT_MD_PATH);
+    let path = &crate::project_md_file();
     let raw = fs::read_to_string(path)
         .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
     let next = infer_project_detail_with_llm(&raw)?;
@@ -193,7 +196,7 @@ pub(crate) fn detail_code_project() -> Result<String, String> {
 }
 
 pub(crate) fn create_code_domain() -> Result<String, String> {
-    let path = Path::new(crate::PROJECT_MD_PATH);
+    let path = &crate::project_md_file();
     let raw = fs::read_to_string(path)
         .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
     let current_domains: Vec<String> = extract_domains_from_project_md(&raw)
@@ -210,13 +213,13 @@ pub(crate) fn create_code_domain() -> Result<String, String> {
 }
 
 pub(crate) fn bootstrap_code_project() -> Result<String, String> {
-    let md = fs::read_to_string(crate::PROJECT_MD_PATH)
+    let md = fs::read_to_string(crate::project_md_file())
         .map_err(|e| format!("failed to read {}: {}", crate::PROJECT_MD_PATH, e))?;
     let info = crate::extract_project_info(&md);
     let name = extract_info_value(&info, "name").unwrap_or_else(|| "project".to_string());
     let spec = extract_project_spec_from_md(&md).unwrap_or_else(|| "next js".to_string());
-    let status = crate::ui::apply_bootstrap_by_spec(Path::new("."), &name)?;
-    let verify = ensure_bootstrap_spec_artifacts(Path::new("."), &spec)?;
+    let status = crate::ui::apply_bootstrap_by_spec(&crate::context::project_root(), &name)?;
+    let verify = ensure_bootstrap_spec_artifacts(&crate::context::project_root(), &spec)?;
     Ok(format!(
         "bootstrap_code_project completed: {} | {} | spec={}",
         status, verify, spec
@@ -233,7 +236,7 @@ pub(crate) fn init_code_plan(args: &[String]) -> Result<String, String> {
         ));
     }
     ensure_plan_yaml_initialized()?;
-    let project_md = fs::read_to_string(crate::PROJECT_MD_PATH)
+    let project_md = fs::read_to_string(crate::project_md_file())
         .map_err(|e| format!("failed to read {}: {}", crate::PROJECT_MD_PATH, e))?;
     let mut doc = infer_plan_doc_with_llm(&project_md)?;
     sync_plan_doc(&mut doc);
@@ -281,7 +284,7 @@ pub(crate) fn add_code_plan(args: &[String]) -> Result<String, String> {
         items.extend(infer_plan_items_with_llm()?);
     }
     if use_file {
-        let objs = parse_input_md_objects(Path::new(crate::INPUT_MD_PATH))?;
+        let objs = parse_input_md_objects(&crate::context::project_path(crate::INPUT_MD_PATH))?;
         items.extend(objs.into_iter().map(|o| o.name));
     }
     if let Some(msg) = message {
@@ -307,7 +310,7 @@ pub(crate) fn add_code_plan(args: &[String]) -> Result<String, String> {
         return Ok(out);
     }
     if !use_file && ask_yes_no("add_code_draft()를 호출할까요? [y/N]: ")? {
-        let draft_msg = run_code_subcommand_in_new_session("add_code_draft", &[])?;
+        let draft_msg = run_code_subcommand_in_new_session("add_code_draft", &[], None)?;
         out = format!("{} | {}", out, draft_msg);
     }
     Ok(out)
@@ -350,7 +353,7 @@ pub(crate) fn add_code_draft(args: &[String]) -> Result<String, String> {
     let mut drafts = load_drafts_doc()?;
     let mut plan_items = plan.drafts.planned.clone();
 
-    let project_md = fs::read_to_string(crate::PROJECT_MD_PATH)
+    let project_md = fs::read_to_string(crate::project_md_file())
         .map_err(|e| format!("failed to read {}: {}", crate::PROJECT_MD_PATH, e))?;
     let plan_yaml_raw = {
         let path = plan_yaml_path()?;
@@ -358,7 +361,7 @@ pub(crate) fn add_code_draft(args: &[String]) -> Result<String, String> {
     };
 
     let input_objects = if use_file {
-        parse_input_md_objects(Path::new(crate::INPUT_MD_PATH))?
+        parse_input_md_objects(&crate::context::project_path(crate::INPUT_MD_PATH))?
     } else {
         Vec::new()
     };
@@ -435,11 +438,22 @@ pub(crate) fn add_code_draft_item(args: &[String]) -> Result<String, String> {
 }
 
 pub(crate) fn auto_code_message(message: &str) -> Result<String, String> {
-    debug_log_auto_stage("auto", "auto message flow start");
     let trimmed = message.trim();
-    match run_code_subcommand_in_new_session("init_code_project", &["-a", trimmed]) {
+    let journal = JobJournal::create(&crate::context::project_root(), JobKind::Auto, Some(trimmed))?;
+    let result = run_auto_code_message(&journal, trimmed, None);
+    journal.finish(&result);
+    result
+}
+
+fn run_auto_code_message(
+    journal: &JobJournal,
+    message: &str,
+    resume: Option<&JobState>,
+) -> Result<String, String> {
+    debug_log_auto_stage("auto", "auto message flow start");
+    match run_job_stage(journal, resume, 0, "init_code_project", &["-a", message]) {
         Ok(init_out) => {
-            let loop_out = run_auto_retry_loop("auto", Some(trimmed), false)?;
+            let loop_out = run_auto_retry_loop(journal, resume, "auto", Some(message), false)?;
             debug_log_auto_stage("auto", "auto message flow completed");
             Ok(format!("{} | {}", init_out, loop_out))
         }
@@ -451,15 +465,27 @@ pub(crate) fn auto_code_message(message: &str) -> Result<String, String> {
 }
 
 pub(crate) fn auto_code_from_input_file() -> Result<String, String> {
+    let journal = JobJournal::create(&crate::context::project_root(), JobKind::AutoFile, None)?;
+    let result = run_auto_code_from_input_file(&journal, None);
+    journal.finish(&result);
+    result
+}
+
+fn run_auto_code_from_input_file(
+    journal: &JobJournal,
+    resume: Option<&JobState>,
+) -> Result<String, String> {
     debug_log_auto_stage("auto-file", "auto -f flow start");
-    let init_msg = run_code_subcommand_in_new_session("init_code_project", &[])?;
-    let plan_msg = if plan_yaml_path()?.exists() {
+    let init_msg = run_job_stage(journal, resume, 0, "init_code_project", &[])?;
+    let plan_msg = if resume.is_some_and(|state| state.is_committed(0, "init_code_plan")) {
+        "init_code_plan resumed: already committed".to_string()
+    } else if plan_yaml_path()?.exists() {
         "init_code_plan skipped: .project/plan.yaml already exists".to_string()
     } else {
-        run_code_subcommand_in_new_session("init_code_plan", &["-a"])?
+        run_job_stage(journal, resume, 0, "init_code_plan", &["-a"])?
     };
-    let input_msg = run_code_subcommand_in_new_session("create_input_md", &[])?;
-    let retry_msg = run_auto_retry_loop("auto -f", None, true)?;
+    let input_msg = run_job_stage(journal, resume, 0, "create_input_md", &[])?;
+    let retry_msg = run_auto_retry_loop(journal, resume, "auto -f", None, true)?;
     debug_log_auto_stage("auto-file", "auto -f flow completed");
 
     Ok(format!(
@@ -468,6 +494,70 @@ pub(crate) fn auto_code_from_input_file() -> Result<String, String> {
     ))
 }
 
+fn run_job_stage(
+    journal: &JobJournal,
+    resume: Option<&JobState>,
+    attempt: usize,
+    command: &str,
+    args: &[&str],
+) -> Result<String, String> {
+    if resume.is_some_and(|state| state.is_committed(attempt, command)) {
+        return Ok(format!("{} resumed: already committed", command));
+    }
+    journal.stage_started(attempt, command);
+    let result = run_code_subcommand_in_new_session(command, args, Some(journal.id()));
+    journal.stage_result(attempt, command, &result);
+    result
+}
+
+pub(crate) async fn resume_code_job(args: &[String]) -> Result<String, String> {
+    if args.len() > 1 {
+        return Err("resume accepts at most one <job_id>".to_string());
+    }
+    let root = crate::context::project_root();
+    let state = match args.first() {
+        Some(id) => JobJournal::open(&root, id)?.state()?,
+        None => jobs::latest_unfinished(&root, None)?.ok_or_else(|| {
+            format!(
+                "resume: no interrupted job under {}",
+                jobs::jobs_dir(&root).display()
+            )
+        })?,
+    };
+    if let Some(ok) = state.finished {
+        return Err(format!(
+            "resume: job {} already finished ({})",
+            state.id,
+            if ok { "ok" } else { "failed" }
+        ));
+    }
+    let header = format!(
+        "resumed {} job {} (attempt={}, last_stage={})",
+        state.kind.as_str(),
+        state.id,
+        state.attempt,
+        state.last_stage.as_deref().unwrap_or("-")
+    );
+    let out = match state.kind {
+        JobKind::Auto => {
+            let journal = JobJournal::open(&root, &state.id)?;
+            let message = state.message.clone().unwrap_or_default();
+            let result = run_auto_code_message(&journal, &message, Some(&state));
+            journal.finish(&result);
+            result?
+        }
+        JobKind::AutoFile => {
+            let journal = JobJournal::open(&root, &state.id)?;
+            let result = run_auto_code_from_input_file(&journal, Some(&state));
+            journal.finish(&result);
+            result?
+        }
+        JobKind::Impl => impl_code_draft_resuming(Some(state)).await?,
+        JobKind::Parallel => crate::parallel::run_parallel_build_code_resuming(Some(state)).await?,
+    };
+    Ok(format!("{} | {}", header, out))
+}
+
 fn auto_retry_max() -> usize {
     env::var("ORC_AUTO_RETRY_MAX")
         .ok()
@@ -514,10 +604,16 @@ fn drafts_resolved() -> bool {
     }
 }
 
-fn run_auto_retry_loop(mode: &str, message: Option<&str>, from_file: bool) -> Result<String, String> {
+fn run_auto_retry_loop(
+    journal: &JobJournal,
+    resume: Option<&JobState>,
+    mode: &str,
+    message: Option<&str>,
+    from_file: bool,
+) -> Result<String, String> {
     let max_retry = auto_retry_max();
     let sleep_sec = auto_retry_sleep_sec();
-    let mut attempt: usize = 0;
+    let mut attempt: usize = resume.map_or(0, |state| state.attempt.saturating_sub(1));
 
     loop {
         attempt += 1;
@@ -536,34 +632,34 @@ fn run_auto_retry_loop(mode: &str, message: Option<&str>, from_file: bool) -> Re
         stage_log.push(format!("attempt={}", attempt));
 
         if from_file {
-            match run_code_subcommand_in_new_session("create_input_md", &[]) {
+            match run_job_stage(journal, resume, attempt, "create_input_md", &[]) {
                 Ok(msg) => stage_log.push(msg),
                 Err(e) => stage_log.push(format!("create_input_md failed: {}", e)),
             }
-            match run_code_subcommand_in_new_session("add_code_plan", &["-f"]) {
+            match run_job_stage(journal, resume, attempt, "add_code_plan", &["-f"]) {
                 Ok(msg) => stage_log.push(msg),
                 Err(e) => stage_log.push(format!("add_code_plan failed: {}", e)),
             }
-            match run_code_subcommand_in_new_session("add_code_draft", &["-f"]) {
+            match run_job_stage(journal, resume, attempt, "add_code_draft", &["-f"]) {
                 Ok(msg) => stage_log.push(msg),
                 Err(e) => stage_log.push(format!("add_code_draft failed: {}", e)),
             }
         } else if let Some(msg) = message {
-            match run_code_subcommand_in_new_session("create_input_md", &[]) {
+            match run_job_stage(journal, resume, attempt, "create_input_md", &[]) {
                 Ok(out) => stage_log.push(out),
                 Err(e) => stage_log.push(format!("create_input_md failed: {}", e)),
             }
-            match run_code_subcommand_in_new_session("add_code_plan", &["-m", msg]) {
+            match run_job_stage(journal, resume, attempt, "add_code_plan", &["-m", msg]) {
                 Ok(out) => stage_log.push(out),
                 Err(e) => stage_log.push(format!("add_code_plan failed: {}", e)),
             }
-            match run_code_subcommand_in_new_session("add_code_draft", &["-m", msg]) {
+            match run_job_stage(journal, resume, attempt, "add_code_draft", &["-m", msg]) {
                 Ok(out) => stage_log.push(out),
                 Err(e) => stage_log.push(format!("add_code_draft failed: {}", e)),
             }
         }
 
-        let impl_status = match run_code_subcommand_in_new_session("impl_code_draft", &[]) {
+        let impl_status = match run_job_stage(journal, resume, attempt, "impl_code_draft", &[]) {
             Ok(msg) => {
                 stage_log.push(msg.clone());
                 true
@@ -573,7 +669,7 @@ fn run_auto_retry_loop(mode: &str, message: Option<&str>, from_file: bool) -> Re
                 false
             }
         };
-        let check_status = match run_code_subcommand_in_new_session("check_code_draft", &["-a"]) {
+        let check_status = match run_job_stage(journal, resume, attempt, "check_code_draft", &["-a"]) {
             Ok(msg) => {
                 stage_log.push(msg.clone());
                 true
@@ -610,13 +706,56 @@ fn run_auto_retry_loop(mode: &str, message: Option<&str>, from_file: bool) -> Re
 }
 
 pub(crate) async fn impl_code_draft() -> Result<String, String> {
+    let resume = jobs::latest_unfinished(&crate::context::project_root(), Some(JobKind::Impl))?;
+    impl_code_draft_resuming(resume).await
+}
+
+async fn impl_code_draft_resuming(resume: Option<JobState>) -> Result<String, String> {
+    let journal = JobJournal::create(&crate::context::project_root(), JobKind::Impl, None)?;
+    let result = run_impl_code_draft(&journal, resume).await;
+    journal.finish(&result);
+    result
+}
+
+async fn run_impl_code_draft(
+    journal: &JobJournal,
+    resume: Option<JobState>,
+) -> Result<String, String> {
     let mut plan = load_plan_doc()?;
     sync_plan_doc(&mut plan);
     let mut drafts = load_drafts_doc()?;
     sync_drafts_doc(&mut drafts);
 
-    // Recover stale state: if planned is empty but worked remains, move worked back to planned.
-    if plan.drafts.planned.is_empty() && (!plan.drafts.worked.is_empty() || !drafts.worked.is_empty()) {
+    if let Some(state) = resume.as_ref() {
+        // The interrupted job knows which worked items already finished; only re-queue the rest.
+        jobs::supersede(&crate::context::project_root(), state, journal);
+        let finished = state.tasks_in_state("complete");
+        let stranded: Vec<String> = plan
+            .drafts
+            .worked
+            .iter()
+            .chain(drafts.worked.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in stranded {
+            if finished.contains(&name) {
+                change_state_plan(&mut plan, &name, "worked", "complete")?;
+                change_state_drafts(&mut drafts, &name, "worked", "complete")?;
+                journal.task(&name, "complete", &format!("recovered from {}", state.id));
+            } else {
+                change_state_plan(&mut plan, &name, "worked", "planned")?;
+                change_state_drafts(&mut drafts, &name, "worked", "planned")?;
+                journal.task(&name, "planned", &format!("requeued from {}", state.id));
+            }
+        }
+        sync_plan_doc(&mut plan);
+        sync_drafts_doc(&mut drafts);
+        save_plan_doc(&plan)?;
+        save_drafts_doc(&drafts)?;
+    } else if plan.drafts.planned.is_empty()
+        && (!plan.drafts.worked.is_empty() || !drafts.worked.is_empty())
+    {
+        // Recover stale state without a journal: move worked back to planned.
         for name in plan.drafts.worked.clone() {
             if !plan.drafts.planned.iter().any(|v| v == &name) {
                 plan.drafts.planned.push(name);
@@ -639,6 +778,7 @@ pub(crate) async fn impl_code_draft() -> Result<String, String> {
         return Ok("impl_code_draft skipped: no drafts.yaml.planned item".to_string());
     }
 
+    journal.stage_started(0, "worked");
     let moved_to_worked = plan.drafts.planned.clone();
     for name in &moved_to_worked {
         change_state_plan(&mut plan, name, "planned", "worked")?;
@@ -648,6 +788,10 @@ pub(crate) async fn impl_code_draft() -> Result<String, String> {
     sync_drafts_doc(&mut drafts);
     save_plan_doc(&plan)?;
     save_drafts_doc(&drafts)?;
+    for name in &moved_to_worked {
+        journal.task(name, "worked", "");
+    }
+    journal.stage_result(0, "worked", &Ok(moved_to_worked.join(", ")));
 
     let worked_items: Vec<DraftItemDoc> = plan
         .drafts
@@ -659,8 +803,20 @@ pub(crate) async fn impl_code_draft() -> Result<String, String> {
         "parallel-start",
         &format!("parallel execution start: {} item(s)", worked_items.len()),
     );
-    let run_msg = match impl_code_draft_parallel(worked_items).await {
+    let satisfied: HashSet<String> = plan
+        .drafts
+        .complete
+        .iter()
+        .chain(drafts.complete.iter())
+        .cloned()
+        .collect();
+    let run_msg = match impl_code_draft_parallel(journal, worked_items, &satisfied).await {
         Ok(run) => {
+            for item in &mut drafts.draft {
+                if let Some(results) = run.verification.get(&item.name) {
+                    item.verification = results.clone();
+                }
+            }
             for name in &run.succeeded {
                 change_state_plan(&mut plan, name, "worked", "complete")?;
                 change_state_drafts(&mut drafts, name, "worked", "complete")?;
@@ -707,84 +863,50 @@ pub(crate) async fn impl_code_draft() -> Result<String, String> {
         }
     };
 
-    let check = check_code_draft(true)?;
-    Ok(format!("impl_code_draft completed | {} | {}", run_msg, check))
-}
-
-struct ImplRunResult {
-    succeeded: Vec<String>,
-    failed: Vec<(String, String)>,
+    journal.stage_started(0, "check_code_draft");
+    let check = check_code_draft(true);
+    journal.stage_result(0, "check_code_draft", &check);
+    Ok(format!("impl_code_draft completed | {} | {}", run_msg, check?))
 }
 
-async fn impl_code_draft_parallel(items: Vec<DraftItemDoc>) -> Result<ImplRunResult, String> {
-    if items.is_empty() {
-        return Ok(ImplRunResult {
-            succeeded: Vec::new(),
-            failed: Vec::new(),
-        });
-    }
-    let prompt_path = Path::new("assets")
-        .join("code")
-        .join("prompts")
-        .join("impl_code_draft.txt");
+async fn impl_code_draft_parallel(
+    journal: &JobJournal,
+    items: Vec<DraftItemDoc>,
+    satisfied: &HashSet<String>,
+) -> Result<crate::parallel::EngineRun, String> {
+    let prompt_path = crate::resolve_code_prompt_path("impl_code_draft.txt")?;
     let prompt_template = fs::read_to_string(&prompt_path)
-        .unwrap_or_else(|_| "impl_code_draft prompt\n- draft_item을 구현하고 제약 만족 여부를 보고한다.".to_string());
-    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(4));
-    let llm_timeout_sec = impl_draft_llm_timeout_sec();
-    let mut handles = Vec::new();
-    for item in items {
-        let permit_pool = semaphore.clone();
-        let prompt_template = prompt_template.clone();
-        handles.push(tokio::spawn(async move {
-            let _permit = permit_pool
-                .acquire_owned()
-                .await
-                .map_err(|e| format!("semaphore acquire failed: {}", e))?;
+        .map_err(|e| format!("failed to read {}: {}", prompt_path.display(), e))?;
+    let tasks = items
+        .into_iter()
+        .map(|mut item| {
+            item.verification.clear();
             let raw = serde_yaml::to_string(&item)
                 .map_err(|e| format!("failed to encode draft item {}: {}", item.name, e))?;
-            let prompt = format!(
-                "{}\n\n```yaml\n{}\n```\n\n위 draft_item을 구현하고 constraints 만족 여부를 마지막 줄에 `constraints: ok|fail`로 출력한다.",
-                prompt_template, raw
-            );
-            let name = item.name.clone();
-            let output = tokio::task::spawn_blocking(move || {
-                crate::run_codex_exec_capture_with_timeout(&prompt, llm_timeout_sec)
+            Ok(crate::parallel::EngineTask {
+                prompt: format!(
+                    "{}\n\n```yaml\n{}\n```\n\n위 draft_item을 구현하고 constraints 만족 여부를 마지막 줄에 `constraints: ok|fail`로 출력한다.",
+                    prompt_template, raw
+                ),
+                checks: item.check.iter().chain(item.constraints.iter()).cloned().collect(),
+                name: item.name,
+                depends_on: item.depends_on,
+                // `scope` is the draft item's declared file footprint.
+                touches: item.scope.iter().fold(Vec::new(), |mut touches, path| {
+                    let path = path.trim();
+                    if !path.is_empty() && !touches.iter().any(|t| t == path) {
+                        touches.push(path.to_string());
+                    }
+                    touches
+                }),
             })
-                .await
-                .map_err(|e| format!("spawn blocking join failed for {}: {}", name, e))??;
-            let tail = output.lines().last().unwrap_or("").to_ascii_lowercase();
-            if tail.contains("constraints: fail") {
-                return Err(format!("{}: constraints reported fail", item.name));
-            }
-            Ok::<String, String>(item.name)
-        }));
-    }
-    let mut done = Vec::new();
-    let mut failed: Vec<(String, String)> = Vec::new();
-    for handle in handles {
-        match handle
-            .await
-            .map_err(|e| format!("parallel task join failed: {}", e))?
-        {
-            Ok(name) => done.push(name),
-            Err(err) => {
-                let name = err
-                    .split_once(':')
-                    .map(|(left, _)| left.trim().to_string())
-                    .filter(|v| !v.is_empty())
-                    .unwrap_or_else(|| "unknown".to_string());
-                failed.push((name, err));
-            }
-        }
-    }
-    Ok(ImplRunResult {
-        succeeded: done,
-        failed,
-    })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    crate::parallel::run_tasks(journal, tasks, satisfied).await
 }
 
 fn write_feedback_md(summary: &str, detail: &str) -> Result<(), String> {
-    let mut body = fs::read_to_string("feedback.md").unwrap_or_else(|_| "# feedback\n".to_string());
+    let mut body = fs::read_to_string(crate::context::project_path("feedback.md")).unwrap_or_else(|_| "# feedback\n".to_string());
     if !body.starts_with("# feedback") {
         body = format!("# feedback\n\n{}", body);
     }
@@ -799,16 +921,20 @@ fn write_feedback_md(summary: &str, detail: &str) -> Result<(), String> {
         "\n## entry-{}\n- status: failed\n- summary: {}\n- detail: {}\n",
         ts, summary, detail
     ));
-    fs::write("feedback.md", body).map_err(|e| format!("failed to write feedback.md: {}", e))
+    fs::write(crate::context::project_path("feedback.md"), body).map_err(|e| format!("failed to write feedback.md: {}", e))
 }
 
 fn run_impl_code_draft_via_cli() -> Result<String, String> {
-    run_code_subcommand_in_new_session("impl_code_draft", &[])
+    run_code_subcommand_in_new_session("impl_code_draft", &[], None)
 }
 
-fn run_code_subcommand_in_new_session(command: &str, args: &[&str]) -> Result<String, String> {
-    if should_use_tmux_worker_pane() {
-        return run_code_subcommand_via_tmux_pane(command, args);
+fn run_code_subcommand_in_new_session(
+    command: &str,
+    args: &[&str],
+    parent_job: Option<&str>,
+) -> Result<String, String> {
+    if crate::terminal::worker_panes_enabled() {
+        return run_code_subcommand_in_pane(command, args, parent_job);
     }
     let exe = env::current_exe().map_err(|e| format!("failed to resolve current exe: {}", e))?;
     debug_log_auto_stage(
@@ -816,14 +942,15 @@ fn run_code_subcommand_in_new_session(command: &str, args: &[&str]) -> Result<St
         &format!("new session start: {} {}", command, args.join(" ")),
     );
     let mut cmd = Command::new(exe);
-    cmd.arg(command);
+    cmd.arg(command).current_dir(crate::context::project_root());
     for arg in args {
         cmd.arg(arg);
     }
-    let mut child = cmd
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
+    if let Some(parent) = parent_job {
+        cmd.env(crate::jobs::PARENT_JOB_ENV, parent);
+    }
+    cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+    let (mut child, _tracked) = crate::process_group::spawn_tracked(&mut cmd)
         .map_err(|e| format!("failed to execute {}: {}", command, e))?;
     let timeout_sec = code_subcommand_timeout_sec();
     let started = Instant::now();
@@ -832,8 +959,7 @@ fn run_code_subcommand_in_new_session(command: &str, args: &[&str]) -> Result<St
             Ok(Some(status)) => break status,
             Ok(None) => {
                 if started.elapsed() >= Duration::from_secs(timeout_sec) {
-                    let _ = child.kill();
-                    let _ = child.wait();
+                    crate::process_group::kill_child_tree(&mut child);
                     debug_log_auto_stage(
                         "session",
                         &format!(
@@ -849,8 +975,7 @@ fn run_code_subcommand_in_new_session(command: &str, args: &[&str]) -> Result<St
                 thread::sleep(Duration::from_millis(250));
             }
             Err(e) => {
-                let _ = child.kill();
-                let _ = child.wait();
+                crate::process_group::kill_child_tree(&mut child);
                 return Err(format!("failed while waiting {}: {}", command, e));
             }
         }
@@ -871,36 +996,30 @@ fn run_code_subcommand_in_new_session(command: &str, args: &[&str]) -> Result<St
     }
 }
 
-fn should_use_tmux_worker_pane() -> bool {
-    if !env_flag_true("ORC_USE_TMUX_PANES") {
-        return false;
-    }
-    env::var("TMUX")
-        .map(|v| !v.trim().is_empty())
-        .unwrap_or(false)
-}
-
-fn env_flag_true(name: &str) -> bool {
-    match env::var(name) {
-        Ok(v) => {
-            let lowered = v.trim().to_ascii_lowercase();
-            lowered == "1" || lowered == "true" || lowered == "yes" || lowered == "on"
-        }
-        Err(_) => false,
-    }
-}
-
 fn quote_sh(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\"'\"'"))
 }
 
-fn run_code_subcommand_via_tmux_pane(command: &str, args: &[&str]) -> Result<String, String> {
+fn run_code_subcommand_in_pane(
+    command: &str,
+    args: &[&str],
+    parent_job: Option<&str>,
+) -> Result<String, String> {
     let exe = env::current_exe().map_err(|e| format!("failed to resolve current exe: {}", e))?;
-    let cwd = env::current_dir().map_err(|e| format!("failed to read cwd: {}", e))?;
-    let runtime = Path::new(".project").join("runtime");
+    run_program_in_pane(&exe, command, args, parent_job)
+}
+
+fn run_program_in_pane(
+    exe: &Path,
+    command: &str,
+    args: &[&str],
+    parent_job: Option<&str>,
+) -> Result<String, String> {
+    let cwd = crate::context::project_root();
+    let runtime = crate::context::project_dir().join("runtime");
     fs::create_dir_all(&runtime)
         .map_err(|e| format!("failed to create {}: {}", runtime.display(), e))?;
-    let token = format!("{}-{}-{}", now_unix_ts(), std::process::id(), normalize_feature_key(command));
+    let token = format!("{}-{}-{}", crate::now_unix_secs(), std::process::id(), normalize_feature_key(command));
     let script_path = runtime.join(format!("tmux-subcmd-{}.sh", token));
     let stdout_path = runtime.join(format!("tmux-subcmd-{}.stdout.log", token));
     let stderr_path = runtime.join(format!("tmux-subcmd-{}.stderr.log", token));
@@ -915,14 +1034,19 @@ fn run_code_subcommand_via_tmux_pane(command: &str, args: &[&str]) -> Result<Str
     } else {
         format!(" {}", quoted_args.join(" "))
     };
+    let parent_export = parent_job.map_or(String::new(), |parent| {
+        format!("export {}={}\n", crate::jobs::PARENT_JOB_ENV, quote_sh(parent))
+    });
     let script = format!(
         "#!/usr/bin/env bash\n\
 set +e\n\
 cd {cwd}\n\
+{parent_export}\
 echo \"[orc-worker] start: {command}{args}\"\n\
 echo \"[orc-worker] cwd: {cwd_display}\"\n\
 {exe} {command}{args} > >(tee {stdout}) 2> >(tee {stderr} >&2)\n\
 status=$?\n\
+wait\n\
 printf \"%s\" \"$status\" > {code}\n",
         cwd = quote_sh(&cwd.display().to_string()),
         cwd_display = cwd.display(),
@@ -935,23 +1059,23 @@ printf \"%s\" \"$status\" > {code}\n",
     );
     fs::write(&script_path, script)
         .map_err(|e| format!("failed to write {}: {}", script_path.display(), e))?;
-    let parent_pane = crate::tmux::current_pane_id().ok();
     debug_log_auto_stage(
         "session",
         &format!("worker pane start: {} {}", command, args.join(" ")),
     );
-    let pane_id = crate::tmux::split_window_run(&format!("bash {}", quote_sh(&script_path.display().to_string())))
-        .map_err(|e| format!("failed to spawn tmux worker pane: {}", e))?;
-    let _ = crate::tmux::rename_pane(&pane_id, &format!("orc-{}", command));
+    let terminal = crate::terminal::backend();
+    let pane = terminal
+        .open(
+            &format!("orc-{}", command),
+            &format!("bash {}", quote_sh(&script_path.display().to_string())),
+        )
+        .map_err(|e| format!("failed to spawn {} worker pane: {}", terminal.name(), e))?;
     let timeout_sec = code_subcommand_timeout_sec();
     let started = Instant::now();
     while !code_path.exists() {
         if started.elapsed() >= Duration::from_secs(timeout_sec) {
-            let _ = crate::tmux::display_message(
-                parent_pane.as_deref().unwrap_or(""),
-                &format!("orc worker timeout: {}", command),
-            );
-            let _ = crate::tmux::kill_pane(&pane_id);
+            terminal.notify(&format!("orc worker timeout: {}", command));
+            terminal.kill(&pane);
             return Err(format!("{} failed: timeout after {}s", command, timeout_sec));
         }
         thread::sleep(Duration::from_millis(200));
@@ -960,15 +1084,12 @@ printf \"%s\" \"$status\" > {code}\n",
         .map_err(|e| format!("failed to read {}: {}", code_path.display(), e))?;
     let code = code_raw.trim().parse::<i32>().unwrap_or(1);
     let stderr = fs::read_to_string(&stderr_path).unwrap_or_default();
-    let _ = crate::tmux::kill_pane(&pane_id);
-    if let Some(parent) = parent_pane.as_deref() {
-        let status_msg = if code == 0 {
-            format!("orc worker done: {}", command)
-        } else {
-            format!("orc worker failed: {} (code={})", command, code)
-        };
-        let _ = crate::tmux::display_message(parent, &status_msg);
-    }
+    terminal.close(&pane);
+    terminal.notify(&if code == 0 {
+        format!("orc worker done: {}", command)
+    } else {
+        format!("orc worker failed: {} (code={})", command, code)
+    });
     if code == 0 {
         debug_log_auto_stage("session", &format!("worker pane done: {}", command));
         Ok(format!("{} completed", command))
@@ -995,16 +1116,8 @@ fn code_subcommand_timeout_sec() -> u64 {
         .unwrap_or(CODE_SUBCOMMAND_TIMEOUT_SEC)
 }
 
-fn impl_draft_llm_timeout_sec() -> u64 {
-    env::var("ORC_IMPL_DRAFT_LLM_TIMEOUT_SEC")
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
-        .filter(|v| *v > 0)
-        .unwrap_or(IMPL_DRAFT_LLM_TIMEOUT_SEC)
-}
-
 fn build_input_md_auto() -> Result<String, String> {
-    let project_md = fs::read_to_string(crate::PROJECT_MD_PATH)
+    let project_md = fs::read_to_string(crate::project_md_file())
         .map_err(|e| format!("failed to read {}: {}", crate::PROJECT_MD_PATH, e))?;
     let plan_path = plan_yaml_path()?;
     let plan_yaml = fs::read_to_string(&plan_path)
@@ -1030,9 +1143,9 @@ fn build_input_md_auto() -> Result<String, String> {
     if body.trim().is_empty() {
         return Err("build_input_md_auto failed: empty input.md body".to_string());
     }
-    fs::write(crate::INPUT_MD_PATH, format!("{}\n", body))
+    fs::write(crate::context::project_path(crate::INPUT_MD_PATH), format!("{}\n", body))
         .map_err(|e| format!("failed to write {}: {}", crate::INPUT_MD_PATH, e))?;
-    let parsed = parse_input_md_objects(Path::new(crate::INPUT_MD_PATH))?;
+    let parsed = parse_input_md_objects(&crate::context::project_path(crate::INPUT_MD_PATH))?;
     if !parsed.is_empty() {
         let mut rebuilt = String::new();
         for obj in &parsed {
@@ -1045,7 +1158,7 @@ fn build_input_md_auto() -> Result<String, String> {
             }
             rebuilt.push('\n');
         }
-        fs::write(crate::INPUT_MD_PATH, rebuilt)
+        fs::write(crate::context::project_path(crate::INPUT_MD_PATH), rebuilt)
             .map_err(|e| format!("failed to write {}: {}", crate::INPUT_MD_PATH, e))?;
     }
     if parsed.is_empty() {
@@ -1061,37 +1174,38 @@ pub(crate) fn check_code_draft(_auto_yes: bool) -> Result<String, String> {
     let debug_enabled = crate::load_app_config()
         .as_ref()
         .is_none_or(crate::config::AppConfig::debug_enabled);
-    let mut debug_pane = String::new();
-    if debug_enabled && env_flag_true("ORC_ENABLE_CHECK_DEBUG_PANE") {
+    let terminal = crate::terminal::backend();
+    let mut debug_pane = None;
+    if debug_enabled && crate::env_flag_true("ORC_ENABLE_CHECK_DEBUG_PANE") {
         let debug_cmd = "mkdir -p .project/reference && touch .project/reference/check-code.log && tail -n 200 -f .project/reference/check-code.log";
-        if let Ok(pane_id) = crate::tmux::split_window_run(debug_cmd) {
-            let _ = crate::tmux::rename_pane(&pane_id, "check-code-debug");
-            debug_pane = pane_id;
-        }
+        debug_pane = terminal.open("check-code-debug", debug_cmd).ok();
     }
     let drafts = load_drafts_doc()?;
     let names: HashSet<String> = drafts.draft.iter().map(|v| v.name.clone()).collect();
     let mut list: Vec<String> = names.into_iter().collect();
     list.sort();
     let follow = crate::run_check_code_after_draft_changes(&list, "check_code_draft")?;
-    let test_result = match crate::test_command() {
-        Ok(v) => v,
-        Err(e) => format!("test failed: {}", e),
-    };
-    let report = Path::new("report.md");
-    let issues = collect_check_draft_issues(&follow, &test_result);
+    let cwd = crate::context::project_root();
+    let test_runs = crate::test_runner::run_project_tests(&cwd);
+    let test_result = summarize_test_runs(&test_runs);
+    let report = &crate::context::project_path("report.md");
+    let mut issues = collect_check_draft_issues(&follow, &test_result);
+    issues.extend(collect_test_failure_issues(&test_runs, &draft_test_hints(&drafts)));
     crate::append_spec_checkpoint_issues("check_code_draft", &issues)?;
     let body = render_check_report_from_template(
         &list,
         &follow,
         &test_result,
-        if debug_pane.is_empty() {
-            "(not opened)"
-        } else {
-            &debug_pane
-        },
+        &test_runs,
+        &debug_pane
+            .as_ref()
+            .map_or_else(|| "(not opened)".to_string(), |pane| pane.describe()),
         &issues,
     )?;
+    // A tmux pane stays for the user to read; a headless one has its log.
+    if let Some(pane) = debug_pane.as_ref().filter(|_| !terminal.interactive()) {
+        terminal.close(pane);
+    }
     fs::write(report, body).map_err(|e| format!("failed to write {}: {}", report.display(), e))?;
     Ok(format!(
         "check_code_draft completed: report.md generated | reference={}",
@@ -1323,6 +1437,7 @@ fn infer_draft_item_with_llm(
         tasks,
         constraints,
         check,
+        verification: Vec::new(),
     }
 }
 
@@ -1442,7 +1557,7 @@ fn apply_project_info_overrides(
     path: &str,
     spec: &str,
 ) -> Result<(), String> {
-    let project_path = Path::new(crate::PROJECT_MD_PATH);
+    let project_path = &crate::project_md_file();
     let raw = fs::read_to_string(project_path)
         .map_err(|e| format!("failed to read {}: {}", project_path.display(), e))?;
     let mut next = raw;
@@ -1504,7 +1619,7 @@ fn debug_log_auto_stage(stage: &str, message: &str) {
         return;
     }
     println!("[auto:{}] {}", stage, message);
-    let project_dir = Path::new(".project");
+    let project_dir = crate::context::project_dir();
     if !project_dir.exists() {
         return;
     }
@@ -1735,15 +1850,15 @@ fn ask_yes_no(prompt: &str) -> Result<bool, String> {
 }
 
 fn is_current_dir_empty() -> Result<bool, String> {
-    let cwd = env::current_dir().map_err(|e| format!("failed to read cwd: {}", e))?;
+    let cwd = crate::context::project_root();
     let mut entries = fs::read_dir(&cwd)
         .map_err(|e| format!("failed to read {}: {}", cwd.display(), e))?;
     Ok(entries.next().is_none())
 }
 
 fn ensure_project_dir() -> Result<PathBuf, String> {
-    let dir = Path::new(".project");
-    fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let dir = crate::context::project_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
     Ok(dir.to_path_buf())
 }
 
@@ -1859,15 +1974,8 @@ fn change_state_drafts(
     Ok(())
 }
 
-fn now_unix_ts() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|v| v.as_secs())
-        .unwrap_or(0)
-}
-
 fn validate_scenario_file() -> Result<(), String> {
-    let path = Path::new(".project").join("scenario.md");
+    let path = crate::context::project_dir().join("scenario.md");
     if !path.exists() {
         return Err(format!("scenario validation failed: missing {}", path.display()));
     }
@@ -1895,7 +2003,7 @@ fn validate_scenario_file() -> Result<(), String> {
 }
 
 fn ensure_default_scenario_file() -> Result<(), String> {
-    let path = Path::new(".project").join("scenario.md");
+    let path = crate::context::project_dir().join("scenario.md");
     if path.exists() {
         return Ok(());
     }
@@ -1966,8 +2074,12 @@ fn extract_domain_subsection_items(project_md: &str, domain: &str, subsection: &
 mod tests {
     use super::{
         change_state_plan, extract_domain_subsection_items, extract_domains_from_project_md,
-        CodePlanDoc,
+        run_program_in_pane, CodePlanDoc,
     };
+    use crate::context::{self, ProjectContext};
+    use crate::terminal::{self, RecordingTerminal, TerminalEvent};
+    use std::path::Path;
+    use std::sync::Arc;
 
     #[test]
     fn extract_domains_from_project_md_reads_new_domain_headers() {
@@ -2024,10 +2136,46 @@ name : sample
         assert_eq!(doc.drafts.complete, vec!["ui".to_string()]);
     }
 
+    #[test]
+    fn worker_pane_reports_exit_code_through_the_terminal() {
+        let root = std::env::temp_dir().join(format!("orc-worker-pane-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).expect("create root");
+        let recorder = Arc::new(RecordingTerminal::running());
+        let _guard = terminal::override_backend(recorder.clone());
+        let (ok, failed) = context::sync_scope(ProjectContext::new(&root), || {
+            (
+                run_program_in_pane(Path::new("true"), "check_code", &[], None),
+                run_program_in_pane(Path::new("false"), "impl_code_draft", &["ui"], None),
+            )
+        });
+        assert_eq!(ok, Ok("check_code completed".to_string()));
+        assert_eq!(failed, Err("impl_code_draft failed: code=1".to_string()));
+
+        let events = recorder.events();
+        assert_eq!(events.len(), 6);
+        assert!(matches!(
+            &events[0],
+            TerminalEvent::Open { title, command }
+                if title == "orc-check_code" && command.starts_with("bash ")
+        ));
+        assert_eq!(events[1], TerminalEvent::Close("rec:0".to_string()));
+        assert_eq!(
+            events[2],
+            TerminalEvent::Notify("orc worker done: check_code".to_string())
+        );
+        assert_eq!(events[4], TerminalEvent::Close("rec:3".to_string()));
+        assert_eq!(
+            events[5],
+            TerminalEvent::Notify("orc worker failed: impl_code_draft (code=1)".to_string())
+        );
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
 }
 
 fn sync_plan_doc(doc: &mut CodePlanDoc) {
-    if let Ok(project_md) = fs::read_to_string(crate::PROJECT_MD_PATH) {
+    if let Ok(project_md) = fs::read_to_string(crate::project_md_file()) {
         for domain in extract_domains_from_project_md(&project_md) {
             if !doc.domains.iter().any(|v| v == &domain) {
                 doc.domains.push(domain);
@@ -2084,14 +2232,11 @@ fn change_state_plan(
 }
 
 fn infer_plan_items_with_llm() -> Result<Vec<String>, String> {
-    let md = fs::read_to_string(crate::PROJECT_MD_PATH)
+    let md = fs::read_to_string(crate::project_md_file())
         .map_err(|e| format!("failed to read {}: {}", crate::PROJECT_MD_PATH, e))?;
-    let prompt_path = Path::new("assets")
-        .join("presets")
-        .join("code")
-        .join("prompts")
-        .join("add_code_plan.txt");
-    let prompt_template = fs::read_to_string(&prompt_path).unwrap_or_else(|_| {
+    let prompt_template = crate::resolve_code_prompt_path("add_code_plan.txt")
+        .and_then(|path| fs::read_to_string(&path).map_err(|e| e.to_string()))
+        .unwrap_or_else(|_| {
         "project.md를 읽고 planned 후보를 YAML로 출력해.\nplanned:\n  - item".to_string()
     });
     let prompt = format!(
@@ -2164,7 +2309,7 @@ fn replace_domains_section(project_md: &str, domains_body: &str) -> String {
 }
 
 fn write_project_md(body: &str) -> Result<(), String> {
-    let project_path = Path::new(crate::PROJECT_MD_PATH);
+    let project_path = &crate::project_md_file();
     if let Some(parent) = project_path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
@@ -2174,16 +2319,16 @@ fn write_project_md(body: &str) -> Result<(), String> {
 }
 
 fn enforce_project_md_primary_path() -> Result<(), String> {
-    let primary = Path::new(".project");
+    let primary = crate::context::project_dir();
     if !primary.exists() {
-        fs::create_dir_all(primary)
+        fs::create_dir_all(&primary)
             .map_err(|e| format!("failed to create {}: {}", primary.display(), e))?;
     }
     Ok(())
 }
 
 fn ensure_project_memo_initialized() -> Result<(), String> {
-    let memo_path = Path::new(".project").join("memo.md");
+    let memo_path = crate::context::project_dir().join("memo.md");
     if memo_path.exists() {
         return Ok(());
     }
@@ -2324,7 +2469,7 @@ fn read_code_template(file_name: &str) -> Result<String, String> {
 }
 
 fn ensure_project_md_initialized() -> Result<(), String> {
-    let path = Path::new(crate::PROJECT_MD_PATH);
+    let path = &crate::project_md_file();
     if path.exists() {
         return Ok(());
     }
@@ -2351,7 +2496,7 @@ fn ensure_drafts_yaml_initialized() -> Result<(), String> {
 }
 
 fn ensure_project_reference_dir() -> Result<PathBuf, String> {
-    let dir = Path::new(".project").join("reference");
+    let dir = crate::context::project_dir().join("reference");
     fs::create_dir_all(&dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
     Ok(dir)
 }
@@ -2369,21 +2514,87 @@ fn collect_check_draft_issues(follow: &str, test_result: &str) -> Vec<String> {
     issues
 }
 
+fn summarize_test_runs(runs: &[crate::test_runner::TestRun]) -> String {
+    use crate::test_runner::TestStatus;
+    if runs.is_empty() {
+        return "test skipped: no test runner detected".to_string();
+    }
+    let failed = runs
+        .iter()
+        .filter(|run| run.status != TestStatus::Passed)
+        .count();
+    if failed == 0 {
+        format!("test completed: {} runner(s) passed", runs.len())
+    } else {
+        format!("test failed: {}/{} runner(s) failed", failed, runs.len())
+    }
+}
+
+fn draft_test_hints(drafts: &CodeDraftsDoc) -> Vec<crate::test_runner::DraftHint> {
+    drafts
+        .draft
+        .iter()
+        .map(|item| {
+            let mut keys = item.scope.clone();
+            keys.extend(item.check.iter().chain(item.constraints.iter()).filter_map(|raw| {
+                match crate::verify::parse_check(raw) {
+                    Some(crate::verify::Check::Test(name)) => Some(name),
+                    Some(crate::verify::Check::Symbol { name, .. }) => Some(name),
+                    _ => None,
+                }
+            }));
+            crate::test_runner::DraftHint {
+                name: item.name.clone(),
+                keys,
+            }
+        })
+        .collect()
+}
+
+fn collect_test_failure_issues(
+    runs: &[crate::test_runner::TestRun],
+    hints: &[crate::test_runner::DraftHint],
+) -> Vec<String> {
+    runs.iter()
+        .flat_map(|run| run.failures.iter())
+        .map(|failure| {
+            let owners = crate::test_runner::attribute_failure(failure, hints);
+            format!(
+                "test failure [{}] {} -> draft: {}{}",
+                failure.target,
+                failure.name,
+                if owners.is_empty() {
+                    "(unmapped)".to_string()
+                } else {
+                    owners.join(", ")
+                },
+                if failure.detail.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", failure.detail)
+                }
+            )
+        })
+        .collect()
+}
+
 fn render_check_report_from_template(
     targets: &[String],
     follow: &str,
     test_result: &str,
+    test_runs: &[crate::test_runner::TestRun],
     debug_pane: &str,
     issues: &[String],
 ) -> Result<String, String> {
     let template = read_code_template("report.md")?;
-    let implementation_lines = [
+    let mut implementation_lines = vec![
         format!("- targets: {}", targets.join(", ")),
         format!("- check_followup: {}", follow),
         format!("- test: {}", test_result),
-        format!("- debug_pane: {}", debug_pane),
-    ]
-    .join("\n");
+    ];
+    implementation_lines.extend(test_runs.iter().map(|run| format!("  - {}", run.summary())));
+    implementation_lines.push(format!("- debug_pane: {}", debug_pane));
+    let implementation_lines = implementation_lines.join("\n");
     let issues_block = if issues.is_empty() {
         "- 없음".to_string()
     } else {
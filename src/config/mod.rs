@@ -13,9 +13,16 @@ pub struct KeymapConfig {
     pub run_parallel: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct AiConfig {
     pub model: Option<String>,
+    pub backend: Option<String>,
+    pub endpoint: Option<String>,
+    pub api_key_env: Option<String>,
+    pub fake_response: Option<String>,
+    pub fixture_dir: Option<String>,
+    pub cassette: Option<String>,
+    pub cassette_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -23,12 +30,16 @@ pub struct AppConfig {
     pub profile: Option<String>,
     pub max_parallel: Option<usize>,
     pub timeout_sec: Option<u64>,
+    pub test_timeout_sec: Option<u64>,
     pub max_read_time: Option<u64>,
+    pub heartbeat_interval: Option<u64>,
     pub auto_yes: Option<bool>,
     pub dangerous_bypass: Option<bool>,
     pub debug: Option<bool>,
     pub draft_retry_on_fail: Option<bool>,
     pub llm_retry_count: Option<u32>,
+    pub worktree_isolation: Option<bool>,
+    pub touches_revert: Option<bool>,
     pub keymap: Option<KeymapConfig>,
     pub ai: Option<AiConfig>,
     pub performance: Option<PerformanceConfig>,
@@ -41,6 +52,16 @@ impl AppConfig {
         serde_yaml::from_str(&raw).map_err(|e| format!("failed to parse yaml: {}", e))
     }
 
+    // Settings that load fine on their own but cannot work together.
+    pub fn validate_parallel(&self) -> Result<(), String> {
+        // Shared-directory runs only fingerprint files, so there is nothing
+        // to revert outside-touch edits from.
+        if self.touches_revert_enabled() && !self.worktree_isolation_enabled() {
+            return Err("touches_revert: true requires worktree_isolation: true".to_string());
+        }
+        Ok(())
+    }
+
     pub fn default_max_parallel(&self) -> usize {
         self.max_parallel
             .or_else(|| self.performance.as_ref().and_then(|v| v.max_parallel))
@@ -53,10 +74,18 @@ impl AppConfig {
             .unwrap_or(300)
     }
 
+    pub fn test_timeout_sec(&self) -> u64 {
+        self.test_timeout_sec.unwrap_or(1800).max(1)
+    }
+
     pub fn max_read_time_sec(&self) -> u64 {
         self.max_read_time.unwrap_or(3)
     }
 
+    pub fn heartbeat_interval_sec(&self) -> u64 {
+        self.heartbeat_interval.unwrap_or(15).max(1)
+    }
+
     pub fn run_parallel_key(&self) -> &str {
         self.keymap
             .as_ref()
@@ -84,6 +113,14 @@ impl AppConfig {
         self.llm_retry_count.unwrap_or(2)
     }
 
+    pub fn worktree_isolation_enabled(&self) -> bool {
+        self.worktree_isolation.unwrap_or(true)
+    }
+
+    pub fn touches_revert_enabled(&self) -> bool {
+        self.touches_revert.unwrap_or(false)
+    }
+
     pub fn default_profile_name(&self) -> &str {
         self.profile
             .as_deref()
@@ -92,4 +129,19 @@ impl AppConfig {
             .unwrap_or("code")
     }
 
+    pub fn llm_backend_name(&self) -> String {
+        let ai = self.ai.as_ref();
+        if let Some(backend) = ai
+            .and_then(|a| a.backend.as_deref())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            return backend.to_ascii_lowercase();
+        }
+        match ai.and_then(|a| a.model.as_deref()).map(str::trim) {
+            Some(model) if model.eq_ignore_ascii_case("claude") => "claude-cli".to_string(),
+            _ => "codex".to_string(),
+        }
+    }
+
 }
@@ -0,0 +1,125 @@
+use std::env;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::config::AppConfig;
+use crate::process_group::ProcessSet;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ProjectContext {
+    pub(crate) root: PathBuf,
+    pub(crate) config: Option<Arc<AppConfig>>,
+    pub(crate) processes: Arc<ProcessSet>,
+}
+
+impl ProjectContext {
+    pub(crate) fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            config: crate::load_app_config().map(Arc::new),
+            processes: Arc::new(ProcessSet::default()),
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: ProjectContext;
+}
+
+pub(crate) async fn scope<F: Future>(ctx: ProjectContext, fut: F) -> F::Output {
+    CURRENT.scope(ctx, fut).await
+}
+
+pub(crate) fn inherit<F: Future>(fut: F) -> impl Future<Output = F::Output> {
+    let ctx = current();
+    async move {
+        match ctx {
+            Some(ctx) => scope(ctx, fut).await,
+            None => fut.await,
+        }
+    }
+}
+
+pub(crate) fn sync_scope<R>(ctx: ProjectContext, f: impl FnOnce() -> R) -> R {
+    CURRENT.sync_scope(ctx, f)
+}
+
+pub(crate) async fn spawn_blocking<R: Send + 'static>(
+    f: impl FnOnce() -> R + Send + 'static,
+) -> Result<R, String> {
+    let ctx = current();
+    let guard = ctx.as_ref().map(|ctx| ctx.processes.enter_blocking());
+    tokio::task::spawn_blocking(move || {
+        let _guard = guard;
+        match ctx {
+            Some(ctx) => sync_scope(ctx, f),
+            None => f(),
+        }
+    })
+    .await
+    .map_err(|e| format!("blocking task failed: {}", e))
+}
+
+pub(crate) fn current() -> Option<ProjectContext> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+pub(crate) fn current_config() -> Option<Option<AppConfig>> {
+    CURRENT.try_with(|ctx| ctx.config.as_deref().cloned()).ok()
+}
+
+pub(crate) fn project_root() -> PathBuf {
+    CURRENT
+        .try_with(|ctx| ctx.root.clone())
+        .ok()
+        .or_else(|| env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+pub(crate) fn project_dir() -> PathBuf {
+    project_root().join(".project")
+}
+
+pub(crate) fn project_path(rel: impl AsRef<Path>) -> PathBuf {
+    project_root().join(rel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_context_overrides_cwd_per_task() {
+        let runtime = tokio::runtime::Runtime::new().expect("runtime");
+        let a = PathBuf::from("/tmp/orc_ctx_a");
+        let b = PathBuf::from("/tmp/orc_ctx_b");
+        let ctx_a = ProjectContext {
+            root: a.clone(),
+            config: None,
+            processes: Arc::default(),
+        };
+        let ctx_b = ProjectContext {
+            root: b.clone(),
+            config: None,
+            processes: Arc::default(),
+        };
+        let (left, right) = runtime.block_on(async {
+            let left = tokio::spawn(scope(ctx_a, async {
+                tokio::task::yield_now().await;
+                project_dir()
+            }));
+            let right = tokio::spawn(scope(ctx_b, async {
+                spawn_blocking(project_root).await.expect("join blocking")
+            }));
+            (
+                left.await.expect("join left"),
+                right.await.expect("join right"),
+            )
+        });
+        assert_eq!(left, a.join(".project"));
+        assert_eq!(right, b);
+        assert!(current().is_none());
+        assert_eq!(project_path("report.md").file_name().unwrap(), "report.md");
+    }
+}
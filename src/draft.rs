@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 use std::fs;
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -28,6 +28,8 @@ pub(crate) struct DraftTask {
     pub(crate) touches: Vec<String>,
     #[serde(default)]
     pub(crate) contracts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) verification: Vec<crate::verify::ConstraintResult>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -141,7 +143,7 @@ fn append_draft_runtime_log(debug_enabled: bool, feature_name: &str, stage: &str
     if !debug_enabled {
         return;
     }
-    let runtime_dir = Path::new(".project").join("runtime");
+    let runtime_dir = crate::context::project_dir().join("runtime");
     if fs::create_dir_all(&runtime_dir).is_err() {
         return;
     }
@@ -354,13 +356,13 @@ fn second_pass_check(
 }
 
 pub(crate) fn draft_create() -> Result<String, String> {
-    let _ = crate::sync_project_tasks_list_from_project_md(Path::new("."))?;
-    let project_root = Path::new(".");
-    let path = crate::resolve_drafts_list_path(project_root)?;
+    let project_root = crate::context::project_root();
+    let _ = crate::sync_project_tasks_list_from_project_md(&project_root)?;
+    let path = crate::resolve_drafts_list_path(&project_root)?;
     let preflight_msg = crate::preflight_draft_create(&path)?;
     let mut doc = crate::load_drafts_list(&path)?;
-    crate::sync_draft_state_doc(project_root, &mut doc);
-    crate::save_drafts_list_primary(project_root, &doc)?;
+    crate::sync_draft_state_doc(&project_root, &mut doc);
+    crate::save_drafts_list_primary(&project_root, &doc)?;
     let project_md_path = crate::resolve_project_md_path_for_flow();
     let project_md = fs::read_to_string(&project_md_path)
         .map_err(|e| format!("failed to read {}: {}", project_md_path.display(), e))?;
@@ -414,9 +416,9 @@ pub(crate) fn draft_create() -> Result<String, String> {
                 let _ = write_draft_failure_report(&feature, &e);
                 next_failures.push((feature, e.clone()));
                 if !retry_on_fail {
-                    crate::sync_draft_state_doc(project_root, &mut doc);
+                    crate::sync_draft_state_doc(&project_root, &mut doc);
                     let _ =
-                        crate::save_drafts_list_primary(project_root, &doc);
+                        crate::save_drafts_list_primary(&project_root, &doc);
                     return Err(format!("create_code_draft failed at `{}`: {}", next_failures[0].0, e));
                 }
             } else {
@@ -444,8 +446,8 @@ pub(crate) fn draft_create() -> Result<String, String> {
                     next_failures.push((feature, e));
                 }
             }
-            crate::sync_draft_state_doc(project_root, &mut doc);
-            let _ = crate::save_drafts_list_primary(project_root, &doc);
+            crate::sync_draft_state_doc(&project_root, &mut doc);
+            let _ = crate::save_drafts_list_primary(&project_root, &doc);
         }
         if next_failures.is_empty() {
             failures.clear();
@@ -453,13 +455,13 @@ pub(crate) fn draft_create() -> Result<String, String> {
         }
         failures = next_failures;
         if attempt < max_attempt {
-            crate::sync_draft_state_doc(project_root, &mut doc);
-            let _ = crate::save_drafts_list_primary(project_root, &doc);
+            crate::sync_draft_state_doc(&project_root, &mut doc);
+            let _ = crate::save_drafts_list_primary(&project_root, &doc);
             attempt_targets = doc.draft_state.pending.clone();
         }
     }
-    crate::sync_draft_state_doc(project_root, &mut doc);
-    crate::save_drafts_list_primary(project_root, &doc)?;
+    crate::sync_draft_state_doc(&project_root, &mut doc);
+    crate::save_drafts_list_primary(&project_root, &doc)?;
     if !failures.is_empty() {
         let pending_names: Vec<String> = failures.into_iter().map(|(name, _)| name).collect();
         return Err(format!(
@@ -486,7 +488,7 @@ pub(crate) fn draft_add(feature_name: &str, request: Option<String>) -> Result<S
     if request_text.trim().is_empty() {
         return Err("draft-add requires non-empty request".to_string());
     }
-    let project_md = fs::read_to_string(crate::PROJECT_MD_PATH)
+    let project_md = fs::read_to_string(crate::project_md_file())
         .map_err(|e| format!("failed to read {}: {}", crate::PROJECT_MD_PATH, e))?;
     let project_info = crate::extract_project_info(&project_md);
     let project_rules = crate::extract_project_rules(&project_md);
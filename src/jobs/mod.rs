@@ -0,0 +1,478 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum JobKind {
+    Auto,
+    AutoFile,
+    Impl,
+    Parallel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum JobEvent {
+    Started {
+        kind: JobKind,
+        #[serde(default)]
+        message: Option<String>,
+        #[serde(default)]
+        pid: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        parent: Option<String>,
+    },
+    StageStarted {
+        attempt: usize,
+        stage: String,
+    },
+    StageCommitted {
+        attempt: usize,
+        stage: String,
+        #[serde(default)]
+        detail: String,
+    },
+    StageFailed {
+        attempt: usize,
+        stage: String,
+        #[serde(default)]
+        detail: String,
+    },
+    Task {
+        task: String,
+        state: String,
+        #[serde(default)]
+        detail: String,
+    },
+    Finished {
+        ok: bool,
+        #[serde(default)]
+        detail: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JobLine {
+    ts: u64,
+    #[serde(flatten)]
+    event: JobEvent,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct JobJournal {
+    id: String,
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct JobState {
+    pub(crate) id: String,
+    pub(crate) kind: JobKind,
+    pub(crate) message: Option<String>,
+    pub(crate) pid: u32,
+    pub(crate) parent: Option<String>,
+    pub(crate) started_at: u64,
+    pub(crate) attempt: usize,
+    pub(crate) last_stage: Option<String>,
+    pub(crate) committed: HashSet<(usize, String)>,
+    pub(crate) tasks: BTreeMap<String, String>,
+    pub(crate) finished: Option<bool>,
+}
+
+impl JobKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            JobKind::Auto => "auto",
+            JobKind::AutoFile => "auto-file",
+            JobKind::Impl => "impl",
+            JobKind::Parallel => "parallel",
+        }
+    }
+}
+
+impl JobState {
+    pub(crate) fn is_committed(&self, attempt: usize, stage: &str) -> bool {
+        self.committed.contains(&(attempt, stage.to_string()))
+    }
+
+    pub(crate) fn tasks_in_state(&self, state: &str) -> Vec<String> {
+        self.tasks
+            .iter()
+            .filter(|(_, v)| v.as_str() == state)
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
+    fn owned_by_live_process(&self) -> bool {
+        self.pid != 0 && self.pid != std::process::id() && process_alive(self.pid)
+    }
+}
+
+// Set on stage subprocesses so their own journals point back at the job
+// that spawned them.
+pub(crate) const PARENT_JOB_ENV: &str = "ORC_PARENT_JOB";
+
+pub(crate) fn jobs_dir(root: &Path) -> PathBuf {
+    root.join(".project").join("runtime").join("jobs")
+}
+
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+impl JobJournal {
+    pub(crate) fn create(
+        root: &Path,
+        kind: JobKind,
+        message: Option<&str>,
+    ) -> Result<Self, String> {
+        let parent = env::var(PARENT_JOB_ENV)
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        Self::create_with_parent(root, kind, message, parent.as_deref())
+    }
+
+    pub(crate) fn create_with_parent(
+        root: &Path,
+        kind: JobKind,
+        message: Option<&str>,
+        parent: Option<&str>,
+    ) -> Result<Self, String> {
+        let dir = jobs_dir(root);
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+        let mut id = format!(
+            "{}-{}-{}",
+            kind.as_str(),
+            crate::now_unix_secs(),
+            std::process::id()
+        );
+        let mut seq = 1usize;
+        while dir.join(format!("{}.jsonl", id)).exists() {
+            seq += 1;
+            id = format!(
+                "{}-{}-{}-{}",
+                kind.as_str(),
+                crate::now_unix_secs(),
+                std::process::id(),
+                seq
+            );
+        }
+        let journal = Self {
+            path: dir.join(format!("{}.jsonl", id)),
+            id,
+        };
+        journal.record(JobEvent::Started {
+            kind,
+            message: message.map(str::to_string),
+            pid: std::process::id(),
+            parent: parent.map(str::to_string),
+        })?;
+        Ok(journal)
+    }
+
+    pub(crate) fn open(root: &Path, id: &str) -> Result<Self, String> {
+        let path = jobs_dir(root).join(format!("{}.jsonl", id));
+        if !path.exists() {
+            return Err(format!("job not found: {}", id));
+        }
+        Ok(Self {
+            id: id.to_string(),
+            path,
+        })
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub(crate) fn record(&self, event: JobEvent) -> Result<(), String> {
+        let line = serde_json::to_string(&JobLine {
+            ts: crate::now_unix_secs(),
+            event,
+        })
+        .map_err(|e| format!("failed to encode job event: {}", e))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("failed to open {}: {}", self.path.display(), e))?;
+        let mut last = [0u8; 1];
+        let torn_tail = file
+            .seek(SeekFrom::End(-1))
+            .and_then(|_| file.read_exact(&mut last))
+            .is_ok_and(|_| last[0] != b'\n');
+        let prefix = if torn_tail { "\n" } else { "" };
+        writeln!(file, "{}{}", prefix, line)
+            .and_then(|_| file.sync_data())
+            .map_err(|e| format!("failed to write {}: {}", self.path.display(), e))
+    }
+
+    pub(crate) fn stage_started(&self, attempt: usize, stage: &str) {
+        let _ = self.record(JobEvent::StageStarted {
+            attempt,
+            stage: stage.to_string(),
+        });
+    }
+
+    pub(crate) fn stage_result(
+        &self,
+        attempt: usize,
+        stage: &str,
+        result: &Result<String, String>,
+    ) {
+        let event = match result {
+            Ok(detail) => JobEvent::StageCommitted {
+                attempt,
+                stage: stage.to_string(),
+                detail: detail.clone(),
+            },
+            Err(detail) => JobEvent::StageFailed {
+                attempt,
+                stage: stage.to_string(),
+                detail: detail.clone(),
+            },
+        };
+        let _ = self.record(event);
+    }
+
+    pub(crate) fn task(&self, task: &str, state: &str, detail: &str) {
+        let _ = self.record(JobEvent::Task {
+            task: task.to_string(),
+            state: state.to_string(),
+            detail: detail.to_string(),
+        });
+    }
+
+    pub(crate) fn finish(&self, result: &Result<String, String>) {
+        let (ok, detail) = match result {
+            Ok(v) => (true, v.clone()),
+            Err(e) => (false, e.clone()),
+        };
+        let _ = self.record(JobEvent::Finished { ok, detail });
+    }
+
+    pub(crate) fn state(&self) -> Result<JobState, String> {
+        load_job_state(&self.path)
+    }
+}
+
+pub(crate) fn load_job_state(path: &Path) -> Result<JobState, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let id = path
+        .file_stem()
+        .and_then(|v| v.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let mut state: Option<JobState> = None;
+    for line in raw.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // A crash can leave a torn final line; everything before it is still valid.
+        let Ok(entry) = serde_json::from_str::<JobLine>(line) else {
+            continue;
+        };
+        match entry.event {
+            JobEvent::Started {
+                kind,
+                message,
+                pid,
+                parent,
+            } => {
+                state = Some(JobState {
+                    id: id.clone(),
+                    kind,
+                    message,
+                    pid,
+                    parent,
+                    started_at: entry.ts,
+                    attempt: 0,
+                    last_stage: None,
+                    committed: HashSet::new(),
+                    tasks: BTreeMap::new(),
+                    finished: None,
+                });
+            }
+            event => {
+                let Some(state) = state.as_mut() else {
+                    continue;
+                };
+                match event {
+                    JobEvent::StageStarted { attempt, stage } => {
+                        state.attempt = state.attempt.max(attempt);
+                        state.last_stage = Some(stage);
+                    }
+                    JobEvent::StageCommitted { attempt, stage, .. } => {
+                        state.committed.insert((attempt, stage));
+                    }
+                    JobEvent::StageFailed { .. } => {}
+                    JobEvent::Task {
+                        task, state: value, ..
+                    } => {
+                        state.tasks.insert(task, value);
+                    }
+                    JobEvent::Finished { ok, .. } => {
+                        state.finished = Some(ok);
+                    }
+                    JobEvent::Started { .. } => {}
+                }
+            }
+        }
+    }
+    state.ok_or_else(|| format!("job journal has no start event: {}", path.display()))
+}
+
+pub(crate) fn list_jobs(root: &Path) -> Result<Vec<JobState>, String> {
+    let dir = jobs_dir(root);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("failed to read {}: {}", dir.display(), e))?;
+    let mut jobs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|v| v.to_str()) != Some("jsonl") {
+            continue;
+        }
+        if let Ok(state) = load_job_state(&path) {
+            jobs.push(state);
+        }
+    }
+    jobs.sort_by(|a, b| {
+        a.started_at
+            .cmp(&b.started_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    Ok(jobs)
+}
+
+// Without a kind, only top-level jobs qualify: resuming the parent re-runs
+// its stage subprocesses, which pick up their own journals.
+pub(crate) fn latest_unfinished(
+    root: &Path,
+    kind: Option<JobKind>,
+) -> Result<Option<JobState>, String> {
+    Ok(list_jobs(root)?
+        .into_iter()
+        .rev()
+        .filter(|job| job.finished.is_none() && !job.owned_by_live_process())
+        .find(|job| match kind {
+            Some(k) => job.kind == k,
+            None => job.parent.is_none(),
+        }))
+}
+
+pub(crate) fn supersede(root: &Path, state: &JobState, by: &JobJournal) {
+    if let Ok(old) = JobJournal::open(root, &state.id) {
+        old.finish(&Err(format!("superseded by {}", by.id())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(tag: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "orc_jobs_{}_{}_{}",
+            tag,
+            std::process::id(),
+            crate::now_unix_secs()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("create temp root");
+        root
+    }
+
+    #[test]
+    fn journal_folds_stage_and_task_transitions() {
+        let root = temp_root("fold");
+        let journal = JobJournal::create(&root, JobKind::Auto, Some("build it")).expect("create");
+        journal.stage_started(0, "init_code_project");
+        journal.stage_result(0, "init_code_project", &Ok("ok".to_string()));
+        journal.stage_started(1, "add_code_plan");
+        journal.stage_result(1, "add_code_plan", &Err("boom".to_string()));
+        journal.task("login", "worked", "");
+        journal.task("login", "complete", "");
+        journal.task("signup", "worked", "");
+
+        let state = journal.state().expect("state");
+        assert_eq!(state.kind, JobKind::Auto);
+        assert_eq!(state.message.as_deref(), Some("build it"));
+        assert_eq!(state.attempt, 1);
+        assert_eq!(state.last_stage.as_deref(), Some("add_code_plan"));
+        assert!(state.is_committed(0, "init_code_project"));
+        assert!(!state.is_committed(1, "add_code_plan"));
+        assert_eq!(state.tasks_in_state("complete"), vec!["login".to_string()]);
+        assert_eq!(state.tasks_in_state("worked"), vec!["signup".to_string()]);
+        assert!(state.finished.is_none());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn latest_unfinished_skips_finished_and_torn_lines() {
+        let root = temp_root("latest");
+        let done = JobJournal::create(&root, JobKind::Impl, None).expect("create done");
+        done.finish(&Ok("done".to_string()));
+        let open = JobJournal::create(&root, JobKind::Impl, None).expect("create open");
+        open.task("a", "complete", "");
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(jobs_dir(&root).join(format!("{}.jsonl", open.id())))
+            .expect("open journal");
+        write!(file, "{{\"ts\":1,\"event\":\"task\",\"ta").expect("write torn line");
+
+        let found = latest_unfinished(&root, Some(JobKind::Impl))
+            .expect("scan")
+            .expect("unfinished job");
+        assert_eq!(found.id, open.id());
+        assert_eq!(found.tasks_in_state("complete"), vec!["a".to_string()]);
+        assert!(latest_unfinished(&root, Some(JobKind::Auto))
+            .expect("scan")
+            .is_none());
+
+        let next = JobJournal::create(&root, JobKind::Impl, None).expect("create next");
+        supersede(&root, &found, &next);
+        let found = latest_unfinished(&root, Some(JobKind::Impl))
+            .expect("scan")
+            .expect("next job");
+        assert_eq!(found.id, next.id());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn default_resume_target_skips_stage_child_jobs() {
+        let root = temp_root("parent");
+        let parent = JobJournal::create_with_parent(&root, JobKind::Auto, Some("x"), None)
+            .expect("create parent");
+        let child = JobJournal::create_with_parent(&root, JobKind::Impl, None, Some(parent.id()))
+            .expect("create child");
+        assert_eq!(
+            child.state().expect("child").parent.as_deref(),
+            Some(parent.id())
+        );
+        let found = latest_unfinished(&root, None)
+            .expect("scan")
+            .expect("parent job");
+        assert_eq!(found.id, parent.id());
+        let found = latest_unfinished(&root, Some(JobKind::Impl))
+            .expect("scan")
+            .expect("child job");
+        assert_eq!(found.id, child.id());
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,299 @@
+use super::{prompt_hash, LlmBackend, LlmOutput, LlmRequest};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Instant;
+
+const DEFAULT_CASSETTE_PATH: &str = ".project/runtime/llm-cassette.jsonl";
+
+static REPLAY_CURSORS: Mutex<Option<HashMap<String, usize>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CassetteMode {
+    Off,
+    Record,
+    Replay,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub(crate) struct CassetteEntry {
+    pub(crate) prompt_hash: String,
+    pub(crate) prompt: String,
+    pub(crate) response: String,
+    #[serde(default)]
+    pub(crate) stderr: String,
+    pub(crate) model: String,
+    pub(crate) duration_ms: u64,
+    #[serde(default)]
+    pub(crate) exit_code: Option<i32>,
+    #[serde(default)]
+    pub(crate) recorded_at: String,
+}
+
+pub(crate) struct RecordingBackend {
+    inner: Box<dyn LlmBackend>,
+    path: Option<PathBuf>,
+}
+
+pub(crate) struct ReplayBackend {
+    path: Option<PathBuf>,
+}
+
+pub(crate) fn cassette_mode(conf: Option<&crate::config::AppConfig>) -> CassetteMode {
+    let raw = env::var("ORC_LLM_CASSETTE")
+        .ok()
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| !v.is_empty())
+        .or_else(|| {
+            conf.and_then(|c| c.ai.as_ref())
+                .and_then(|a| a.cassette.as_deref())
+                .map(|v| v.trim().to_ascii_lowercase())
+        })
+        .unwrap_or_default();
+    match raw.as_str() {
+        "record" => CassetteMode::Record,
+        "replay" => CassetteMode::Replay,
+        _ => CassetteMode::Off,
+    }
+}
+
+pub(crate) fn cassette_path(conf: Option<&crate::config::AppConfig>) -> Option<PathBuf> {
+    env::var("ORC_LLM_CASSETTE_PATH")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .or_else(|| {
+            conf.and_then(|c| c.ai.as_ref())
+                .and_then(|a| a.cassette_path.clone())
+                .filter(|v| !v.trim().is_empty())
+        })
+        .map(PathBuf::from)
+}
+
+// Relative to the project root, not the request dir: parallel tasks run in
+// throwaway worktrees and their recordings must outlive them.
+fn resolve_path(configured: Option<&Path>) -> PathBuf {
+    let path = configured
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CASSETTE_PATH));
+    if path.is_absolute() {
+        return path;
+    }
+    crate::context::project_path(path)
+}
+
+pub(crate) fn load_cassette(path: &Path) -> Result<Vec<CassetteEntry>, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read cassette {}: {}", path.display(), e))?;
+    let mut out = Vec::new();
+    for (idx, line) in raw.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: CassetteEntry = serde_json::from_str(line).map_err(|e| {
+            format!(
+                "failed to parse cassette {} line {}: {}",
+                path.display(),
+                idx + 1,
+                e
+            )
+        })?;
+        out.push(entry);
+    }
+    Ok(out)
+}
+
+fn append_entry(path: &Path, entry: &CassetteEntry) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("failed to encode cassette entry: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("failed to open cassette {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line)
+        .map_err(|e| format!("failed to write cassette {}: {}", path.display(), e))
+}
+
+fn next_replay_index(path: &Path, hash: &str) -> usize {
+    let key = format!("{}#{}", path.display(), hash);
+    let mut guard = REPLAY_CURSORS.lock().unwrap_or_else(|e| e.into_inner());
+    let cursors = guard.get_or_insert_with(HashMap::new);
+    let slot = cursors.entry(key).or_insert(0);
+    let current = *slot;
+    *slot += 1;
+    current
+}
+
+impl RecordingBackend {
+    pub(crate) fn new(inner: Box<dyn LlmBackend>, path: Option<PathBuf>) -> Self {
+        Self { inner, path }
+    }
+}
+
+impl ReplayBackend {
+    pub(crate) fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+}
+
+impl LlmBackend for RecordingBackend {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn label(&self) -> String {
+        self.inner.label()
+    }
+
+    // No process command even when the inner backend has one: callers that
+    // get a command run it themselves (tmux pane, streamed output), which
+    // would bypass the recording. Recorded calls therefore always go
+    // through `exec` and show their output only once they finish.
+    fn command(&self, _request: &LlmRequest<'_>) -> Option<Command> {
+        None
+    }
+
+    fn exec(&self, request: &LlmRequest<'_>) -> Result<LlmOutput, String> {
+        let started = Instant::now();
+        let result = self.inner.exec(request);
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let (response, stderr, exit_code) = match &result {
+            Ok(out) => (out.stdout.clone(), out.stderr.clone(), out.code),
+            Err(e) => (String::new(), e.clone(), None),
+        };
+        let entry = CassetteEntry {
+            prompt_hash: prompt_hash(request.prompt),
+            prompt: request.prompt.to_string(),
+            response,
+            stderr,
+            model: self.inner.label(),
+            duration_ms,
+            exit_code,
+            recorded_at: crate::now_unix(),
+        };
+        // A failed recording must not turn a successful call into a failure.
+        if let Err(e) = append_entry(&resolve_path(self.path.as_deref()), &entry) {
+            eprintln!("[cassette] {}", e);
+        }
+        result
+    }
+}
+
+impl LlmBackend for ReplayBackend {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    fn label(&self) -> String {
+        "cassette replay".to_string()
+    }
+
+    // Replayed responses come from the cassette, so there is nothing to run
+    // in a pane or stream.
+    fn command(&self, _request: &LlmRequest<'_>) -> Option<Command> {
+        None
+    }
+
+    fn exec(&self, request: &LlmRequest<'_>) -> Result<LlmOutput, String> {
+        let path = resolve_path(self.path.as_deref());
+        let hash = prompt_hash(request.prompt);
+        let matches: Vec<CassetteEntry> = load_cassette(&path)?
+            .into_iter()
+            .filter(|entry| entry.prompt_hash == hash)
+            .collect();
+        let Some(last) = matches.last() else {
+            return Err(format!(
+                "cassette replay: no recorded response for prompt hash {} in {}",
+                hash,
+                path.display()
+            ));
+        };
+        let idx = next_replay_index(&path, &hash);
+        let entry = matches.get(idx).unwrap_or(last);
+        Ok(LlmOutput {
+            success: entry.exit_code == Some(0),
+            stdout: entry.response.clone(),
+            stderr: entry.stderr.clone(),
+            code: entry.exit_code,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoBackend;
+
+    impl LlmBackend for EchoBackend {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn label(&self) -> String {
+            "echo".to_string()
+        }
+
+        fn command(&self, _request: &LlmRequest<'_>) -> Option<Command> {
+            None
+        }
+
+        fn exec(&self, request: &LlmRequest<'_>) -> Result<LlmOutput, String> {
+            Ok(LlmOutput {
+                success: true,
+                stdout: format!("echo:{}", request.prompt),
+                stderr: String::new(),
+                code: Some(0),
+            })
+        }
+    }
+
+    #[test]
+    fn recorded_cassette_replays_same_responses_in_order() {
+        let dir = std::env::temp_dir().join(format!(
+            "orc_cassette_{}_{}",
+            std::process::id(),
+            crate::now_unix()
+        ));
+        let path = dir.join("cassette.jsonl");
+        let recorder = RecordingBackend::new(Box::new(EchoBackend), Some(path.clone()));
+        recorder
+            .exec(&LlmRequest::new("first", 5))
+            .expect("record first");
+        recorder
+            .exec(&LlmRequest::new("second", 5))
+            .expect("record second");
+
+        let entries = load_cassette(&path).expect("load cassette");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].prompt, "first");
+        assert_eq!(entries[0].model, "echo");
+        assert_eq!(entries[0].exit_code, Some(0));
+
+        let replay = ReplayBackend::new(Some(path.clone()));
+        let out = replay
+            .exec(&LlmRequest::new("second", 5))
+            .expect("replay second");
+        assert!(out.success);
+        assert_eq!(out.stdout, "echo:second");
+        assert!(replay.exec(&LlmRequest::new("unknown", 5)).is_err());
+
+        // The cassette "directory" is a file, so the write fails.
+        let broken = RecordingBackend::new(Box::new(EchoBackend), Some(path.join("x.jsonl")));
+        let out = broken
+            .exec(&LlmRequest::new("third", 5))
+            .expect("output survives a failed recording");
+        assert_eq!(out.stdout, "echo:third");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,588 @@
+mod cassette;
+
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const CODEX_DANGEROUS_FLAG: &str = "--dangerously-bypass-approvals-and-sandbox";
+const CLAUDE_DANGEROUS_FLAG: &str = "--dangerously-skip-permissions";
+const DEFAULT_OPENAI_ENDPOINT: &str = "https://api.openai.com/v1";
+const DEFAULT_OPENAI_KEY_ENV: &str = "OPENAI_API_KEY";
+const DEFAULT_FAKE_RESPONSE: &str = "NO_CHANGE";
+const FAKE_RULES_FILE: &str = "rules.yaml";
+
+#[derive(Debug, Clone)]
+pub(crate) struct LlmRequest<'a> {
+    pub(crate) prompt: &'a str,
+    pub(crate) dir: Option<&'a Path>,
+    pub(crate) timeout_sec: u64,
+    pub(crate) auto_yes: bool,
+}
+
+impl<'a> LlmRequest<'a> {
+    pub(crate) fn new(prompt: &'a str, timeout_sec: u64) -> Self {
+        Self {
+            prompt,
+            dir: None,
+            timeout_sec,
+            auto_yes: false,
+        }
+    }
+
+    pub(crate) fn in_dir(mut self, dir: &'a Path) -> Self {
+        self.dir = Some(dir);
+        self
+    }
+
+    pub(crate) fn with_auto_yes(mut self, auto_yes: bool) -> Self {
+        self.auto_yes = auto_yes;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LlmOutput {
+    pub(crate) success: bool,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    pub(crate) code: Option<i32>,
+}
+
+impl LlmOutput {
+    fn from_process(output: Output) -> Self {
+        Self {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            code: output.status.code(),
+        }
+    }
+}
+
+pub(crate) trait LlmBackend: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn label(&self) -> String;
+
+    fn command(&self, request: &LlmRequest<'_>) -> Option<Command>;
+
+    fn exec(&self, request: &LlmRequest<'_>) -> Result<LlmOutput, String> {
+        let Some(command) = self.command(request) else {
+            return Err(format!("{} has no process command", self.label()));
+        };
+        let output = run_command_with_timeout(command, None, request.timeout_sec, &self.label())?;
+        Ok(LlmOutput::from_process(output))
+    }
+}
+
+struct CodexBackend {
+    bin: String,
+    dangerous: bool,
+}
+
+struct ClaudeCliBackend {
+    bin: String,
+    dangerous: bool,
+}
+
+struct OpenAiHttpBackend {
+    endpoint: String,
+    model: String,
+    api_key_env: String,
+}
+
+struct FakeBackend {
+    response: String,
+    fixture_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct FakeFixtureDoc {
+    #[serde(default)]
+    rules: Vec<FakeFixtureRule>,
+    #[serde(default)]
+    default: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+struct FakeFixtureRule {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    contains: Vec<String>,
+    #[serde(default)]
+    response: Option<String>,
+    #[serde(default)]
+    response_file: Option<String>,
+    #[serde(default)]
+    exit_code: Option<i32>,
+}
+
+impl LlmBackend for CodexBackend {
+    fn name(&self) -> &str {
+        "codex"
+    }
+
+    fn label(&self) -> String {
+        format!("{} exec", self.bin)
+    }
+
+    fn command(&self, request: &LlmRequest<'_>) -> Option<Command> {
+        let mut command = Command::new(&self.bin);
+        if let Some(dir) = request.dir {
+            command.current_dir(dir);
+        }
+        command.arg("exec");
+        if request.auto_yes {
+            command.arg("-y");
+        }
+        if self.dangerous && self.bin.eq_ignore_ascii_case("codex") {
+            command.arg(CODEX_DANGEROUS_FLAG);
+        }
+        command.arg(request.prompt);
+        Some(command)
+    }
+}
+
+impl LlmBackend for ClaudeCliBackend {
+    fn name(&self) -> &str {
+        "claude-cli"
+    }
+
+    fn label(&self) -> String {
+        format!("{} -p", self.bin)
+    }
+
+    fn command(&self, request: &LlmRequest<'_>) -> Option<Command> {
+        let mut command = Command::new(&self.bin);
+        if let Some(dir) = request.dir {
+            command.current_dir(dir);
+        }
+        if self.dangerous {
+            command.arg(CLAUDE_DANGEROUS_FLAG);
+        }
+        command.arg("-p").arg(request.prompt);
+        Some(command)
+    }
+}
+
+impl LlmBackend for OpenAiHttpBackend {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn label(&self) -> String {
+        format!("openai {} ({})", self.model, self.endpoint)
+    }
+
+    fn command(&self, _request: &LlmRequest<'_>) -> Option<Command> {
+        None
+    }
+
+    fn exec(&self, request: &LlmRequest<'_>) -> Result<LlmOutput, String> {
+        let api_key = env::var(&self.api_key_env)
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default();
+        let body = serde_json::json!({
+            "model": self.model,
+            "messages": [{ "role": "user", "content": request.prompt }],
+        });
+        let url = format!("{}/chat/completions", self.endpoint.trim_end_matches('/'));
+        let mut curl_config = vec![
+            format!("url = {}", quote_curl_config(&url)),
+            "request = \"POST\"".to_string(),
+            "header = \"Content-Type: application/json\"".to_string(),
+            format!("data-binary = {}", quote_curl_config(&body.to_string())),
+        ];
+        if !api_key.is_empty() {
+            curl_config.push(format!(
+                "header = {}",
+                quote_curl_config(&format!("Authorization: Bearer {}", api_key))
+            ));
+        }
+        let mut command = Command::new("curl");
+        if let Some(dir) = request.dir {
+            command.current_dir(dir);
+        }
+        command.args(["-sS", "--fail-with-body", "--config", "-"]);
+        let output = run_command_with_timeout(
+            command,
+            Some(curl_config.join("\n") + "\n"),
+            request.timeout_sec,
+            &self.label(),
+        )?;
+        let mut result = LlmOutput::from_process(output);
+        if !result.success {
+            if result.stderr.is_empty() {
+                result.stderr = result.stdout.trim().to_string();
+            }
+            return Ok(result);
+        }
+        let parsed: serde_json::Value = serde_json::from_str(&result.stdout)
+            .map_err(|e| format!("{} returned invalid json: {}", self.label(), e))?;
+        match parsed
+            .pointer("/choices/0/message/content")
+            .and_then(serde_json::Value::as_str)
+        {
+            Some(content) => result.stdout = content.to_string(),
+            None => {
+                result.success = false;
+                result.stderr = format!("{} response has no message content", self.label());
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl LlmBackend for FakeBackend {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn label(&self) -> String {
+        "fake llm".to_string()
+    }
+
+    fn command(&self, _request: &LlmRequest<'_>) -> Option<Command> {
+        None
+    }
+
+    fn exec(&self, request: &LlmRequest<'_>) -> Result<LlmOutput, String> {
+        let Some(dir) = self.fixture_dir.as_deref() else {
+            return Ok(fake_output(self.response.clone(), 0));
+        };
+        replay_fixture(dir, request.prompt)
+    }
+}
+
+fn fake_output(stdout: String, code: i32) -> LlmOutput {
+    LlmOutput {
+        success: code == 0,
+        stderr: if code == 0 {
+            String::new()
+        } else {
+            format!("fake llm exit code {}", code)
+        },
+        stdout,
+        code: Some(code),
+    }
+}
+
+pub(crate) fn prompt_hash(prompt: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in prompt.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn load_fake_fixture_doc(dir: &Path) -> Result<FakeFixtureDoc, String> {
+    let path = dir.join(FAKE_RULES_FILE);
+    if !path.exists() {
+        return Ok(FakeFixtureDoc::default());
+    }
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    serde_yaml::from_str(&raw).map_err(|e| format!("failed to parse {}: {}", path.display(), e))
+}
+
+fn replay_fixture(dir: &Path, prompt: &str) -> Result<LlmOutput, String> {
+    let hash = prompt_hash(prompt);
+    let hashed_path = dir.join(format!("{}.txt", hash));
+    if hashed_path.exists() {
+        let stdout = fs::read_to_string(&hashed_path)
+            .map_err(|e| format!("failed to read {}: {}", hashed_path.display(), e))?;
+        return Ok(fake_output(stdout, 0));
+    }
+    let doc = load_fake_fixture_doc(dir)?;
+    let matched = doc.rules.iter().find(|rule| {
+        !rule.contains.is_empty() && rule.contains.iter().all(|needle| prompt.contains(needle.as_str()))
+    });
+    if let Some(rule) = matched {
+        let stdout = match (&rule.response, &rule.response_file) {
+            (Some(inline), _) => inline.clone(),
+            (None, Some(file)) => {
+                let path = dir.join(file);
+                fs::read_to_string(&path)
+                    .map_err(|e| format!("fake rule `{}` failed to read {}: {}", rule.name, path.display(), e))?
+            }
+            (None, None) => String::new(),
+        };
+        return Ok(fake_output(stdout, rule.exit_code.unwrap_or(0)));
+    }
+    if let Some(default) = doc.default {
+        return Ok(fake_output(default, 0));
+    }
+    let unmatched_dir = dir.join("unmatched");
+    if fs::create_dir_all(&unmatched_dir).is_ok() {
+        let _ = fs::write(unmatched_dir.join(format!("{}.prompt.txt", hash)), prompt);
+    }
+    Ok(LlmOutput {
+        success: false,
+        stdout: String::new(),
+        stderr: format!("fake llm: no fixture for prompt hash {} in {}", hash, dir.display()),
+        code: Some(1),
+    })
+}
+
+fn quote_curl_config(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(crate) fn run_command_with_timeout(
+    mut command: Command,
+    stdin_payload: Option<String>,
+    timeout_sec: u64,
+    timeout_label: &str,
+) -> Result<Output, String> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    if stdin_payload.is_some() {
+        command.stdin(Stdio::piped());
+    }
+    let (mut child, _tracked) = crate::process_group::spawn_tracked(&mut command)
+        .map_err(|e| format!("failed to spawn {}: {}", timeout_label, e))?;
+    if let (Some(payload), Some(mut stdin)) = (stdin_payload, child.stdin.take()) {
+        stdin
+            .write_all(payload.as_bytes())
+            .map_err(|e| format!("failed to write stdin for {}: {}", timeout_label, e))?;
+    }
+    let started = Instant::now();
+    loop {
+        match child
+            .try_wait()
+            .map_err(|e| format!("failed while waiting {}: {}", timeout_label, e))?
+        {
+            Some(_) => {
+                return child
+                    .wait_with_output()
+                    .map_err(|e| format!("failed to collect output for {}: {}", timeout_label, e));
+            }
+            None => {
+                if started.elapsed() >= Duration::from_secs(timeout_sec) {
+                    crate::process_group::kill_child_tree(&mut child);
+                    return Err(format!(
+                        "{} timed out after {}s",
+                        timeout_label, timeout_sec
+                    ));
+                }
+                thread::sleep(Duration::from_millis(200));
+            }
+        }
+    }
+}
+
+pub(crate) fn backend_from_config(
+    conf: Option<&crate::config::AppConfig>,
+) -> Box<dyn LlmBackend> {
+    with_cassette(conf, base_backend_from_config(conf))
+}
+
+fn with_cassette(
+    conf: Option<&crate::config::AppConfig>,
+    inner: Box<dyn LlmBackend>,
+) -> Box<dyn LlmBackend> {
+    match cassette::cassette_mode(conf) {
+        cassette::CassetteMode::Off => inner,
+        cassette::CassetteMode::Record => Box::new(cassette::RecordingBackend::new(
+            inner,
+            cassette::cassette_path(conf),
+        )),
+        cassette::CassetteMode::Replay => {
+            Box::new(cassette::ReplayBackend::new(cassette::cassette_path(conf)))
+        }
+    }
+}
+
+fn base_backend_from_config(conf: Option<&crate::config::AppConfig>) -> Box<dyn LlmBackend> {
+    let ai = conf.and_then(|c| c.ai.as_ref());
+    let model = ai
+        .and_then(|a| a.model.as_deref())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string);
+    let dangerous = conf.is_none_or(crate::config::AppConfig::dangerous_bypass_enabled);
+    let backend_name = env::var("ORC_LLM_BACKEND")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .or_else(|| conf.map(|c| c.llm_backend_name()))
+        .unwrap_or_else(|| "codex".to_string());
+    match backend_name.as_str() {
+        "claude-cli" | "claude" => Box::new(ClaudeCliBackend {
+            bin: model
+                .filter(|v| !v.eq_ignore_ascii_case("codex"))
+                .unwrap_or_else(|| "claude".to_string()),
+            dangerous,
+        }),
+        "openai" | "openai-compatible" => Box::new(OpenAiHttpBackend {
+            endpoint: ai
+                .and_then(|a| a.endpoint.clone())
+                .unwrap_or_else(|| DEFAULT_OPENAI_ENDPOINT.to_string()),
+            model: model.unwrap_or_else(|| "gpt-4o-mini".to_string()),
+            api_key_env: ai
+                .and_then(|a| a.api_key_env.clone())
+                .unwrap_or_else(|| DEFAULT_OPENAI_KEY_ENV.to_string()),
+        }),
+        "fake" => Box::new(FakeBackend {
+            response: ai
+                .and_then(|a| a.fake_response.clone())
+                .unwrap_or_else(|| DEFAULT_FAKE_RESPONSE.to_string()),
+            fixture_dir: env::var("ORC_LLM_FIXTURE_DIR")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .or_else(|| ai.and_then(|a| a.fixture_dir.clone()))
+                .map(|v| resolve_fixture_dir(&v)),
+        }),
+        _ => Box::new(CodexBackend {
+            bin: model.unwrap_or_else(|| "codex".to_string()),
+            dangerous,
+        }),
+    }
+}
+
+fn resolve_fixture_dir(raw: &str) -> PathBuf {
+    let path = PathBuf::from(raw);
+    if path.is_absolute() {
+        path
+    } else {
+        crate::source_root().join(path)
+    }
+}
+
+pub(crate) fn resolve_backend() -> Box<dyn LlmBackend> {
+    backend_from_config(crate::load_app_config().as_ref())
+}
+
+pub(crate) fn backend_for_model(model_bin: &str) -> Box<dyn LlmBackend> {
+    let conf = crate::load_app_config();
+    let dangerous = conf
+        .as_ref()
+        .is_none_or(crate::config::AppConfig::dangerous_bypass_enabled);
+    if crate::default_model_bin() == model_bin && !model_bin.eq_ignore_ascii_case("claude") {
+        return backend_from_config(conf.as_ref());
+    }
+    let inner: Box<dyn LlmBackend> = if model_bin.eq_ignore_ascii_case("claude") {
+        Box::new(ClaudeCliBackend {
+            bin: model_bin.to_string(),
+            dangerous,
+        })
+    } else {
+        Box::new(CodexBackend {
+            bin: model_bin.to_string(),
+            dangerous,
+        })
+    };
+    with_cassette(conf.as_ref(), inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AiConfig, AppConfig};
+
+    fn config_with_ai(ai: AiConfig) -> AppConfig {
+        serde_yaml::from_str::<AppConfig>("{}")
+            .map(|mut conf| {
+                conf.ai = Some(ai);
+                conf
+            })
+            .expect("empty config")
+    }
+
+    #[test]
+    fn llm_backend_name_infers_from_model_when_backend_missing() {
+        let conf = config_with_ai(AiConfig {
+            model: Some("claude".to_string()),
+            backend: None,
+            endpoint: None,
+            api_key_env: None,
+            fake_response: None,
+            fixture_dir: None,
+            cassette: None,
+            cassette_path: None,
+        });
+        assert_eq!(conf.llm_backend_name(), "claude-cli");
+        let conf = config_with_ai(AiConfig {
+            model: Some("codex".to_string()),
+            backend: None,
+            endpoint: None,
+            api_key_env: None,
+            fake_response: None,
+            fixture_dir: None,
+            cassette: None,
+            cassette_path: None,
+        });
+        assert_eq!(conf.llm_backend_name(), "codex");
+    }
+
+    #[test]
+    fn fake_backend_returns_configured_response_without_process() {
+        let conf = config_with_ai(AiConfig {
+            model: None,
+            backend: Some("fake".to_string()),
+            endpoint: None,
+            api_key_env: None,
+            fake_response: Some("canned".to_string()),
+            fixture_dir: None,
+            cassette: None,
+            cassette_path: None,
+        });
+        let backend = backend_from_config(Some(&conf));
+        let request = LlmRequest::new("hello", 5);
+        assert!(backend.command(&request).is_none());
+        let out = backend.exec(&request).expect("fake exec");
+        assert!(out.success);
+        assert_eq!(out.stdout, "canned");
+    }
+
+    #[test]
+    fn codex_backend_builds_exec_command_with_prompt_last() {
+        let backend = CodexBackend {
+            bin: "codex".to_string(),
+            dangerous: true,
+        };
+        let request = LlmRequest::new("do it", 5).with_auto_yes(true);
+        let command = backend.command(&request).expect("codex command");
+        let args: Vec<String> = command
+            .get_args()
+            .map(|v| v.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(args, vec!["exec", "-y", CODEX_DANGEROUS_FLAG, "do it"]);
+    }
+
+    #[test]
+    fn fake_fixture_prefers_prompt_hash_then_rules_then_records_unmatched() {
+        let dir = std::env::temp_dir().join(format!(
+            "orc_fake_fixture_{}_{}",
+            std::process::id(),
+            crate::now_unix()
+        ));
+        fs::create_dir_all(&dir).expect("create fixture dir");
+        fs::write(dir.join(format!("{}.txt", prompt_hash("exact prompt"))), "from hash")
+            .expect("write hash fixture");
+        fs::write(
+            dir.join(FAKE_RULES_FILE),
+            "rules:\n  - name: greet\n    contains: [\"hello\", \"world\"]\n    response: from rule\n",
+        )
+        .expect("write rules");
+
+        let hashed = replay_fixture(&dir, "exact prompt").expect("hash replay");
+        assert_eq!(hashed.stdout, "from hash");
+        let ruled = replay_fixture(&dir, "say hello to the world").expect("rule replay");
+        assert_eq!(ruled.stdout, "from rule");
+        let missing = replay_fixture(&dir, "hello only").expect("unmatched replay");
+        assert!(!missing.success);
+        assert!(dir
+            .join("unmatched")
+            .join(format!("{}.prompt.txt", prompt_hash("hello only")))
+            .exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
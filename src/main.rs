@@ -1,104 +1,65 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 #![allow(unused_mut)]
+#![allow(clippy::items_after_test_module)]
 
 mod config;
+mod context;
 mod code;
 mod cli;
 mod chat;
+mod chat_room;
 mod draft;
+mod jobs;
+mod llm;
+mod monorepo;
 mod parallel;
 mod plan;
+mod process_group;
 mod profile;
+mod registry;
 mod story;
+mod supervisor;
+mod terminal;
+mod test_runner;
 mod tmux;
 mod tui;
 mod ui;
+mod verify;
 mod web;
 mod web_api;
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
 use std::fs::OpenOptions;
-use std::io::{self, BufRead, ErrorKind, Write};
+use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use chat_room::ChatMessage;
 pub(crate) use draft::{DraftDoc, DraftsListDoc, PlannedItem};
+pub(crate) use registry::{ProjectRecord, ProjectRegistry, ProjectType};
 
-const REGISTRY_PATH: &str = "configs/project.yaml";
 const EXEC_LOG_PATH: &str = ".project/log.md";
 const PROJECT_MD_PATH: &str = ".project/project.md";
 const PRIMARY_DRAFTS_LIST_FILE: &str = "drafts_list.yaml";
 pub(crate) const INPUT_MD_PATH: &str = "input.md";
+// Directories left out when walking a project tree for files or symbols.
+pub(crate) const SCAN_SKIP_DIRS: [&str; 5] = [".git", ".project", ".temp", "target", "node_modules"];
 const FEATURE_NAME_SKILL_PATH: &str = "/home/tree/ai/skills/rule-naming/SKILL.md";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct ProjectRecord {
-    #[serde(default)]
-    id: String,
-    name: String,
-    path: String,
-    description: String,
-    created_at: String,
-    updated_at: String,
-    selected: bool,
-    #[serde(default = "default_project_type")]
-    project_type: String,
-}
-
-fn default_project_type() -> String {
-    "code".to_string()
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub(crate) struct ProjectRegistry {
-    #[serde(default, rename = "recentActivepane")]
-    recent_active_pane: Option<String>,
-    #[serde(default)]
-    projects: Vec<ProjectRecord>,
-}
-
 #[derive(Debug, Clone)]
 pub(crate) struct ParallelFeatureTask {
     pub(crate) name: String,
     pub(crate) draft_path: PathBuf,
     pub(crate) depends_on: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-struct ChatRoomDoc {
-    #[serde(default)]
-    room_name: String,
-    #[serde(default)]
-    users: Vec<ChatUser>,
-    #[serde(default)]
-    messages: Vec<ChatMessage>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-struct ChatUser {
-    user_id: String,
-    #[serde(default = "default_chat_user_role")]
-    role: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-struct ChatMessage {
-    message_id: String,
-    command: String,
-    #[serde(default)]
-    data: Option<String>,
-    #[serde(default)]
-    receiver: Option<String>,
-    sender_id: String,
-    #[serde(default)]
-    created_at: String,
+    pub(crate) touches: Vec<String>,
+    pub(crate) contracts: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -116,6 +77,7 @@ struct ChatWaitArgs {
     name: String,
     react_all: bool,
     target_count: Option<usize>,
+    serve: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -132,26 +94,24 @@ struct ChatSessionRecord {
     updated_at: String,
 }
 
-struct ChatRoomLockGuard {
-    lock_path: PathBuf,
-}
-
-impl Drop for ChatRoomLockGuard {
-    fn drop(&mut self) {
-        let _ = fs::remove_file(&self.lock_path);
-    }
+pub(crate) fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
-fn default_chat_user_role() -> String {
-    "user".to_string()
+pub(crate) fn now_unix() -> String {
+    now_unix_secs().to_string()
 }
 
-fn now_unix() -> String {
-    let secs = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-    secs.to_string()
+pub(crate) fn env_flag_true(name: &str) -> bool {
+    env::var(name).is_ok_and(|v| {
+        matches!(
+            v.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    })
 }
 
 fn primary_drafts_list_path(project_root: &Path) -> PathBuf {
@@ -174,54 +134,8 @@ pub(crate) fn save_drafts_list_primary(
     Ok(())
 }
 
-fn generate_project_id(existing: &HashSet<String>) -> String {
-    const ALNUM: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
-    let mut seed = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(0);
-    for _ in 0..512 {
-        let mut out = String::with_capacity(4);
-        for _ in 0..4 {
-            seed = seed
-                .wrapping_mul(6364136223846793005)
-                .wrapping_add(1);
-            let idx = (seed as usize) % ALNUM.len();
-            out.push(ALNUM[idx] as char);
-        }
-        if !existing.contains(&out) {
-            return out;
-        }
-    }
-    "0000".to_string()
-}
-
 pub(crate) fn normalize_registry(registry: &mut ProjectRegistry) -> bool {
-    let mut changed = false;
-    let mut ids: HashSet<String> = registry
-        .projects
-        .iter()
-        .filter_map(|p| if p.id.is_empty() { None } else { Some(p.id.clone()) })
-        .collect();
-    for project in &mut registry.projects {
-        if project.id.is_empty() {
-            let id = generate_project_id(&ids);
-            ids.insert(id.clone());
-            project.id = id;
-            changed = true;
-        }
-    }
-    if let Some(recent_id) = &registry.recent_active_pane {
-        if !registry.projects.iter().any(|p| &p.id == recent_id) {
-            registry.recent_active_pane = None;
-            changed = true;
-        }
-    }
-    changed
-}
-
-pub(crate) fn model_supports_dangerous_flag(model_bin: &str) -> bool {
-    model_bin.eq_ignore_ascii_case("codex")
+    registry::normalize(registry)
 }
 
 pub(crate) fn default_model_bin() -> String {
@@ -397,14 +311,15 @@ fn collect_unresolved_placeholders(rendered: &str, keys: &[&str]) -> Vec<String>
 }
 
 pub(crate) fn append_failure_log(task_name: &str, reason: &str) -> Result<(), String> {
-    if let Some(parent) = Path::new(EXEC_LOG_PATH).parent() {
+    let log_path = context::project_path(EXEC_LOG_PATH);
+    if let Some(parent) = log_path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
     }
     let mut f = OpenOptions::new()
         .create(true)
         .append(true)
-        .open(EXEC_LOG_PATH)
+        .open(&log_path)
         .map_err(|e| format!("failed to open {}: {}", EXEC_LOG_PATH, e))?;
     writeln!(
         f,
@@ -417,28 +332,15 @@ pub(crate) fn append_failure_log(task_name: &str, reason: &str) -> Result<(), St
 }
 
 pub(crate) fn load_registry(path: &Path) -> Result<ProjectRegistry, String> {
-    if !path.exists() {
-        return Ok(ProjectRegistry::default());
-    }
-    let raw = fs::read_to_string(path)
-        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
-    let mut parsed: ProjectRegistry =
-        serde_yaml::from_str(&raw).map_err(|e| format!("failed to parse yaml: {}", e))?;
-    normalize_registry(&mut parsed);
-    Ok(parsed)
+    registry::load(path)
 }
 
-pub(crate) fn save_registry(path: &Path, registry: &ProjectRegistry) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
-    }
-    let raw = serde_yaml::to_string(registry).map_err(|e| format!("yaml encode error: {}", e))?;
-    fs::write(path, raw).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+pub(crate) fn save_registry(path: &Path, registry: &mut ProjectRegistry) -> Result<(), String> {
+    registry::save(path, registry)
 }
 
 fn default_project_path() -> PathBuf {
-    env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+    context::project_root()
 }
 
 fn is_existing_project(path: &Path) -> bool {
@@ -455,8 +357,8 @@ fn select_only(registry: &ProjectRegistry, target: &str) -> ProjectRegistry {
         })
         .collect();
     let mut updated = ProjectRegistry {
-        recent_active_pane: registry.recent_active_pane.clone(),
         projects,
+        ..registry.clone()
     };
     if let Some(selected) = updated.projects.iter().find(|p| p.selected) {
         updated.recent_active_pane = Some(selected.id.clone());
@@ -481,27 +383,28 @@ fn upsert_project(
         existing.description = description.to_string();
         existing.updated_at = now;
         if existing.id.is_empty() {
-            existing.id = generate_project_id(&existing_ids);
+            existing.id = registry::generate_project_id(&existing_ids);
         }
         return ProjectRegistry {
-            recent_active_pane: registry.recent_active_pane.clone(),
             projects: updated,
+            ..registry.clone()
         };
     }
 
     updated.push(ProjectRecord {
-        id: generate_project_id(&existing_ids),
+        id: registry::generate_project_id(&existing_ids),
         name: name.to_string(),
         path: path.display().to_string(),
         description: description.to_string(),
         created_at: now.clone(),
         updated_at: now,
         selected: false,
-        project_type: default_project_type(),
+        project_type: ProjectType::default(),
+        state: None,
     });
     ProjectRegistry {
-        recent_active_pane: registry.recent_active_pane.clone(),
         projects: updated,
+        ..registry.clone()
     }
 }
 
@@ -513,8 +416,8 @@ fn delete_project(registry: &ProjectRegistry, name: &str) -> ProjectRegistry {
         .cloned()
         .collect();
     let mut updated = ProjectRegistry {
-        recent_active_pane: registry.recent_active_pane.clone(),
         projects,
+        ..registry.clone()
     };
     if let Some(recent_id) = &updated.recent_active_pane {
         if !updated.projects.iter().any(|p| &p.id == recent_id) {
@@ -992,21 +895,6 @@ fn run_command_in_dir(
     }
 }
 
-fn test_command() -> Result<String, String> {
-    let cwd = env::current_dir().map_err(|e| format!("failed to read cwd: {}", e))?;
-    let cargo_toml = cwd.join("Cargo.toml");
-    if !cargo_toml.exists() {
-        return Ok("test skipped: Cargo.toml not found".to_string());
-    }
-    let out = run_command_in_dir(&cwd, "cargo", &["test", "-q"], "cargo test -q")?;
-    let first = out.lines().next().unwrap_or("").trim();
-    if first.is_empty() {
-        Ok("test completed: cargo test -q passed".to_string())
-    } else {
-        Ok(format!("test completed: {}", first))
-    }
-}
-
 fn draft_create() -> Result<String, String> {
     draft::draft_create()
 }
@@ -1095,7 +983,7 @@ fn parse_add_function_objects(raw: &str) -> Vec<AddFunctionObject> {
 }
 
 fn append_feature_to_project_md(feature_name: &str, display_name: &str) -> Result<(), String> {
-    let path = Path::new(PROJECT_MD_PATH);
+    let path = &project_md_file();
     let mut lines: Vec<String> = fs::read_to_string(path)
         .map_err(|e| format!("failed to read {}: {}", PROJECT_MD_PATH, e))?
         .lines()
@@ -1512,7 +1400,7 @@ current:\n{}",
 }
 
 pub(crate) fn check_and_improve_drafts_before_parallel() -> Result<String, String> {
-    let root = Path::new(".project").join("feature");
+    let root = context::project_dir().join("feature");
     if !root.exists() {
         return Ok("check-draft skipped: no feature directory".to_string());
     }
@@ -1562,6 +1450,9 @@ pub(crate) fn check_and_improve_drafts_before_parallel() -> Result<String, Strin
 }
 
 pub(crate) fn load_app_config() -> Option<config::AppConfig> {
+    if let Some(conf) = context::current_config() {
+        return conf;
+    }
     let root = source_root();
     let candidates = [
         root.join("configs").join("configs.yaml"),
@@ -1576,7 +1467,7 @@ pub(crate) fn load_app_config() -> Option<config::AppConfig> {
     None
 }
 
-fn generate_chat_id_8() -> String {
+pub(crate) fn generate_chat_id_8() -> String {
     const ALNUM: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
     let mut seed = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -1594,7 +1485,7 @@ fn generate_chat_id_8() -> String {
     out
 }
 
-fn now_chat_timestamp() -> String {
+pub(crate) fn now_chat_timestamp() -> String {
     let secs = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_secs())
@@ -1602,51 +1493,6 @@ fn now_chat_timestamp() -> String {
     format!("{}Z", secs)
 }
 
-fn chat_room_path(name: &str) -> PathBuf {
-    source_root().join(".temp").join(format!("{}.yaml", name))
-}
-
-fn chat_room_lock_path(name: &str) -> PathBuf {
-    source_root().join(".temp").join(format!("{}.lock", name))
-}
-
-fn acquire_chat_room_lock(name: &str) -> Result<ChatRoomLockGuard, String> {
-    let lock_path = chat_room_lock_path(name);
-    if let Some(parent) = lock_path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
-    }
-    let started = SystemTime::now();
-    loop {
-        match OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(&lock_path)
-        {
-            Ok(_) => {
-                return Ok(ChatRoomLockGuard { lock_path });
-            }
-            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
-                let elapsed = started
-                    .elapsed()
-                    .map(|d| d.as_secs())
-                    .unwrap_or(0);
-                if elapsed >= 15 {
-                    return Err(format!("chat room lock timeout: {}", lock_path.display()));
-                }
-                thread::sleep(Duration::from_millis(50));
-            }
-            Err(e) => {
-                return Err(format!(
-                    "failed to create chat room lock {}: {}",
-                    lock_path.display(),
-                    e
-                ));
-            }
-        }
-    }
-}
-
 fn chat_session_path(name: &str) -> PathBuf {
     source_root()
         .join(".temp")
@@ -1802,6 +1648,7 @@ fn parse_chat_wait_args(args: &[String]) -> Result<ChatWaitArgs, String> {
     let mut name: Option<String> = None;
     let mut react_all: Option<bool> = None;
     let mut target_count: Option<usize> = None;
+    let mut serve = false;
     let mut i = 0usize;
     while i < args.len() {
         match args[i].as_str() {
@@ -1834,6 +1681,7 @@ fn parse_chat_wait_args(args: &[String]) -> Result<ChatWaitArgs, String> {
                 }
                 target_count = Some(parsed);
             }
+            "--serve" => serve = true,
             other => {
                 return Err(format!("chat-wait unknown option: {}", other));
             }
@@ -1849,48 +1697,10 @@ fn parse_chat_wait_args(args: &[String]) -> Result<ChatWaitArgs, String> {
         name,
         react_all,
         target_count,
+        serve,
     })
 }
 
-fn load_chat_room(path: &Path) -> Result<ChatRoomDoc, String> {
-    let room_name = path
-        .file_stem()
-        .and_then(|v| v.to_str())
-        .unwrap_or("")
-        .to_string();
-    let default_doc = ChatRoomDoc {
-        room_name: room_name.clone(),
-        users: Vec::new(),
-        messages: Vec::new(),
-    };
-
-    if !path.exists() {
-        save_chat_room(path, &default_doc)?;
-        return Ok(default_doc);
-    }
-    let raw = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
-    if raw.trim().is_empty() {
-        save_chat_room(path, &default_doc)?;
-        return Ok(default_doc);
-    }
-    let mut doc: ChatRoomDoc =
-        serde_yaml::from_str(&raw).map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
-    if doc.room_name.trim().is_empty() {
-        doc.room_name = room_name;
-        save_chat_room(path, &doc)?;
-    }
-    Ok(doc)
-}
-
-fn save_chat_room(path: &Path, doc: &ChatRoomDoc) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
-    }
-    let raw = serde_yaml::to_string(doc).map_err(|e| format!("chat room yaml encode error: {}", e))?;
-    fs::write(path, raw).map_err(|e| format!("failed to write {}: {}", path.display(), e))
-}
-
 fn print_chat_messages(room_name: &str, messages: &[ChatMessage]) {
     for m in messages {
         let receiver = m.receiver.as_deref().unwrap_or("*");
@@ -1902,47 +1712,20 @@ fn print_chat_messages(room_name: &str, messages: &[ChatMessage]) {
     }
 }
 
-fn chat_new_messages(messages: &[ChatMessage], last_read_message_id: Option<&str>) -> Vec<ChatMessage> {
-    if messages.is_empty() {
-        return Vec::new();
-    }
-    if let Some(last_id) = last_read_message_id {
-        if let Some(pos) = messages.iter().position(|m| m.message_id == last_id) {
-            return messages[pos + 1..].to_vec();
-        }
-    }
-    messages.to_vec()
-}
-
 fn chat_send(parsed: &ChatCliArgs) -> Result<String, String> {
-    let _guard = acquire_chat_room_lock(&parsed.name)?;
-    let path = chat_room_path(&parsed.name);
-    let mut room = load_chat_room(&path)?;
     let llm_id = chat_sender_id_for_session(&parsed.name)?;
-    if !room.users.iter().any(|u| u.user_id == llm_id) {
-        room.users.push(ChatUser {
-            user_id: llm_id.clone(),
-            role: "user".to_string(),
-        });
-    }
-    let mut used_ids: HashSet<String> = room.messages.iter().map(|m| m.message_id.clone()).collect();
-    let mut message_id = generate_chat_id_8();
-    while used_ids.contains(&message_id) {
-        message_id = generate_chat_id_8();
-    }
-    used_ids.insert(message_id.clone());
-    room.messages.push(ChatMessage {
+    chat_room::join(&parsed.name, &llm_id)?;
+    let message_id = generate_chat_id_8();
+    let mut message = ChatMessage {
         message_id: message_id.clone(),
         command: parsed.message.clone().unwrap_or_default(),
         data: parsed.data.clone(),
         receiver: parsed.receiver.clone(),
         sender_id: llm_id.clone(),
         created_at: now_chat_timestamp(),
-    });
-    if room.room_name.trim().is_empty() {
-        room.room_name = parsed.name.clone();
-    }
-    save_chat_room(&path, &room)?;
+    };
+    chat_room::protocol::normalize(&mut message)?;
+    chat_room::append(&parsed.name, &message)?;
     Ok(format!(
         "chat message sent: room={} message_id={} sender_id={}",
         parsed.name, message_id, llm_id
@@ -1971,8 +1754,7 @@ fn chat_watch_log_path(name: &str) -> PathBuf {
         .join(format!("{}.watch.log", name))
 }
 
-fn spawn_chat_background(name: &str) -> Result<String, String> {
-    let log_path = chat_watch_log_path(name);
+fn spawn_chat_process(args: &[&str], log_path: &Path) -> Result<u32, String> {
     if let Some(parent) = log_path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
@@ -1980,43 +1762,325 @@ fn spawn_chat_background(name: &str) -> Result<String, String> {
     let stdout = OpenOptions::new()
         .create(true)
         .append(true)
-        .open(&log_path)
+        .open(log_path)
         .map_err(|e| format!("failed to open {}: {}", log_path.display(), e))?;
     let stderr = stdout
         .try_clone()
         .map_err(|e| format!("failed to clone log handle {}: {}", log_path.display(), e))?;
     let exe = env::current_exe().map_err(|e| format!("failed to resolve current exe: {}", e))?;
     let child = Command::new(exe)
-        .arg("chat")
-        .arg("-n")
-        .arg(name)
-        .arg("--watch")
+        .args(args)
         .stdout(Stdio::from(stdout))
         .stderr(Stdio::from(stderr))
         .spawn()
-        .map_err(|e| format!("failed to spawn background chat watcher: {}", e))?;
+        .map_err(|e| format!("failed to spawn background chat process: {}", e))?;
+    Ok(child.id())
+}
+
+fn spawn_chat_background(name: &str) -> Result<String, String> {
+    let log_path = chat_watch_log_path(name);
+    let pid = spawn_chat_process(&["chat", "-n", name, "--watch"], &log_path)?;
     Ok(format!(
         "chat watcher started: room={} pid={} log={}",
         name,
-        child.id(),
+        pid,
         log_path.display()
     ))
 }
 
-fn chat_watch_loop(name: &str, path: &Path, mut last_read_message_id: Option<String>) -> Result<(), String> {
-    let max_read_time = chat_max_read_time_sec();
+// Wakes on every send to the room, or after `max_read_time` as a fallback poll.
+fn spawn_chat_wakeups(name: &str) -> tokio::sync::mpsc::Receiver<()> {
+    let bell = chat_room::Doorbell::bind(name);
+    let interval = Duration::from_secs(chat_max_read_time_sec());
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    thread::spawn(move || loop {
+        bell.wait(interval);
+        if tx.blocking_send(()).is_err() {
+            break;
+        }
+    });
+    rx
+}
+
+fn chat_watch_loop(name: &str, mut cursor: chat_room::ChatCursor) -> Result<(), String> {
+    let bell = chat_room::Doorbell::bind(name);
+    let interval = Duration::from_secs(chat_max_read_time_sec());
     loop {
-        thread::sleep(Duration::from_secs(max_read_time));
-        let latest = load_chat_room(path)?;
-        let new_messages = chat_new_messages(&latest.messages, last_read_message_id.as_deref());
+        let new_messages = cursor.read_new()?;
         if !new_messages.is_empty() {
             print_chat_messages(name, &new_messages);
-            last_read_message_id = latest.messages.last().map(|m| m.message_id.clone());
         }
+        bell.wait(interval);
+    }
+}
+
+fn chat_repair_command(args: &[String]) -> Result<String, String> {
+    let name = match args {
+        [flag, name] if flag == "-n" && !name.trim().is_empty() => name.trim(),
+        _ => return Err("chat repair requires -n <name>".to_string()),
+    };
+    let report = chat_room::repair(name)?;
+    Ok(format!(
+        "chat repaired: room={} header={} messages_kept={} lines_dropped={} stale_lock={}",
+        name,
+        report.header,
+        report.kept,
+        report.dropped,
+        report.lock.as_deref().unwrap_or("none")
+    ))
+}
+
+// `<n>[s|m|h|d]`; a bare number is seconds.
+fn parse_duration_sec(raw: &str) -> Option<u64> {
+    let raw = raw.trim();
+    let (digits, unit) = match raw.char_indices().last()? {
+        (i, c) if c.is_ascii_alphabetic() => (&raw[..i], c),
+        _ => (raw, 's'),
+    };
+    let n: u64 = digits.parse().ok()?;
+    let scale = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3600,
+        'd' => 86400,
+        _ => return None,
+    };
+    n.checked_mul(scale)
+}
+
+// Absolute `<unix seconds>[Z]` like `created_at`, or a duration ago (`2h`).
+fn parse_chat_time(raw: &str, now: u64) -> Option<u64> {
+    let raw = raw.trim();
+    if let Some(abs) = raw.strip_suffix('Z') {
+        return abs.parse().ok();
+    }
+    if raw.bytes().all(|b| b.is_ascii_digit()) && raw.len() >= 9 {
+        return raw.parse().ok();
+    }
+    parse_duration_sec(raw).map(|ago| now.saturating_sub(ago))
+}
+
+fn chat_history_command(args: &[String]) -> Result<String, String> {
+    let now = now_unix_secs();
+    let mut name: Option<String> = None;
+    let mut filter = chat_room::HistoryFilter::default();
+    let mut json = false;
+    let mut out: Option<PathBuf> = None;
+    let mut i = 0usize;
+    while i < args.len() {
+        let flag = args[i].as_str();
+        if flag == "--json" {
+            json = true;
+            i += 1;
+            continue;
+        }
+        let value = args
+            .get(i + 1)
+            .cloned()
+            .ok_or_else(|| format!("chat history: {} requires value", flag))?;
+        match flag {
+            "-n" => name = Some(value),
+            "--sender" => filter.sender = Some(value),
+            "--receiver" => filter.receiver = Some(value),
+            "--command" => filter.command = Some(value),
+            "--since" | "--until" => {
+                let at = parse_chat_time(&value, now)
+                    .ok_or_else(|| format!("chat history: invalid time {}", value))?;
+                if flag == "--since" {
+                    filter.since = Some(at);
+                } else {
+                    filter.until = Some(at);
+                }
+            }
+            "-o" => {
+                json = true;
+                out = Some(PathBuf::from(value));
+            }
+            other => return Err(format!("chat history unknown option: {}", other)),
+        }
+        i += 2;
     }
+    let name = name
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| "chat history requires -n <name>".to_string())?;
+    let messages = chat_room::history(&name, &filter)?;
+    if !json {
+        print_chat_messages(&name, &messages);
+        return Ok(format!("chat history: room={} count={}", name, messages.len()));
+    }
+    let raw = serde_json::to_string_pretty(&messages)
+        .map_err(|e| format!("chat history json encode error: {}", e))?;
+    match out {
+        Some(path) => {
+            fs::write(&path, raw)
+                .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+            Ok(format!(
+                "chat history exported: room={} count={} path={}",
+                name,
+                messages.len(),
+                path.display()
+            ))
+        }
+        None => Ok(raw),
+    }
+}
+
+fn chat_retention_command(args: &[String]) -> Result<String, String> {
+    let mut name: Option<String> = None;
+    let mut retention: Option<chat_room::Retention> = None;
+    let mut i = 0usize;
+    while i < args.len() {
+        let flag = args[i].as_str();
+        if flag == "--clear" {
+            retention = Some(chat_room::Retention::default());
+            i += 1;
+            continue;
+        }
+        let value = args
+            .get(i + 1)
+            .ok_or_else(|| format!("chat retention: {} requires value", flag))?;
+        match flag {
+            "-n" => name = Some(value.clone()),
+            "--max-messages" => {
+                let max = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|v| *v > 0)
+                    .ok_or_else(|| "chat retention --max-messages must be >= 1".to_string())?;
+                retention.get_or_insert_with(Default::default).max_messages = Some(max);
+            }
+            "--max-age" => {
+                let age = parse_duration_sec(value)
+                    .filter(|v| *v > 0)
+                    .ok_or_else(|| format!("chat retention: invalid --max-age {}", value))?;
+                retention.get_or_insert_with(Default::default).max_age_sec = Some(age);
+            }
+            other => return Err(format!("chat retention unknown option: {}", other)),
+        }
+        i += 2;
+    }
+    let name = name
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| "chat retention requires -n <name>".to_string())?;
+    let (current, archived) = match retention {
+        Some(retention) => (retention, chat_room::set_retention(&name, retention)?),
+        None => (chat_room::open_room(&name)?.retention, 0),
+    };
+    let show = |v: Option<String>| v.unwrap_or_else(|| "none".to_string());
+    Ok(format!(
+        "chat retention: room={} max_messages={} max_age_sec={} archived={}",
+        name,
+        show(current.max_messages.map(|v| v.to_string())),
+        show(current.max_age_sec.map(|v| v.to_string())),
+        archived
+    ))
+}
+
+// Sender ids of sessions that live in a tmux pane, mapped to the pane id.
+fn chat_pane_targets(room_name: &str) -> Result<HashMap<String, String>, String> {
+    let doc = load_chat_sessions(&chat_session_path(room_name))?;
+    Ok(doc
+        .sessions
+        .into_iter()
+        .filter_map(|s| {
+            let pane = s.session_key.strip_prefix("tmux-pane:")?.to_string();
+            Some((s.sender_id, pane))
+        })
+        .collect())
+}
+
+fn chat_bridge_command(args: &[String]) -> Result<String, String> {
+    let mut name: Option<String> = None;
+    let mut options = chat_room::BridgeOptions::default();
+    let mut background = false;
+    let mut i = 0usize;
+    while i < args.len() {
+        let flag = args[i].as_str();
+        if flag == "--background" {
+            background = true;
+            i += 1;
+            continue;
+        }
+        let value = args
+            .get(i + 1)
+            .ok_or_else(|| format!("chat bridge: {} requires value", flag))?;
+        match flag {
+            "-n" => name = Some(value.trim().to_string()),
+            "--settle" | "--timeout" => {
+                let secs = parse_duration_sec(value)
+                    .filter(|v| *v > 0)
+                    .ok_or_else(|| format!("chat bridge: invalid {} {}", flag, value))?;
+                if flag == "--settle" {
+                    options.settle = Duration::from_secs(secs);
+                } else {
+                    options.timeout = Duration::from_secs(secs);
+                }
+            }
+            "--until" => {
+                options.until = Some(
+                    regex::Regex::new(value)
+                        .map_err(|e| format!("chat bridge: invalid --until: {}", e))?,
+                );
+            }
+            other => return Err(format!("chat bridge unknown option: {}", other)),
+        }
+        i += 2;
+    }
+    let name = name
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| "chat bridge requires -n <name>".to_string())?;
+    chat_room::open_room(&name)?;
+    if background {
+        let settle = options.settle.as_secs().to_string();
+        let timeout = options.timeout.as_secs().to_string();
+        let log_path = source_root()
+            .join(".temp")
+            .join(format!("{}.bridge.log", name));
+        let mut bridge_args = vec![
+            "chat",
+            "bridge",
+            "-n",
+            &name,
+            "--settle",
+            &settle,
+            "--timeout",
+            &timeout,
+        ];
+        if let Some(until) = &options.until {
+            bridge_args.extend(["--until", until.as_str()]);
+        }
+        let pid = spawn_chat_process(&bridge_args, &log_path)?;
+        return Ok(format!(
+            "chat bridge started: room={} pid={} log={}",
+            name,
+            pid,
+            log_path.display()
+        ));
+    }
+    println!(
+        "chat bridge active: room={} panes={}",
+        name,
+        chat_pane_targets(&name)?.len()
+    );
+    chat_room::bridge::run(
+        &name,
+        || chat_pane_targets(&name),
+        options,
+        Duration::from_secs(chat_max_read_time_sec()),
+    )?;
+    Ok(format!("chat bridge stopped: room={}", name))
 }
 
 pub(crate) async fn chat_command(args: &[String]) -> Result<String, String> {
+    match args.first().map(String::as_str) {
+        Some("repair") => return chat_repair_command(&args[1..]),
+        Some("history") => return chat_history_command(&args[1..]),
+        Some("retention") => return chat_retention_command(&args[1..]),
+        Some("bridge") => return chat_bridge_command(&args[1..]),
+        _ => {}
+    }
     let parsed = parse_chat_args(args)?;
     if parsed.background && parsed.watch {
         return Err("chat cannot use --background and --watch together".to_string());
@@ -2031,38 +2095,27 @@ pub(crate) async fn chat_command(args: &[String]) -> Result<String, String> {
         return spawn_chat_background(&parsed.name);
     }
 
-    let path = chat_room_path(&parsed.name);
-    let mut room = load_chat_room(&path)?;
     let llm_id = chat_sender_id_for_session(&parsed.name)?;
-    if !room.users.iter().any(|u| u.user_id == llm_id) {
-        room.users.push(ChatUser {
-            user_id: llm_id.clone(),
-            role: "user".to_string(),
-        });
-        save_chat_room(&path, &room)?;
-    }
-
-    let mut last_read_message_id = room.messages.last().map(|m| m.message_id.clone());
+    chat_room::join(&parsed.name, &llm_id)?;
     if parsed.watch {
-        chat_watch_loop(&parsed.name, &path, last_read_message_id.clone())?;
+        chat_watch_loop(&parsed.name, chat_room::ChatCursor::at_end(&parsed.name))?;
     }
 
+    let mut wakeups = spawn_chat_wakeups(&parsed.name);
+    let mut cursor = chat_room::ChatCursor::from_start(&parsed.name);
     println!("chat mode active: room={}, sender_id={}", parsed.name, llm_id);
     println!("exit: Ctrl+D");
-    print_chat_messages(&parsed.name, &room.messages);
+    print_chat_messages(&parsed.name, &cursor.read_new()?);
 
-    let max_read_time = chat_max_read_time_sec();
     use tokio::io::{self as tokio_io, AsyncBufReadExt};
     let mut stdin = tokio_io::BufReader::new(tokio_io::stdin());
     let mut input_line = String::new();
     loop {
         tokio::select! {
-            _ = tokio::time::sleep(Duration::from_secs(max_read_time)) => {
-                let latest = load_chat_room(&path)?;
-                let new_messages = chat_new_messages(&latest.messages, last_read_message_id.as_deref());
+            _ = wakeups.recv() => {
+                let new_messages = cursor.read_new()?;
                 if !new_messages.is_empty() {
                     print_chat_messages(&parsed.name, &new_messages);
-                    last_read_message_id = latest.messages.last().map(|m| m.message_id.clone());
                 }
             }
             read = stdin.read_line(&mut input_line) => {
@@ -2080,56 +2133,70 @@ pub(crate) async fn chat_command(args: &[String]) -> Result<String, String> {
     Ok(format!("chat closed: room={} sender_id={}", parsed.name, llm_id))
 }
 
+// Runs the handler for a typed command and posts its result back to the sender.
+async fn serve_agent_command(
+    room: &str,
+    self_id: &str,
+    message: &ChatMessage,
+) -> Result<(), String> {
+    use chat_room::protocol::{self, AgentCommand};
+    let result = match AgentCommand::parse(message) {
+        None => return Ok(()),
+        Some(Err(e)) => Err(e),
+        Some(Ok(command)) => match protocol::handle(room, &command, message).await {
+            Some(result) => result,
+            None => return Ok(()),
+        },
+    };
+    let mut reply = protocol::reply_to(message, self_id, result);
+    reply.message_id = generate_chat_id_8();
+    reply.created_at = now_chat_timestamp();
+    chat_room::append(room, &reply)
+}
+
 pub(crate) async fn chat_wait_command(args: &[String]) -> Result<String, String> {
     let parsed = parse_chat_wait_args(args)?;
-    let path = chat_room_path(&parsed.name);
-    let mut room = load_chat_room(&path)?;
     let self_id = chat_sender_id_for_session(&parsed.name)?;
-    if !room.users.iter().any(|u| u.user_id == self_id) {
-        room.users.push(ChatUser {
-            user_id: self_id.clone(),
-            role: "user".to_string(),
-        });
-        save_chat_room(&path, &room)?;
-    }
+    chat_room::join(&parsed.name, &self_id)?;
+    let mut wakeups = spawn_chat_wakeups(&parsed.name);
+    let mut cursor = chat_room::ChatCursor::at_end(&parsed.name);
 
     println!(
-        "chat-wait active: room={} self_id={} react_all={} target_count={}",
+        "chat-wait active: room={} self_id={} react_all={} target_count={} serve={}",
         parsed.name,
         self_id,
         parsed.react_all,
-        parsed.target_count.unwrap_or(0)
+        parsed.target_count.unwrap_or(0),
+        parsed.serve
     );
-    let mut last_read_message_id = room.messages.last().map(|m| m.message_id.clone());
-    let max_read_time = chat_max_read_time_sec();
     let mut reacted_count = 0usize;
-    loop {
-        tokio::time::sleep(Duration::from_secs(max_read_time)).await;
-        let latest = load_chat_room(&path)?;
-        let new_messages = chat_new_messages(&latest.messages, last_read_message_id.as_deref());
-        if !new_messages.is_empty() {
-            for message in &new_messages {
-                let should_react = if parsed.react_all {
-                    true
-                } else {
-                    message.receiver.as_deref() == Some(self_id.as_str())
-                };
-                if should_react {
-                    println!("{}", reaction(message));
-                    reacted_count += 1;
+    while wakeups.recv().await.is_some() {
+        for message in cursor.read_new()? {
+            let should_react = if parsed.react_all {
+                true
+            } else {
+                message.receiver.as_deref() == Some(self_id.as_str())
+            };
+            if should_react {
+                println!("{}", reaction(&message));
+                reacted_count += 1;
+                if parsed.serve && message.sender_id != self_id {
+                    if let Err(e) = serve_agent_command(&parsed.name, &self_id, &message).await {
+                        eprintln!("[chat-wait] {} from {}: {}", message.message_id, message.sender_id, e);
+                    }
                 }
             }
-            last_read_message_id = latest.messages.last().map(|m| m.message_id.clone());
-            if let Some(target_count) = parsed.target_count {
-                if reacted_count >= target_count {
-                    return Ok(format!(
-                        "chat-wait done: room={} reacted={} target={}",
-                        parsed.name, reacted_count, target_count
-                    ));
-                }
+        }
+        if let Some(target_count) = parsed.target_count {
+            if reacted_count >= target_count {
+                return Ok(format!(
+                    "chat-wait done: room={} reacted={} target={}",
+                    parsed.name, reacted_count, target_count
+                ));
             }
         }
     }
+    Err(format!("chat-wait stopped: room={}", parsed.name))
 }
 
 pub(crate) fn extract_project_info(project_md: &str) -> String {
@@ -2495,7 +2562,11 @@ fn sync_draft_state_doc(project_root: &Path, doc: &mut DraftsListDoc) {
 }
 
 fn resolve_project_md_path_for_flow() -> PathBuf {
-    PathBuf::from(PROJECT_MD_PATH)
+    project_md_file()
+}
+
+pub(crate) fn project_md_file() -> PathBuf {
+    context::project_path(PROJECT_MD_PATH)
 }
 
 fn preflight_draft_create(path: &Path) -> Result<String, String> {
@@ -2535,7 +2606,7 @@ pub(crate) fn preflight_parallel_build(path: &Path) -> Result<String, String> {
     }
     let mut missing = Vec::new();
     for name in &doc.planned {
-        let dir = Path::new(".project").join("feature").join(name);
+        let dir = context::project_dir().join("feature").join(name);
         let has_task = [
             dir.join("drafts.yaml"),
             dir.join("tasks.yaml"),
@@ -2562,7 +2633,7 @@ pub(crate) fn preflight_parallel_build(path: &Path) -> Result<String, String> {
 }
 
 pub(crate) fn add_feature_to_planned(feature_name: &str) -> Result<(), String> {
-    let path = resolve_drafts_list_path(Path::new("."))?;
+    let path = resolve_drafts_list_path(&context::project_root())?;
     add_feature_to_planned_at(&path, feature_name)
 }
 
@@ -2710,9 +2781,9 @@ fn promote_planned_to_features_at(path: &Path, items: &[String]) -> Result<(), S
 }
 
 pub(crate) fn promote_planned_to_features(items: &[String]) -> Result<(), String> {
-    let path = resolve_drafts_list_path(Path::new("."))?;
+    let path = resolve_drafts_list_path(&context::project_root())?;
     promote_planned_to_features_at(&path, items)?;
-    let _ = promote_project_md_plan_to_features(Path::new("."), items)?;
+    let _ = promote_project_md_plan_to_features(&context::project_root(), items)?;
     Ok(())
 }
 
@@ -2720,8 +2791,8 @@ pub(crate) fn move_finished_features_to_clear(items: &[String]) -> Result<String
     if items.is_empty() {
         return Ok("move-finished skipped: no completed feature".to_string());
     }
-    let feature_root = Path::new(".project").join("feature");
-    let clear_root = Path::new(".project").join("clear");
+    let feature_root = context::project_dir().join("feature");
+    let clear_root = context::project_dir().join("clear");
     fs::create_dir_all(&clear_root)
         .map_err(|e| format!("failed to create {}: {}", clear_root.display(), e))?;
     let mut moved = 0usize;
@@ -2757,7 +2828,7 @@ fn check_code_timeout_sec() -> u64 {
 }
 
 fn append_check_code_runtime_log(stage: &str, detail: &str) {
-    let runtime = Path::new(".project").join("reference");
+    let runtime = context::project_dir().join("reference");
     if fs::create_dir_all(&runtime).is_err() {
         return;
     }
@@ -2771,7 +2842,7 @@ fn collect_draft_constraints_checklist(feature_names: &[String]) -> Result<Vec<S
     if feature_names.is_empty() {
         return Ok(Vec::new());
     }
-    let path = Path::new(".project").join("drafts.yaml");
+    let path = context::project_dir().join("drafts.yaml");
     if !path.exists() {
         return Ok(vec!["- (drafts.yaml not found)".to_string()]);
     }
@@ -2858,17 +2929,21 @@ pub(crate) fn run_check_code_after_draft_changes(
         "시작/프롬프트 전송",
         &format!("trigger={} timeout={}s", trigger, timeout_sec),
     );
-    let debug_enabled = load_app_config()
+    let app_conf = load_app_config();
+    let debug_enabled = app_conf
         .as_ref()
         .is_none_or(config::AppConfig::debug_enabled);
+    let interval = app_conf
+        .as_ref()
+        .map_or(15, config::AppConfig::heartbeat_interval_sec);
     let wait_stop = Arc::new(AtomicBool::new(false));
     let heartbeat = if debug_enabled {
         let stop = Arc::clone(&wait_stop);
         Some(thread::spawn(move || {
             let mut elapsed = 0u64;
             while !stop.load(Ordering::Relaxed) {
-                thread::sleep(Duration::from_secs(15));
-                elapsed += 15;
+                thread::sleep(Duration::from_secs(interval));
+                elapsed += interval;
                 if stop.load(Ordering::Relaxed) {
                     break;
                 }
@@ -2909,7 +2984,7 @@ pub(crate) fn source_root() -> PathBuf {
 }
 
 pub(crate) fn registry_path() -> PathBuf {
-    source_root().join(REGISTRY_PATH)
+    registry::registry_path(&source_root())
 }
 
 fn resolve_project_template_path() -> Result<PathBuf, String> {
@@ -2970,23 +3045,27 @@ pub(crate) fn resolve_project_md_prompt_path(auto_mode: bool) -> Result<PathBuf,
 }
 
 pub(crate) fn resolve_task_template_path() -> Result<PathBuf, String> {
+    resolve_code_prompt_path("tasks.txt")
+}
+
+pub(crate) fn resolve_code_prompt_path(file_name: &str) -> Result<PathBuf, String> {
     let root = source_root();
     let candidates = [
-        root.join("assets").join("presets").join("code").join("prompts").join("tasks.txt"),
+        root.join("assets").join("presets").join("code").join("prompts").join(file_name),
         PathBuf::from("assets")
-            .join("code")
+            .join("presets").join("code")
             .join("prompts")
-            .join("tasks.txt"),
+            .join(file_name),
         root.join("src")
             .join("assets")
             .join("presets").join("code")
             .join("prompts")
-            .join("tasks.txt"),
+            .join(file_name),
         PathBuf::from("src")
             .join("assets")
             .join("presets").join("code")
             .join("prompts")
-            .join("tasks.txt"),
+            .join(file_name),
     ];
     for candidate in candidates {
         if candidate.exists() {
@@ -2994,7 +3073,8 @@ pub(crate) fn resolve_task_template_path() -> Result<PathBuf, String> {
         }
     }
     Err(format!(
-        "tasks template not found (source root: {})",
+        "{} prompt not found (source root: {})",
+        file_name,
         root.display()
     ))
 }
@@ -3073,7 +3153,7 @@ pub(crate) fn write_parallel_feedback(
     let raw = run_codex_exec_capture_with_timeout(&prompt, 120)?;
     let feedback_md = raw.trim().to_string();
     validate_parallel_feedback_markdown(&feedback_md)?;
-    let out_path = Path::new(".project").join("feedback.md");
+    let out_path = context::project_dir().join("feedback.md");
     fs::write(&out_path, feedback_md + "\n")
         .map_err(|e| format!("failed to write {}: {}", out_path.display(), e))?;
     Ok(format!(
@@ -3123,7 +3203,7 @@ pub(crate) fn initialize_parallel_workspace_if_empty(path: &Path) -> Result<Opti
 }
 
 pub(crate) fn collect_parallel_feature_tasks() -> Result<Vec<ParallelFeatureTask>, String> {
-    let root = Path::new(".project").join("feature");
+    let root = context::project_dir().join("feature");
     if !root.exists() {
         return Ok(Vec::new());
     }
@@ -3159,10 +3239,26 @@ pub(crate) fn collect_parallel_feature_tasks() -> Result<Vec<ParallelFeatureTask
             .map(|v| v.to_string_lossy().to_string())
             .unwrap_or_else(|| "unknown".to_string());
         let depends_on = doc.depends_on.clone();
+        let mut touches: Vec<String> = Vec::new();
+        let mut contracts: Vec<String> = Vec::new();
+        for task in &doc.task {
+            for touch in &task.touches {
+                if !touches.contains(touch) {
+                    touches.push(touch.clone());
+                }
+            }
+            for contract in &task.contracts {
+                if !contracts.contains(contract) {
+                    contracts.push(contract.clone());
+                }
+            }
+        }
         out.push(ParallelFeatureTask {
             name,
             draft_path,
             depends_on,
+            touches,
+            contracts,
         });
     }
     out.sort_by(|a, b| a.name.cmp(&b.name));
@@ -3532,7 +3628,6 @@ name : sample
     #[test]
     fn preflight_parallel_build_rejects_missing_task_files() {
         let root = make_temp_dir("orc_preflight_parallel");
-        let old_cwd = env::current_dir().expect("cwd");
         fs::create_dir_all(root.join(".project")).expect("create .project");
         let tasks_path = root.join(".project").join("drafts_list.yaml");
         let doc = DraftsListDoc {
@@ -3540,11 +3635,194 @@ name : sample
             ..Default::default()
         };
         save_drafts_list(&tasks_path, &doc).expect("save drafts_list");
-        env::set_current_dir(&root).expect("enter temp root");
-        let err = preflight_parallel_build(Path::new(".project").join("drafts_list.yaml").as_path())
-            .expect_err("should fail");
+        let err = context::sync_scope(context::ProjectContext::new(&root), || {
+            preflight_parallel_build(&tasks_path)
+        })
+        .expect_err("should fail");
         assert!(err.contains("missing draft/task file"));
-        env::set_current_dir(old_cwd).expect("restore cwd");
+        let _ = fs::remove_dir_all(root);
+    }
+
+    fn write_pipeline_fixture(dir: &Path) {
+        fs::create_dir_all(dir).expect("create fixture dir");
+        fs::write(
+            dir.join("project.md"),
+            "# info\n- name: sample\n- description: greet cli\n- spec: rust cli\n\n# features\n- greet_user\n\n# rules\n- 출력은 한 줄\n\n# constraints\n- no network\n\n# domains\n## app\n### states\n- idle\n### action\n- greet\n### rules\n- deterministic output\n",
+        )
+        .expect("write project fixture");
+        fs::write(
+            dir.join("plan.yaml"),
+            "goal: greet\ndomains:\n- app\ndrafts:\n  planned:\n  - greet_user\n  worked: []\n  complete: []\n",
+        )
+        .expect("write plan fixture");
+        fs::write(
+            dir.join("draft_item.yaml"),
+            "```yaml\nname: greet_user\ntype: action\ndomain:\n- app\nscope:\n- src/main.rs\nrule:\n- output == \"hello\"\nstep:\n- print greeting\ntasks:\n- add greet fn\nconstraints:\n- name -> greeting : print greeting\ncheck:\n- cargo test\n```\n",
+        )
+        .expect("write draft item fixture");
+        fs::write(
+            dir.join("rules.yaml"),
+            r#"rules:
+  - name: project_detail
+    contains: ["출력은 project.md 전체 markdown만 반환한다."]
+    response_file: project.md
+  - name: plan
+    contains: ["출력은 plan.yaml YAML만 반환한다."]
+    response_file: plan.yaml
+  - name: draft_item
+    contains: ["draft_item_template:"]
+    response_file: draft_item.yaml
+  - name: impl
+    contains: ["constraints: ok|fail"]
+    response: "implemented greet_user\nconstraints: ok"
+  - name: check_code
+    contains: ["트리거:"]
+    response: "NO_CHANGE"
+default: ""
+"#,
+        )
+        .expect("write rules fixture");
+    }
+
+    // Project context rooted at `project_dir` whose LLM calls go to the fake
+    // backend replaying `fixture_dir`, so tests share no cwd or env vars.
+    fn fake_llm_context(project_dir: &Path, fixture_dir: &Path) -> context::ProjectContext {
+        let mut conf = load_app_config()
+            .unwrap_or_else(|| serde_yaml::from_str("{}").expect("empty config"));
+        conf.ai = Some(config::AiConfig {
+            backend: Some("fake".to_string()),
+            fixture_dir: Some(fixture_dir.display().to_string()),
+            ..config::AiConfig::default()
+        });
+        conf.heartbeat_interval = Some(1);
+        let mut ctx = context::ProjectContext::new(project_dir);
+        ctx.config = Some(Arc::new(conf));
+        ctx
+    }
+
+    #[test]
+    fn fake_llm_pipeline_runs_project_plan_draft_impl_check_offline() {
+        let root = make_temp_dir("orc_fake_pipeline");
+        let fixture_dir = root.join("fixture");
+        write_pipeline_fixture(&fixture_dir);
+        let project_dir = root.join("project");
+        fs::create_dir_all(project_dir.join(".project")).expect("create project meta");
+        fs::copy(
+            fixture_dir.join("project.md"),
+            project_dir.join(".project").join("project.md"),
+        )
+        .expect("seed project.md");
+        let ctx = fake_llm_context(&project_dir, &fixture_dir);
+
+        let result = (|| -> Result<(), String> {
+            context::sync_scope(ctx.clone(), || -> Result<(), String> {
+                code::detail_code_project()?;
+                code::create_code_domain()?;
+                code::init_code_plan(&[])?;
+                code::add_code_draft(&[])?;
+                Ok(())
+            })?;
+            let drafts_path = project_dir.join(".project").join("drafts.yaml");
+            let raw = fs::read_to_string(&drafts_path).map_err(|e| e.to_string())?;
+            let seeded = raw.replacen(
+                "  check: []\n",
+                "  check:\n  - exists(.project/project.md)\n  - output == \"hello\"\n",
+                1,
+            );
+            fs::write(&drafts_path, seeded).map_err(|e| e.to_string())?;
+            let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+            runtime.block_on(context::scope(ctx.clone(), code::impl_code_draft()))?;
+            context::sync_scope(ctx, || code::check_code_draft(true))?;
+            Ok(())
+        })();
+
+        result.expect("pipeline should succeed with fake llm");
+
+        let meta = project_dir.join(".project");
+        let plan: serde_yaml::Value =
+            serde_yaml::from_str(&fs::read_to_string(meta.join("plan.yaml")).expect("read plan"))
+                .expect("parse plan");
+        assert_eq!(plan["drafts"]["complete"][0].as_str(), Some("greet_user"));
+        assert!(plan["drafts"]["planned"].as_sequence().is_some_and(|v| v.is_empty()));
+        let drafts: serde_yaml::Value = serde_yaml::from_str(
+            &fs::read_to_string(meta.join("drafts.yaml")).expect("read drafts"),
+        )
+        .expect("parse drafts");
+        assert_eq!(drafts["draft"][0]["name"].as_str(), Some("greet_user"));
+        assert_eq!(drafts["complete"][0].as_str(), Some("greet_user"));
+        assert!(drafts["failed"].as_sequence().is_some_and(|v| v.is_empty()));
+        let verification = &drafts["draft"][0]["verification"];
+        assert_eq!(verification[0]["constraint"].as_str(), Some("exists(.project/project.md)"));
+        assert_eq!(verification[0]["status"].as_str(), Some("pass"));
+        assert_eq!(verification[1]["status"].as_str(), Some("skip"));
+        let report = fs::read_to_string(project_dir.join("report.md")).expect("read report");
+        assert!(report.contains("- targets: greet_user"));
+        assert!(report.contains("check-code follow-up: NO_CHANGE"));
+        assert!(!fixture_dir.join("unmatched").exists());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn resume_recovers_impl_items_finished_before_interruption() {
+        let root = make_temp_dir("orc_resume_impl");
+        let fixture_dir = root.join("fixture");
+        write_pipeline_fixture(&fixture_dir);
+        let project_dir = root.join("project");
+        fs::create_dir_all(project_dir.join(".project")).expect("create project meta");
+        fs::copy(
+            fixture_dir.join("project.md"),
+            project_dir.join(".project").join("project.md"),
+        )
+        .expect("seed project.md");
+        let ctx = fake_llm_context(&project_dir, &fixture_dir);
+
+        let result = (|| -> Result<(String, String), String> {
+            context::sync_scope(ctx.clone(), || -> Result<(), String> {
+                code::detail_code_project()?;
+                code::create_code_domain()?;
+                code::init_code_plan(&[])?;
+                code::add_code_draft(&[])?;
+                Ok(())
+            })?;
+            for file in ["plan.yaml", "drafts.yaml"] {
+                let path = project_dir.join(".project").join(file);
+                let raw = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+                let moved = raw
+                    .replacen("planned:\n  - greet_user", "planned: []\n  worked:\n  - greet_user", 1)
+                    .replacen("planned:\n- greet_user", "planned: []\nworked:\n- greet_user", 1)
+                    .replacen("  worked: []\n", "", 1)
+                    .replacen("\nworked: []\n", "\n", 1);
+                fs::write(&path, moved).map_err(|e| e.to_string())?;
+            }
+            let interrupted = jobs::JobJournal::create(&project_dir, jobs::JobKind::Impl, None)?;
+            interrupted.task("greet_user", "worked", "");
+            interrupted.task("greet_user", "complete", "");
+            let drafts_path = project_dir.join(".project").join("drafts.yaml");
+            let raw = fs::read_to_string(&drafts_path).map_err(|e| e.to_string())?;
+            let failing = raw.replacen("  check: []\n", "  check:\n  - \"command: exit 1\"\n", 1);
+            fs::write(&drafts_path, failing).map_err(|e| e.to_string())?;
+            let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+            let out = runtime.block_on(context::scope(ctx, code::resume_code_job(&[])))?;
+            Ok((out, interrupted.id().to_string()))
+        })();
+
+        let (out, interrupted_id) = result.expect("resume should succeed");
+        assert!(out.contains(&format!("resumed impl job {}", interrupted_id)));
+
+        let meta = project_dir.join(".project");
+        let plan: serde_yaml::Value =
+            serde_yaml::from_str(&fs::read_to_string(meta.join("plan.yaml")).expect("read plan"))
+                .expect("parse plan");
+        assert_eq!(plan["drafts"]["complete"][0].as_str(), Some("greet_user"));
+        assert!(plan["drafts"]["worked"].as_sequence().is_some_and(|v| v.is_empty()));
+        let interrupted = jobs::load_job_state(
+            &jobs::jobs_dir(&project_dir).join(format!("{}.jsonl", interrupted_id)),
+        )
+        .expect("load interrupted job");
+        assert_eq!(interrupted.finished, Some(false));
+        assert!(jobs::latest_unfinished(&project_dir, None)
+            .expect("scan jobs")
+            .is_none());
         let _ = fs::remove_dir_all(root);
     }
 }
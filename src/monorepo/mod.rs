@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub(crate) fn list_immediate_dirs(base: &Path) -> Vec<String> {
+    if !base.exists() || !base.is_dir() {
+        return vec![];
+    }
+    let mut out = vec![];
+    if let Ok(read_dir) = fs::read_dir(base) {
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name == "node_modules" {
+                continue;
+            }
+            out.push(name);
+        }
+    }
+    out.sort();
+    out
+}
+
+// Package dirs under the monorepo buckets as (kind, dir, label); app and
+// template buckets are scanned two levels deep (`apps/<group>/<app>`).
+pub(crate) fn collect_packages(root: &Path) -> Vec<(String, PathBuf, String)> {
+    let buckets = vec![
+        ("app".to_string(), vec!["apps", "app"], true),
+        (
+            "feature".to_string(),
+            vec!["packages/features", "features", "feature"],
+            false,
+        ),
+        ("template".to_string(), vec!["template", "templates"], true),
+    ];
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut out = vec![];
+    for (kind, dirs, deep_scan) in buckets {
+        for rel in dirs {
+            let parent = root.join(rel);
+            for child in list_immediate_dirs(&parent) {
+                let first = parent.join(&child);
+                if deep_scan {
+                    let nested = list_immediate_dirs(&first);
+                    let mut added_nested = false;
+                    for grandchild in nested {
+                        let second = first.join(&grandchild);
+                        if !seen.insert(second.clone()) {
+                            continue;
+                        }
+                        out.push((kind.clone(), second, format!("{}/{}", child, grandchild)));
+                        added_nested = true;
+                    }
+                    if !added_nested {
+                        let fallback = if first.join("next.config.js").exists()
+                            || first.join("next.config.ts").exists()
+                        {
+                            "next".to_string()
+                        } else if first.join("astro.config.mjs").exists()
+                            || first.join("astro.config.ts").exists()
+                        {
+                            "astro".to_string()
+                        } else if first.join("app.json").exists() {
+                            "expo".to_string()
+                        } else {
+                            "app".to_string()
+                        };
+                        if seen.insert(first.clone()) {
+                            out.push((kind.clone(), first, format!("{}/{}", child, fallback)));
+                        }
+                    }
+                    continue;
+                }
+                if !seen.insert(first.clone()) {
+                    continue;
+                }
+                out.push((kind.clone(), first, child));
+            }
+        }
+    }
+    out.sort_by(|a, b| a.1.cmp(&b.1));
+    out
+}
@@ -0,0 +1,228 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DagPlan {
+    pub(crate) priority: HashMap<String, usize>,
+    pub(crate) dependents: HashMap<String, Vec<String>>,
+    pub(crate) invalid: BTreeMap<String, String>,
+    pub(crate) issues: Vec<String>,
+}
+
+impl DagPlan {
+    pub(crate) fn priority_of(&self, name: &str) -> usize {
+        self.priority.get(name).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn descendants(&self, name: &str) -> Vec<String> {
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut stack = vec![name.to_string()];
+        let mut out = Vec::new();
+        while let Some(current) = stack.pop() {
+            for child in self.dependents.get(&current).into_iter().flatten() {
+                if seen.insert(child.clone()) {
+                    out.push(child.clone());
+                    stack.push(child.clone());
+                }
+            }
+        }
+        out.sort();
+        out
+    }
+}
+
+fn find_cycle(
+    start: &str,
+    edges: &HashMap<String, Vec<String>>,
+    stuck: &HashSet<String>,
+) -> Vec<String> {
+    let mut path: Vec<String> = vec![start.to_string()];
+    let mut current = start.to_string();
+    loop {
+        let Some(next) = edges
+            .get(&current)
+            .into_iter()
+            .flatten()
+            .find(|dep| stuck.contains(*dep))
+        else {
+            return Vec::new();
+        };
+        if let Some(pos) = path.iter().position(|v| v == next) {
+            return path[pos..].to_vec();
+        }
+        path.push(next.clone());
+        current = next.clone();
+    }
+}
+
+pub(crate) fn plan(tasks: &[(String, Vec<String>)], satisfied: &HashSet<String>) -> DagPlan {
+    let names: HashSet<String> = tasks.iter().map(|(name, _)| name.clone()).collect();
+    let mut out = DagPlan::default();
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (name, deps) in tasks {
+        let mut kept = Vec::new();
+        for dep in deps {
+            if names.contains(dep) {
+                kept.push(dep.clone());
+                out.dependents
+                    .entry(dep.clone())
+                    .or_default()
+                    .push(name.clone());
+            } else if !satisfied.contains(dep) {
+                out.issues
+                    .push(format!("missing dependency: {} -> {}", name, dep));
+                out.invalid
+                    .entry(name.clone())
+                    .or_insert_with(|| format!("missing dependency {}", dep));
+            }
+        }
+        edges.insert(name.clone(), kept);
+    }
+
+    let mut indegree: HashMap<String, usize> = edges
+        .iter()
+        .map(|(name, deps)| (name.clone(), deps.len()))
+        .collect();
+    let mut queue: Vec<String> = indegree
+        .iter()
+        .filter(|(_, n)| **n == 0)
+        .map(|(name, _)| name.clone())
+        .collect();
+    let mut topo: Vec<String> = Vec::new();
+    while let Some(name) = queue.pop() {
+        for child in out.dependents.get(&name).into_iter().flatten() {
+            if let Some(n) = indegree.get_mut(child) {
+                *n -= 1;
+                if *n == 0 {
+                    queue.push(child.clone());
+                }
+            }
+        }
+        topo.push(name);
+    }
+
+    let stuck: HashSet<String> = names
+        .iter()
+        .filter(|name| !topo.contains(name))
+        .cloned()
+        .collect();
+    let mut stuck_sorted: Vec<&String> = stuck.iter().collect();
+    stuck_sorted.sort();
+    let mut reported: HashSet<String> = HashSet::new();
+    for name in stuck_sorted {
+        let cycle = find_cycle(name, &edges, &stuck);
+        if cycle.is_empty() || cycle.iter().any(|v| reported.contains(v)) {
+            continue;
+        }
+        let mut shown = cycle.clone();
+        shown.push(cycle[0].clone());
+        let text = shown.join(" -> ");
+        out.issues.push(format!("dependency cycle: {}", text));
+        for member in &cycle {
+            reported.insert(member.clone());
+            out.invalid
+                .insert(member.clone(), format!("dependency cycle {}", text));
+        }
+    }
+
+    let roots: Vec<String> = out.invalid.keys().cloned().collect();
+    for root in roots {
+        for child in out.descendants(&root) {
+            out.invalid
+                .entry(child)
+                .or_insert_with(|| format!("blocked by invalid dependency {}", root));
+        }
+    }
+    for name in &stuck {
+        out.invalid
+            .entry(name.clone())
+            .or_insert_with(|| "blocked by dependency cycle".to_string());
+    }
+
+    for name in topo.iter().rev() {
+        let best = out
+            .dependents
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|child| out.priority.get(child).copied().unwrap_or(0))
+            .max()
+            .unwrap_or(0);
+        out.priority.insert(name.clone(), best + 1);
+    }
+    out
+}
+
+pub(crate) fn order_ready(ready: &mut [String], plan: &DagPlan) {
+    ready.sort_by(|a, b| {
+        plan.priority_of(b)
+            .cmp(&plan.priority_of(a))
+            .then_with(|| a.cmp(b))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(name: &str, deps: &[&str]) -> (String, Vec<String>) {
+        (
+            name.to_string(),
+            deps.iter().map(|v| v.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn plan_reports_missing_names_and_cycles_and_blocks_dependents() {
+        let tasks = vec![
+            task("a", &[]),
+            task("b", &["a", "ghost"]),
+            task("c", &["b"]),
+            task("x", &["y"]),
+            task("y", &["x"]),
+            task("z", &["y"]),
+            task("done_dep", &["cleared"]),
+        ];
+        let satisfied: HashSet<String> = ["cleared".to_string()].into_iter().collect();
+        let plan = plan(&tasks, &satisfied);
+        assert!(plan
+            .issues
+            .contains(&"missing dependency: b -> ghost".to_string()));
+        assert!(plan
+            .issues
+            .iter()
+            .any(|v| v == "dependency cycle: x -> y -> x"));
+        assert_eq!(
+            plan.invalid.get("b").map(String::as_str),
+            Some("missing dependency ghost")
+        );
+        assert_eq!(
+            plan.invalid.get("c").map(String::as_str),
+            Some("blocked by invalid dependency b")
+        );
+        assert!(plan.invalid.contains_key("z"));
+        assert!(!plan.invalid.contains_key("a"));
+        assert!(!plan.invalid.contains_key("done_dep"));
+    }
+
+    #[test]
+    fn critical_path_tasks_are_ordered_first() {
+        let tasks = vec![
+            task("leaf", &[]),
+            task("base", &[]),
+            task("mid", &["base"]),
+            task("top", &["mid"]),
+        ];
+        let plan = plan(&tasks, &HashSet::new());
+        assert!(plan.issues.is_empty());
+        assert_eq!(plan.priority_of("base"), 3);
+        assert_eq!(plan.priority_of("leaf"), 1);
+        let mut ready = vec!["leaf".to_string(), "base".to_string()];
+        order_ready(&mut ready, &plan);
+        assert_eq!(ready, vec!["base".to_string(), "leaf".to_string()]);
+        assert_eq!(
+            plan.descendants("base"),
+            vec!["mid".to_string(), "top".to_string()]
+        );
+    }
+}
@@ -0,0 +1,637 @@
+use super::dag;
+use super::live::{self, StatusBoard};
+use super::sandbox::{self, TouchViolation, WorkspaceFingerprint};
+use super::worktree::{append_conflict_report, GitWorkspace, MergeOutcome, TaskWorktree};
+use crate::jobs::JobJournal;
+use crate::llm::{LlmBackend, LlmRequest};
+use crate::verify::{self, ConstraintResult};
+use crate::{
+    append_failure_log, append_spec_checkpoint_issues, config, load_app_config,
+    print_parallel_modal, ui,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EngineTask {
+    pub(crate) name: String,
+    pub(crate) prompt: String,
+    pub(crate) depends_on: Vec<String>,
+    pub(crate) touches: Vec<String>,
+    pub(crate) checks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EngineRun {
+    pub(crate) succeeded: Vec<String>,
+    pub(crate) failed: Vec<(String, String)>,
+    pub(crate) touches_violations: usize,
+    pub(crate) verification: BTreeMap<String, Vec<ConstraintResult>>,
+}
+
+type TaskOutcome = (Result<String, String>, Vec<ConstraintResult>);
+
+pub(crate) type StatusSink = Arc<dyn Fn(&str, ui::TaskRuntimeState) + Send + Sync>;
+
+tokio::task_local! {
+    static STATUS_SINK: StatusSink;
+}
+
+pub(crate) async fn with_status_sink<F: Future>(sink: StatusSink, fut: F) -> F::Output {
+    STATUS_SINK.scope(sink, fut).await
+}
+
+fn publish_status(name: &str, state: ui::TaskRuntimeState) {
+    let _ = STATUS_SINK.try_with(|sink| sink(name, state));
+}
+
+struct EngineSettings {
+    backend: Arc<dyn LlmBackend>,
+    max_parallel: usize,
+    timeout_sec: u64,
+    debug_enabled: bool,
+    worktree_isolation: bool,
+    revert_outside: bool,
+}
+
+impl EngineSettings {
+    fn load() -> Result<Self, String> {
+        let app_conf = load_app_config();
+        if let Some(conf) = app_conf.as_ref() {
+            conf.validate_parallel()
+                .map_err(|e| format!("invalid parallel config: {}", e))?;
+        }
+        Ok(Self {
+            backend: Arc::from(crate::llm::backend_from_config(app_conf.as_ref())),
+            max_parallel: app_conf
+                .as_ref()
+                .map_or(10, config::AppConfig::default_max_parallel)
+                .max(1),
+            timeout_sec: app_conf
+                .as_ref()
+                .map_or(300, config::AppConfig::default_timeout_sec),
+            debug_enabled: app_conf
+                .as_ref()
+                .is_none_or(config::AppConfig::debug_enabled),
+            worktree_isolation: app_conf
+                .as_ref()
+                .is_none_or(config::AppConfig::worktree_isolation_enabled),
+            revert_outside: app_conf
+                .as_ref()
+                .is_some_and(config::AppConfig::touches_revert_enabled),
+        })
+    }
+}
+
+fn update_task_status(
+    statuses: &[(String, ui::TaskRuntimeState)],
+    target: &str,
+    state: ui::TaskRuntimeState,
+) -> Vec<(String, ui::TaskRuntimeState)> {
+    statuses
+        .iter()
+        .map(|(name, old)| {
+            if name == target {
+                (name.clone(), state)
+            } else {
+                (name.clone(), *old)
+            }
+        })
+        .collect()
+}
+
+async fn run_one_parallel_task(
+    backend: Arc<dyn LlmBackend>,
+    task_name: String,
+    prompt: String,
+    checks: Vec<String>,
+    run_dir: PathBuf,
+    timeout_sec: u64,
+    debug_enabled: bool,
+) -> TaskOutcome {
+    append_task_runtime_log(
+        debug_enabled,
+        &task_name,
+        "시작/프롬프트 전송",
+        &format!("{} 호출을 시작했습니다.", backend.label()),
+    );
+    let run_fut = run_backend_status(
+        backend.clone(),
+        &task_name,
+        prompt,
+        run_dir.clone(),
+        timeout_sec,
+    );
+    let code = match tokio::time::timeout(Duration::from_secs(timeout_sec), run_fut).await {
+        Ok(Ok(code)) => code,
+        Ok(Err(e)) => {
+            append_task_runtime_log(
+                debug_enabled,
+                &task_name,
+                "완료/실패",
+                &format!("프로세스 실행 실패: {}", e),
+            );
+            return (
+                Err(format!("failed to run command for {task_name}: {}", e)),
+                Vec::new(),
+            );
+        }
+        Err(_) => {
+            append_task_runtime_log(
+                debug_enabled,
+                &task_name,
+                "완료/실패",
+                &format!("timeout ({timeout_sec}s)"),
+            );
+            return (
+                Err(format!("timeout ({timeout_sec}s) for {task_name}")),
+                Vec::new(),
+            );
+        }
+    };
+    append_task_runtime_log(
+        debug_enabled,
+        &task_name,
+        "LLM 응답 수신",
+        &format!("{} 종료 code={:?}", backend.label(), code),
+    );
+    if code != Some(0) {
+        append_task_runtime_log(
+            debug_enabled,
+            &task_name,
+            "완료/실패",
+            &format!("실패 code={:?}", code),
+        );
+        return (
+            Err(format!("{} failed with exit code {:?}", task_name, code)),
+            Vec::new(),
+        );
+    }
+    append_task_runtime_log(
+        debug_enabled,
+        &task_name,
+        "검증 단계",
+        &format!("check/contracts {}개를 실행합니다.", checks.len()),
+    );
+    let results = if checks.is_empty() {
+        Vec::new()
+    } else {
+        crate::context::spawn_blocking(move || verify::run_checks(&run_dir, &checks, timeout_sec))
+            .await
+            .unwrap_or_default()
+    };
+    let failed: Vec<String> = verify::failures(&results)
+        .into_iter()
+        .map(|r| format!("{} ({})", r.constraint, r.detail))
+        .collect();
+    if !failed.is_empty() {
+        append_task_runtime_log(
+            debug_enabled,
+            &task_name,
+            "완료/실패",
+            &format!("검증 실패: {}", failed.join(" | ")),
+        );
+        return (
+            Err(format!(
+                "{} verification failed: {}",
+                task_name,
+                failed.join(" | ")
+            )),
+            results,
+        );
+    }
+    append_task_runtime_log(
+        debug_enabled,
+        &task_name,
+        "파일 반영 단계",
+        "LLM 작업 결과를 워크스페이스에 반영 완료로 간주합니다.",
+    );
+    append_task_runtime_log(
+        debug_enabled,
+        &task_name,
+        "완료/실패",
+        &format!("완료 ({})", verify::summary(&results)),
+    );
+    (Ok(task_name), results)
+}
+
+// Kills the task pane if the run gives up on it (timeout or cancel) before
+// the command wrote its exit code.
+struct PaneTask<'a> {
+    pane_id: String,
+    script: &'a crate::chat::LlmPaneScript,
+}
+
+impl Drop for PaneTask<'_> {
+    fn drop(&mut self) {
+        if !self.script.finished() {
+            let _ = crate::tmux::kill_pane(&self.pane_id);
+        }
+    }
+}
+
+async fn run_backend_status(
+    backend: Arc<dyn LlmBackend>,
+    task_name: &str,
+    prompt: String,
+    run_dir: PathBuf,
+    timeout_sec: u64,
+) -> Result<Option<i32>, String> {
+    let request = LlmRequest::new(&prompt, timeout_sec).in_dir(&run_dir);
+    let runtime = live::runtime_dir(&crate::context::project_dir());
+    let out_log = live::create_out_log(&runtime, task_name)?;
+    let root = crate::context::project_root();
+    if let (Some(command), Some(_)) = (
+        backend.command(&request),
+        crate::tmux::layout::active(&root),
+    ) {
+        let script =
+            crate::chat::write_llm_pane_script(&run_dir, &command, &prompt, &backend.label())?;
+        let pane = crate::tmux::layout::open_task_pane(
+            &root,
+            task_name,
+            &script.command,
+            Some(&live::out_log_path(&runtime, task_name)),
+        )?;
+        let _pane = PaneTask {
+            pane_id: pane.pane_id,
+            script: &script,
+        };
+        while !script.finished() {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        return Ok(script.output()?.code);
+    }
+    if let Some(command) = backend.command(&request) {
+        let mut command = tokio::process::Command::from(command);
+        command
+            .kill_on_drop(true)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let (mut child, _tracked) =
+            crate::process_group::spawn_tracked_tokio(&mut command).map_err(|e| e.to_string())?;
+        let err_log = out_log.try_clone().map_err(|e| e.to_string())?;
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let (status, _, _) = tokio::join!(
+            child.wait(),
+            async {
+                if let Some(stdout) = stdout {
+                    live::tee(stdout, out_log, std::io::stdout()).await;
+                }
+            },
+            async {
+                if let Some(stderr) = stderr {
+                    live::tee(stderr, err_log, std::io::stderr()).await;
+                }
+            },
+        );
+        return Ok(status.map_err(|e| e.to_string())?.code());
+    }
+    crate::context::spawn_blocking(move || {
+        let request = LlmRequest::new(&prompt, timeout_sec).in_dir(&run_dir);
+        backend.exec(&request).map(|out| {
+            print!("{}", out.stdout);
+            let mut out_log = out_log;
+            let _ = write!(out_log, "{}", out.stdout);
+            if !out.stderr.is_empty() {
+                let _ = writeln!(out_log, "{}", out.stderr);
+            }
+            out.code
+        })
+    })
+    .await?
+}
+
+fn append_task_runtime_log(debug_enabled: bool, task_name: &str, stage: &str, detail: &str) {
+    if !debug_enabled {
+        return;
+    }
+    let runtime_dir = crate::context::project_dir().join("runtime");
+    if fs::create_dir_all(&runtime_dir).is_err() {
+        return;
+    }
+    let log_path = runtime_dir.join(format!("{}.log", task_name));
+    let mut file = match fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+    {
+        Ok(f) => f,
+        Err(_) => return,
+    };
+    let _ = writeln!(file, "[{}] {} | {}", crate::now_unix(), stage, detail);
+}
+
+fn record_failure(
+    journal: &JobJournal,
+    board: &StatusBoard,
+    run: &mut EngineRun,
+    name: &str,
+    reason: String,
+) {
+    journal.task(name, "failed", &reason);
+    board.set(name, "failed");
+    publish_status(name, ui::TaskRuntimeState::Failed);
+    let _ = append_failure_log(name, &reason);
+    run.failed.push((name.to_string(), reason));
+}
+
+fn fail_dependents(
+    failed_task: &str,
+    dag_plan: &dag::DagPlan,
+    pending: &mut Vec<EngineTask>,
+    journal: &JobJournal,
+    board: &StatusBoard,
+    run: &mut EngineRun,
+) {
+    let blocked = dag_plan.descendants(failed_task);
+    let (dropped, kept): (Vec<EngineTask>, Vec<EngineTask>) = pending
+        .drain(..)
+        .partition(|task| blocked.contains(&task.name));
+    *pending = kept;
+    for task in dropped {
+        let reason = format!("blocked by failed dependency {}", failed_task);
+        record_failure(journal, board, run, &task.name, reason);
+    }
+}
+
+fn settle_worktree_task(
+    cwd: &Path,
+    ws: &GitWorkspace,
+    wt: &TaskWorktree,
+    name: String,
+    touches: &[String],
+    revert_outside: bool,
+    violations: &mut Vec<TouchViolation>,
+) -> Result<String, String> {
+    let merged = ws.changed_files(wt).and_then(|files| {
+        let outside = sandbox::out_of_scope(touches, &files);
+        violations.extend(outside.iter().map(|file| TouchViolation {
+            task: name.clone(),
+            file: file.clone(),
+            reverted: revert_outside,
+        }));
+        let skip = if revert_outside { outside } else { Vec::new() };
+        ws.merge_back(wt, &skip)
+    });
+    let keep_branch = matches!(merged, Ok(MergeOutcome::Conflict { .. }));
+    ws.discard(wt, keep_branch);
+    match merged {
+        Ok(MergeOutcome::Conflict { files, detail }) => {
+            let report = append_conflict_report(cwd, wt, &files, &detail)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|e| e);
+            Err(format!(
+                "{} merge conflict on [{}] (branch {} kept, see {})",
+                name,
+                files.join(", "),
+                wt.branch,
+                report
+            ))
+        }
+        Ok(_) => Ok(name),
+        Err(e) => Err(format!("{} merge failed: {}", name, e)),
+    }
+}
+
+fn open_tmux_run(cwd: &Path) -> Option<crate::tmux::layout::RunGuard> {
+    if !crate::tmux::layout::session_layout_enabled() {
+        return None;
+    }
+    match crate::tmux::layout::begin_run(cwd) {
+        Ok(guard) => {
+            println!(
+                "tmux: tasks run in session {} (attach: orc tmux-attach)",
+                guard.session()
+            );
+            Some(guard)
+        }
+        Err(e) => {
+            println!("tmux layout unavailable ({}); running tasks without panes", e);
+            None
+        }
+    }
+}
+
+pub(crate) async fn run_tasks(
+    journal: &JobJournal,
+    mut pending: Vec<EngineTask>,
+    satisfied: &HashSet<String>,
+) -> Result<EngineRun, String> {
+    let mut run = EngineRun::default();
+    if pending.is_empty() {
+        return Ok(run);
+    }
+    let cwd = crate::context::project_root();
+    let settings = EngineSettings::load()?;
+    let _tmux_run = open_tmux_run(&cwd);
+
+    let mut statuses: Vec<(String, ui::TaskRuntimeState)> = pending
+        .iter()
+        .map(|t| (t.name.clone(), ui::TaskRuntimeState::Inactive))
+        .collect();
+    print_parallel_modal(&statuses);
+    for (name, state) in &statuses {
+        publish_status(name, *state);
+    }
+    let board = StatusBoard::start(
+        &live::runtime_dir(&crate::context::project_dir()),
+        &statuses.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>(),
+    );
+
+    let workspace = if settings.worktree_isolation {
+        let detected = GitWorkspace::detect(&cwd);
+        if detected.is_none() && settings.revert_outside {
+            return Err(format!(
+                "touches_revert needs worktree isolation, but {} has no git HEAD",
+                cwd.display()
+            ));
+        }
+        if detected.is_none() {
+            println!(
+                "worktree isolation unavailable (no git HEAD); tasks share {}",
+                cwd.display()
+            );
+        }
+        detected
+    } else {
+        None
+    };
+    let mut violations: Vec<TouchViolation> = Vec::new();
+
+    let graph: Vec<(String, Vec<String>)> = pending
+        .iter()
+        .map(|task| (task.name.clone(), task.depends_on.clone()))
+        .collect();
+    let dag_plan = dag::plan(&graph, satisfied);
+    for issue in &dag_plan.issues {
+        println!("dag: {}", issue);
+    }
+    append_spec_checkpoint_issues("parallel_dag", &dag_plan.issues)?;
+    let (invalid, valid): (Vec<EngineTask>, Vec<EngineTask>) = pending
+        .into_iter()
+        .partition(|task| dag_plan.invalid.contains_key(&task.name));
+    pending = valid;
+    for task in invalid {
+        let reason = dag_plan.invalid[&task.name].clone();
+        record_failure(journal, &board, &mut run, &task.name, reason);
+    }
+
+    let mut finished: HashSet<String> = HashSet::new();
+    let mut running: JoinSet<TaskOutcome> = JoinSet::new();
+    let mut running_names: HashMap<tokio::task::Id, String> = HashMap::new();
+    let mut active: HashMap<String, (Vec<String>, Option<TaskWorktree>)> = HashMap::new();
+    let mut announced: HashSet<String> = HashSet::new();
+    let mut window_before: Option<WorkspaceFingerprint> = None;
+    let mut window_tasks: Vec<(String, Vec<String>)> = Vec::new();
+
+    loop {
+        let mut ready: Vec<String> = pending
+            .iter()
+            .filter(|task| {
+                task.depends_on
+                    .iter()
+                    .all(|dep| finished.contains(dep) || satisfied.contains(dep))
+            })
+            .map(|task| task.name.clone())
+            .collect();
+        dag::order_ready(&mut ready, &dag_plan);
+        for name in ready {
+            if active.len() >= settings.max_parallel {
+                break;
+            }
+            let Some(pos) = pending.iter().position(|task| task.name == name) else {
+                continue;
+            };
+            if let Some(other) = active
+                .iter()
+                .find(|(_, (touches, _))| sandbox::touches_overlap(touches, &pending[pos].touches))
+                .map(|(other, _)| other.clone())
+            {
+                if announced.insert(name.clone()) {
+                    println!("serialized {} after {} (overlapping touches)", name, other);
+                }
+                continue;
+            }
+            let task = pending.remove(pos);
+            statuses = update_task_status(&statuses, &task.name, ui::TaskRuntimeState::Active);
+            print_parallel_modal(&statuses);
+            publish_status(&task.name, ui::TaskRuntimeState::Active);
+            board.set(&task.name, "active");
+            let worktree = match workspace.as_ref() {
+                Some(ws) => match ws.snapshot().and_then(|base| ws.create(&task.name, &base)) {
+                    Ok(wt) => Some(wt),
+                    Err(e) => {
+                        let reason = format!("{} worktree setup failed: {}", task.name, e);
+                        record_failure(journal, &board, &mut run, &task.name, reason);
+                        fail_dependents(&task.name, &dag_plan, &mut pending, journal, &board, &mut run);
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            if worktree.is_none() {
+                if active.is_empty() {
+                    window_before = Some(sandbox::fingerprint(&cwd));
+                }
+                window_tasks.push((task.name.clone(), task.touches.clone()));
+            }
+            journal.task(&task.name, "worked", "");
+            let handle = running.spawn(crate::context::inherit(run_one_parallel_task(
+                settings.backend.clone(),
+                task.name.clone(),
+                task.prompt,
+                task.checks,
+                worktree
+                    .as_ref()
+                    .map_or(cwd.clone(), |wt| wt.run_dir.clone()),
+                settings.timeout_sec,
+                settings.debug_enabled,
+            )));
+            running_names.insert(handle.id(), task.name.clone());
+            active.insert(task.name, (task.touches, worktree));
+        }
+
+        let Some(joined) = running.join_next_with_id().await else {
+            for task in std::mem::take(&mut pending) {
+                let reason = format!("blocked by unresolved depends_on: {:?}", task.depends_on);
+                record_failure(journal, &board, &mut run, &task.name, reason);
+            }
+            break;
+        };
+        let (task_name, (outcome, results)) = match joined {
+            Ok((id, result)) => (running_names.remove(&id).unwrap_or_default(), result),
+            Err(join_err) => {
+                let name = running_names.remove(&join_err.id()).unwrap_or_default();
+                let reason = format!("{} join failed: {}", name, join_err);
+                (name, (Err(reason), Vec::new()))
+            }
+        };
+        if !results.is_empty() {
+            run.verification.insert(task_name.clone(), results);
+        }
+        let (touches, worktree) = active.remove(&task_name).unwrap_or_default();
+        let outcome = match (outcome, workspace.as_ref(), worktree.as_ref()) {
+            (Ok(name), Some(ws), Some(wt)) => settle_worktree_task(
+                &cwd,
+                ws,
+                wt,
+                name,
+                &touches,
+                settings.revert_outside,
+                &mut violations,
+            ),
+            (outcome, ws, wt) => {
+                if let (Some(ws), Some(wt)) = (ws, wt) {
+                    ws.discard(wt, false);
+                }
+                outcome
+            }
+        };
+        match outcome {
+            Ok(name) => {
+                let detail = run
+                    .verification
+                    .get(&name)
+                    .map(|results| verify::summary(results))
+                    .unwrap_or_default();
+                journal.task(&name, "complete", &detail);
+                finished.insert(name.clone());
+                statuses = update_task_status(&statuses, &name, ui::TaskRuntimeState::Clear);
+                print_parallel_modal(&statuses);
+                publish_status(&name, ui::TaskRuntimeState::Clear);
+                board.set(&name, "clear");
+                run.succeeded.push(name);
+            }
+            Err(reason) => {
+                record_failure(journal, &board, &mut run, &task_name, reason);
+                fail_dependents(&task_name, &dag_plan, &mut pending, journal, &board, &mut run);
+            }
+        }
+        if active.values().all(|(_, wt)| wt.is_some()) {
+            if let Some(before) = window_before.take() {
+                let changed = sandbox::changed_files(&before, &sandbox::fingerprint(&cwd));
+                violations.extend(sandbox::attribute_shared_changes(&window_tasks, &changed));
+                window_tasks.clear();
+            }
+        }
+    }
+    if !violations.is_empty() {
+        let issues = sandbox::violation_issues(&violations);
+        for issue in &issues {
+            println!("{}", issue);
+        }
+        sandbox::append_touches_report(&crate::context::project_path("report.md"), &violations)?;
+        append_spec_checkpoint_issues("parallel_touches", &issues)?;
+    }
+    run.touches_violations = violations.len();
+    Ok(run)
+}
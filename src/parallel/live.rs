@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::ui::TaskRuntimeState;
+
+// What the TUI (a separate process) reads to follow a run: a status board
+// plus per-task output and stage logs, all under `.project/runtime`.
+const STATUS_FILE: &str = "parallel-status.json";
+const TAIL_BYTES: u64 = 64 * 1024;
+
+pub(crate) fn runtime_dir(project_dir: &Path) -> PathBuf {
+    project_dir.join("runtime")
+}
+
+pub(crate) fn out_log_path(runtime: &Path, task: &str) -> PathBuf {
+    runtime.join(format!("{}.out.log", task))
+}
+
+// Written by `append_task_runtime_log` (debug only).
+pub(crate) fn stage_log_path(runtime: &Path, task: &str) -> PathBuf {
+    runtime.join(format!("{}.log", task))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct TaskStatus {
+    pub(crate) name: String,
+    // `inactive`, `active`, `clear` or `failed`.
+    pub(crate) state: String,
+    #[serde(default)]
+    pub(crate) started_at: Option<u64>,
+    #[serde(default)]
+    pub(crate) finished_at: Option<u64>,
+}
+
+impl TaskStatus {
+    pub(crate) fn runtime_state(&self) -> TaskRuntimeState {
+        match self.state.as_str() {
+            "active" => TaskRuntimeState::Active,
+            "clear" => TaskRuntimeState::Clear,
+            "failed" => TaskRuntimeState::Failed,
+            _ => TaskRuntimeState::Inactive,
+        }
+    }
+
+    pub(crate) fn elapsed_sec(&self, now: u64) -> Option<u64> {
+        let started = self.started_at?;
+        Some(self.finished_at.unwrap_or(now).saturating_sub(started))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub(crate) struct Board {
+    pub(crate) started_at: u64,
+    pub(crate) tasks: Vec<TaskStatus>,
+}
+
+pub(crate) struct StatusBoard {
+    path: PathBuf,
+    board: Mutex<Board>,
+}
+
+impl StatusBoard {
+    pub(crate) fn start(runtime: &Path, names: &[String]) -> Self {
+        let board = Board {
+            started_at: crate::now_unix_secs(),
+            tasks: names
+                .iter()
+                .map(|name| TaskStatus {
+                    name: name.clone(),
+                    state: "inactive".to_string(),
+                    started_at: None,
+                    finished_at: None,
+                })
+                .collect(),
+        };
+        let status = Self {
+            path: runtime.join(STATUS_FILE),
+            board: Mutex::new(board),
+        };
+        status.save();
+        status
+    }
+
+    pub(crate) fn set(&self, name: &str, state: &str) {
+        let now = crate::now_unix_secs();
+        if let Ok(mut board) = self.board.lock() {
+            if let Some(task) = board.tasks.iter_mut().find(|t| t.name == name) {
+                task.state = state.to_string();
+                match state {
+                    "active" => task.started_at = Some(now),
+                    "clear" | "failed" => task.finished_at = Some(now),
+                    _ => {}
+                }
+            }
+        }
+        self.save();
+    }
+
+    // Best effort: a missing board only costs the TUI its live view.
+    fn save(&self) {
+        let Ok(board) = self.board.lock() else {
+            return;
+        };
+        let Ok(raw) = serde_json::to_vec_pretty(&*board) else {
+            return;
+        };
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        if fs::write(&tmp, raw).is_ok() {
+            let _ = fs::rename(&tmp, &self.path);
+        }
+    }
+}
+
+pub(crate) fn read_board(runtime: &Path) -> Option<Board> {
+    let raw = fs::read(runtime.join(STATUS_FILE)).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+// Starts the task's output log afresh for this run.
+pub(crate) fn create_out_log(runtime: &Path, task: &str) -> Result<fs::File, String> {
+    fs::create_dir_all(runtime)
+        .map_err(|e| format!("failed to create {}: {}", runtime.display(), e))?;
+    let path = out_log_path(runtime, task);
+    fs::File::create(&path).map_err(|e| format!("failed to create {}: {}", path.display(), e))
+}
+
+// Copies a child stream into the log while still echoing it, so CLI runs
+// keep their terminal output.
+pub(crate) async fn tee<R: AsyncRead + Unpin>(
+    mut reader: R,
+    mut log: fs::File,
+    mut echo: impl Write,
+) {
+    let mut buf = [0u8; 8192];
+    loop {
+        match reader.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let _ = log.write_all(&buf[..n]);
+                let _ = echo.write_all(&buf[..n]);
+                let _ = echo.flush();
+            }
+        }
+    }
+}
+
+// Last `lines` lines of a log, with terminal control sequences stripped.
+pub(crate) fn tail(path: &Path, lines: usize) -> Vec<String> {
+    let Ok(mut file) = fs::File::open(path) else {
+        return Vec::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(TAIL_BYTES);
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return Vec::new();
+    }
+    let mut raw = Vec::new();
+    let _ = file.read_to_end(&mut raw);
+    let text = strip_control(&String::from_utf8_lossy(&raw));
+    let mut all: Vec<&str> = text.lines().collect();
+    // The first line may be cut by the byte window.
+    if start > 0 && !all.is_empty() {
+        all.remove(0);
+    }
+    let skip = all.len().saturating_sub(lines);
+    all[skip..].iter().map(|l| l.to_string()).collect()
+}
+
+// `[<ts>] <stage> | <detail>` lines; returns `<stage> | <detail>`.
+pub(crate) fn last_stage(runtime: &Path, task: &str) -> Option<String> {
+    let raw = fs::read_to_string(stage_log_path(runtime, task)).ok()?;
+    let line = raw.lines().rev().find(|l| !l.trim().is_empty())?;
+    let stage = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+        .map_or(line, |(_, stage)| stage);
+    Some(stage.to_string())
+}
+
+fn strip_control(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => {
+                // CSI `ESC [ ... final` or a two-character escape.
+                if chars.next_if_eq(&'[').is_some() {
+                    while chars.next().is_some_and(|c| !('@'..='~').contains(&c)) {}
+                } else {
+                    chars.next();
+                }
+            }
+            '\r' => {
+                if chars.peek() != Some(&'\n') {
+                    // Progress redraws: keep only what follows the last `\r`.
+                    let line_start = out.rfind('\n').map_or(0, |i| i + 1);
+                    out.truncate(line_start);
+                }
+            }
+            '\t' | '\n' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_tracks_task_times_and_logs_are_tailed_clean() {
+        let runtime = std::env::temp_dir().join(format!("orc-live-test-{}", std::process::id()));
+        let names = vec!["login".to_string(), "signup".to_string()];
+        let board = StatusBoard::start(&runtime, &names);
+        board.set("login", "active");
+        board.set("login", "failed");
+        let read = read_board(&runtime).expect("board");
+        assert_eq!(read.tasks.len(), 2);
+        let login = &read.tasks[0];
+        assert_eq!(login.runtime_state(), TaskRuntimeState::Failed);
+        assert!(login.started_at.is_some() && login.finished_at.is_some());
+        assert_eq!(read.tasks[1].elapsed_sec(read.started_at), None);
+
+        let mut log = create_out_log(&runtime, "login").expect("log");
+        log.write_all(b"\x1b[32mok\x1b[0m build\r\n10%\r55%\r100%\nlast line\n")
+            .expect("write");
+        assert_eq!(
+            tail(&out_log_path(&runtime, "login"), 2),
+            vec!["100%", "last line"]
+        );
+        assert_eq!(tail(&out_log_path(&runtime, "login"), 5)[0], "ok build");
+
+        fs::write(
+            stage_log_path(&runtime, "login"),
+            "[100] 시작/프롬프트 전송 | codex exec\n[105] 검증 단계 | check 2개\n",
+        )
+        .expect("stage");
+        assert_eq!(
+            last_stage(&runtime, "login").as_deref(),
+            Some("검증 단계 | check 2개")
+        );
+        let _ = fs::remove_dir_all(&runtime);
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TouchViolation {
+    pub(crate) task: String,
+    pub(crate) file: String,
+    pub(crate) reverted: bool,
+}
+
+pub(crate) type WorkspaceFingerprint = BTreeMap<String, u64>;
+
+fn normalize(raw: &str) -> String {
+    raw.trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .replace('\\', "/")
+}
+
+fn glob_match(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            glob_match(&pattern[1..], path) || (!path.is_empty() && glob_match(pattern, &path[1..]))
+        }
+        (Some(p), Some(s)) => {
+            segment_match(p.as_bytes(), s.as_bytes()) && glob_match(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+fn segment_match(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            segment_match(&pattern[1..], text)
+                || (!text.is_empty() && segment_match(pattern, &text[1..]))
+        }
+        (Some(b'?'), Some(_)) => segment_match(&pattern[1..], &text[1..]),
+        (Some(p), Some(t)) if p == t => segment_match(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+pub(crate) fn touch_matches(pattern: &str, file: &str) -> bool {
+    let pattern = normalize(pattern);
+    let file = normalize(file);
+    if pattern.is_empty() {
+        return false;
+    }
+    if pattern == file || file.starts_with(&format!("{}/", pattern)) {
+        return true;
+    }
+    let pattern_parts: Vec<&str> = pattern.split('/').collect();
+    let file_parts: Vec<&str> = file.split('/').collect();
+    glob_match(&pattern_parts, &file_parts)
+}
+
+fn literal_prefix(pattern: &str) -> String {
+    let pattern = normalize(pattern);
+    let cut = pattern.find(['*', '?']).unwrap_or(pattern.len());
+    pattern[..cut].to_string()
+}
+
+pub(crate) fn touches_overlap(a: &[String], b: &[String]) -> bool {
+    a.iter().any(|left| {
+        b.iter().any(|right| {
+            let l = literal_prefix(left);
+            let r = literal_prefix(right);
+            if l.is_empty() || r.is_empty() {
+                return true;
+            }
+            l == r
+                || l.starts_with(&format!("{}/", r.trim_end_matches('/')))
+                || r.starts_with(&format!("{}/", l.trim_end_matches('/')))
+                || (l.len() < normalize(left).len() && r.starts_with(&l))
+                || (r.len() < normalize(right).len() && l.starts_with(&r))
+        })
+    })
+}
+
+pub(crate) fn out_of_scope(touches: &[String], files: &[String]) -> Vec<String> {
+    if touches.is_empty() {
+        return Vec::new();
+    }
+    files
+        .iter()
+        .filter(|file| !touches.iter().any(|pattern| touch_matches(pattern, file)))
+        .cloned()
+        .collect()
+}
+
+pub(crate) fn fingerprint(root: &Path) -> WorkspaceFingerprint {
+    let mut out = BTreeMap::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let name = entry.file_name();
+                if !crate::SCAN_SKIP_DIRS.iter().any(|skip| name == *skip) {
+                    stack.push(path);
+                }
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            let Ok(bytes) = fs::read(&path) else {
+                continue;
+            };
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            if let Ok(rel) = path.strip_prefix(root) {
+                out.insert(rel.to_string_lossy().replace('\\', "/"), hasher.finish());
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn changed_files(
+    before: &WorkspaceFingerprint,
+    after: &WorkspaceFingerprint,
+) -> Vec<String> {
+    let mut out: Vec<String> = after
+        .iter()
+        .filter(|(path, hash)| before.get(*path) != Some(*hash))
+        .map(|(path, _)| path.clone())
+        .collect();
+    out.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    out.sort();
+    out
+}
+
+pub(crate) fn attribute_shared_changes(
+    tasks: &[(String, Vec<String>)],
+    files: &[String],
+) -> Vec<TouchViolation> {
+    let mut out = Vec::new();
+    for file in files {
+        if tasks.iter().any(|(_, touches)| {
+            touches.is_empty() || touches.iter().any(|p| touch_matches(p, file))
+        }) {
+            continue;
+        }
+        let owner = if tasks.len() == 1 {
+            tasks[0].0.clone()
+        } else {
+            format!(
+                "unattributed({})",
+                tasks
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        out.push(TouchViolation {
+            task: owner,
+            file: file.clone(),
+            reverted: false,
+        });
+    }
+    out
+}
+
+pub(crate) fn violation_issues(violations: &[TouchViolation]) -> Vec<String> {
+    violations
+        .iter()
+        .map(|v| {
+            format!(
+                "touches 위반: {} -> {} ({})",
+                v.task,
+                v.file,
+                if v.reverted { "reverted" } else { "kept" }
+            )
+        })
+        .collect()
+}
+
+pub(crate) fn append_touches_report(
+    report: &Path,
+    violations: &[TouchViolation],
+) -> Result<(), String> {
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let mut body = fs::read_to_string(report).unwrap_or_default();
+    if !body.is_empty() && !body.ends_with('\n') {
+        body.push('\n');
+    }
+    if !body.is_empty() {
+        body.push('\n');
+    }
+    body.push_str("# touches 검사\n");
+    for issue in violation_issues(violations) {
+        body.push_str(&format!("- {}\n", issue));
+    }
+    fs::write(report, body).map_err(|e| format!("failed to write {}: {}", report.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: &[&str]) -> Vec<String> {
+        items.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn touches_match_paths_dirs_and_globs() {
+        assert!(touch_matches("src/main.rs", "./src/main.rs"));
+        assert!(touch_matches("src/api/", "src/api/routes.rs"));
+        assert!(touch_matches("src/**/*.rs", "src/a/b/c.rs"));
+        assert!(touch_matches("src/*.rs", "src/lib.rs"));
+        assert!(!touch_matches("src/*.rs", "src/a/lib.rs"));
+        assert!(!touch_matches("src/api", "src/apiary.rs"));
+        assert_eq!(
+            out_of_scope(
+                &list(&["src/api/**"]),
+                &list(&["src/api/x.rs", "Cargo.toml"])
+            ),
+            list(&["Cargo.toml"])
+        );
+        assert!(out_of_scope(&[], &list(&["anything"])).is_empty());
+    }
+
+    #[test]
+    fn overlapping_touches_are_detected_conservatively() {
+        assert!(touches_overlap(
+            &list(&["src/api"]),
+            &list(&["src/api/routes.rs"])
+        ));
+        assert!(touches_overlap(
+            &list(&["src/**/*.rs"]),
+            &list(&["src/ui/mod.rs"])
+        ));
+        assert!(touches_overlap(&list(&["src/ap*"]), &list(&["src/api.rs"])));
+        assert!(!touches_overlap(&list(&["src/api"]), &list(&["src/ui"])));
+        assert!(!touches_overlap(
+            &list(&["src/api"]),
+            &list(&["src/apiary.rs"])
+        ));
+    }
+
+    #[test]
+    fn shared_changes_are_attributed_by_touches() {
+        let tasks = vec![
+            ("a".to_string(), list(&["src/a.rs"])),
+            ("b".to_string(), list(&["src/b.rs"])),
+        ];
+        let violations = attribute_shared_changes(&tasks, &list(&["src/a.rs", "README.md"]));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].task, "unattributed(a, b)");
+        assert_eq!(violations[0].file, "README.md");
+        let solo = attribute_shared_changes(&tasks[..1], &list(&["src/b.rs"]));
+        assert_eq!(solo[0].task, "a");
+    }
+}
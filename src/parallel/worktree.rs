@@ -0,0 +1,475 @@
+use std::ffi::OsStr;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Clone)]
+pub(crate) struct GitWorkspace {
+    toplevel: PathBuf,
+    prefix: PathBuf,
+    worktrees_root: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TaskWorktree {
+    pub(crate) task: String,
+    pub(crate) branch: String,
+    pub(crate) path: PathBuf,
+    pub(crate) run_dir: PathBuf,
+    base: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MergeOutcome {
+    NoChanges,
+    Merged { files: Vec<String> },
+    Conflict { files: Vec<String>, detail: String },
+}
+
+const PROJECT_META_EXCLUDE: &str = ":(exclude).project";
+
+static NEXT_SNAPSHOT: AtomicU64 = AtomicU64::new(0);
+static NEXT_WORKTREE: AtomicU64 = AtomicU64::new(0);
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    git_env(dir, args, &[])
+}
+
+fn git_env(dir: &Path, args: &[&str], env: &[(&str, &OsStr)]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .envs(env.iter().copied())
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("failed to run git {}: {}", args.join(" "), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn branch_safe(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let trimmed = cleaned.trim_matches('-');
+    if trimmed.is_empty() {
+        "task".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+impl GitWorkspace {
+    pub(crate) fn detect(project_dir: &Path) -> Option<Self> {
+        let toplevel = git(project_dir, &["rev-parse", "--show-toplevel"]).ok()?;
+        let prefix = git(project_dir, &["rev-parse", "--show-prefix"]).ok()?;
+        let common_dir = git(project_dir, &["rev-parse", "--git-common-dir"]).ok()?;
+        git(project_dir, &["rev-parse", "--verify", "HEAD"]).ok()?;
+        let common_dir = PathBuf::from(common_dir.trim());
+        let common_dir = if common_dir.is_absolute() {
+            common_dir
+        } else {
+            project_dir.join(common_dir)
+        };
+        Some(Self {
+            toplevel: PathBuf::from(toplevel.trim()),
+            prefix: PathBuf::from(prefix.trim()),
+            worktrees_root: common_dir.join("orc-worktrees"),
+        })
+    }
+
+    // Commit of the current main tree, untracked files included: merged
+    // tasks leave their new files uncommitted, and dependents must see them.
+    // Built in a throwaway index so the user's staging area is untouched.
+    pub(crate) fn snapshot(&self) -> Result<String, String> {
+        fs::create_dir_all(&self.worktrees_root)
+            .map_err(|e| format!("failed to create {}: {}", self.worktrees_root.display(), e))?;
+        let index = self.worktrees_root.join(format!(
+            "snapshot-{}-{}.index",
+            std::process::id(),
+            NEXT_SNAPSHOT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = self.snapshot_with_index(&index);
+        let _ = fs::remove_file(&index);
+        result
+    }
+
+    fn snapshot_with_index(&self, index: &Path) -> Result<String, String> {
+        let env = [("GIT_INDEX_FILE", index.as_os_str())];
+        git_env(&self.toplevel, &["read-tree", "HEAD"], &env)?;
+        // Untracked runtime files under `.project` stay out; tracked
+        // metadata changes are still picked up by `add -u`.
+        git_env(
+            &self.toplevel,
+            &["add", "-A", "--", ".", ":(exclude,glob)**/.project/**"],
+            &env,
+        )?;
+        git_env(&self.toplevel, &["add", "-u"], &env)?;
+        let tree = git_env(&self.toplevel, &["write-tree"], &env)?;
+        let head = git(&self.toplevel, &["rev-parse", "HEAD"])?;
+        if git(&self.toplevel, &["rev-parse", "HEAD^{tree}"])?.trim() == tree.trim() {
+            return Ok(head.trim().to_string());
+        }
+        Ok(git(
+            &self.toplevel,
+            &[
+                "-c",
+                "user.name=orc",
+                "-c",
+                "user.email=orc@localhost",
+                "commit-tree",
+                tree.trim(),
+                "-p",
+                head.trim(),
+                "-m",
+                "orc parallel snapshot",
+            ],
+        )?
+        .trim()
+        .to_string())
+    }
+
+    pub(crate) fn create(&self, task: &str, base: &str) -> Result<TaskWorktree, String> {
+        fs::create_dir_all(&self.worktrees_root)
+            .map_err(|e| format!("failed to create {}: {}", self.worktrees_root.display(), e))?;
+        // Same-second starts and branches kept by an earlier `discard` must
+        // not collide, so bump the counter until the branch name is free.
+        let (slug, branch) = loop {
+            let slug = format!(
+                "{}-{}-{}",
+                branch_safe(task),
+                crate::now_unix(),
+                NEXT_WORKTREE.fetch_add(1, Ordering::Relaxed)
+            );
+            let branch = format!("orc/{}", slug);
+            if git(&self.toplevel, &["rev-parse", "--verify", "--quiet", &branch]).is_err() {
+                break (slug, branch);
+            }
+        };
+        let path = self.worktrees_root.join(&slug);
+        if path.exists() {
+            let _ = git(
+                &self.toplevel,
+                &["worktree", "remove", "--force", &path.display().to_string()],
+            );
+        }
+        git(
+            &self.toplevel,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                &branch,
+                &path.display().to_string(),
+                base,
+            ],
+        )?;
+        let run_dir = path.join(&self.prefix);
+        fs::create_dir_all(&run_dir)
+            .map_err(|e| format!("failed to create {}: {}", run_dir.display(), e))?;
+        Ok(TaskWorktree {
+            task: task.to_string(),
+            branch,
+            path,
+            run_dir,
+            base: base.to_string(),
+        })
+    }
+
+    pub(crate) fn changed_files(&self, wt: &TaskWorktree) -> Result<Vec<String>, String> {
+        git(&wt.path, &["add", "-A"])?;
+        let staged = git(&wt.path, &["diff", "--cached", "--name-only"])?;
+        if !staged.trim().is_empty() {
+            git(
+                &wt.path,
+                &[
+                    "-c",
+                    "user.name=orc",
+                    "-c",
+                    "user.email=orc@localhost",
+                    "commit",
+                    "-q",
+                    "--no-verify",
+                    "-m",
+                    &format!("orc: {}", wt.task),
+                ],
+            )?;
+        }
+        let range = format!("{}..HEAD", wt.base);
+        Ok(git(
+            &wt.run_dir,
+            &[
+                "diff",
+                "--name-only",
+                "--relative",
+                &range,
+                "--",
+                ".",
+                PROJECT_META_EXCLUDE,
+            ],
+        )?
+        .lines()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect())
+    }
+
+    pub(crate) fn merge_back(
+        &self,
+        wt: &TaskWorktree,
+        skip: &[String],
+    ) -> Result<MergeOutcome, String> {
+        let files: Vec<String> = self
+            .changed_files(wt)?
+            .into_iter()
+            .filter(|file| !skip.contains(file))
+            .collect();
+        if files.is_empty() {
+            return Ok(MergeOutcome::NoChanges);
+        }
+        let range = format!("{}..HEAD", wt.base);
+        let skip_specs: Vec<String> = skip
+            .iter()
+            .map(|file| format!(":(exclude){}", file))
+            .collect();
+        let mut diff_args = vec![
+            "diff",
+            "--binary",
+            range.as_str(),
+            "--",
+            ".",
+            PROJECT_META_EXCLUDE,
+        ];
+        diff_args.extend(skip_specs.iter().map(String::as_str));
+        let patch = git(&wt.run_dir, &diff_args)?;
+        let patch_path = self
+            .worktrees_root
+            .join(format!("{}.patch", branch_safe(&wt.branch)));
+        fs::write(&patch_path, patch)
+            .map_err(|e| format!("failed to write {}: {}", patch_path.display(), e))?;
+        let patch_arg = patch_path.display().to_string();
+        let result = match git(
+            &self.toplevel,
+            &["apply", "--check", "--binary", &patch_arg],
+        ) {
+            Ok(_) => git(&self.toplevel, &["apply", "--binary", &patch_arg]).map(|_| {
+                MergeOutcome::Merged {
+                    files: files.clone(),
+                }
+            }),
+            Err(detail) => Ok(MergeOutcome::Conflict { files, detail }),
+        };
+        let _ = fs::remove_file(&patch_path);
+        result
+    }
+
+    pub(crate) fn discard(&self, wt: &TaskWorktree, keep_branch: bool) {
+        let _ = git(
+            &self.toplevel,
+            &[
+                "worktree",
+                "remove",
+                "--force",
+                &wt.path.display().to_string(),
+            ],
+        );
+        if !keep_branch {
+            let _ = git(&self.toplevel, &["branch", "-D", &wt.branch]);
+        }
+    }
+}
+
+pub(crate) fn append_conflict_report(
+    project_dir: &Path,
+    wt: &TaskWorktree,
+    files: &[String],
+    detail: &str,
+) -> Result<PathBuf, String> {
+    let path = project_dir.join(".project").join("conflicts.md");
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    let mut body = String::new();
+    if is_new {
+        body.push_str("# conflicts\n");
+    }
+    body.push_str(&format!(
+        "\n## {} ({})\n- branch: {}\n- files:\n",
+        wt.task,
+        crate::now_unix(),
+        wt.branch
+    ));
+    for item in files {
+        body.push_str(&format!("  - {}\n", item));
+    }
+    body.push_str(&format!(
+        "- detail: {}\n- resolve: `git merge {}` 또는 branch를 확인한 뒤 task를 다시 실행\n",
+        detail.replace('\n', " "),
+        wt.branch
+    ));
+    file.write_all(body.as_bytes())
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_repo(tag: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "orc_worktree_{}_{}_{}",
+            tag,
+            std::process::id(),
+            crate::now_unix()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(".project")).expect("create repo dir");
+        git(&root, &["init", "-q"]).expect("git init");
+        fs::write(root.join("shared.txt"), "one\ntwo\nthree\n").expect("seed file");
+        fs::write(root.join(".project").join("plan.yaml"), "goal: x\n").expect("seed meta");
+        git(&root, &["add", "-A"]).expect("git add");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "commit",
+                "-q",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("git commit");
+        root
+    }
+
+    #[test]
+    fn merge_back_applies_task_changes_and_reports_conflicts() {
+        let root = init_repo("merge");
+        let ws = GitWorkspace::detect(&root).expect("detect git workspace");
+        fs::write(root.join("shared.txt"), "one\ntwo\nthree\nfour\n").expect("dirty main");
+        let base = ws.snapshot().expect("snapshot");
+
+        let a = ws.create("feature a", &base).expect("create a");
+        let b = ws.create("feature_b", &base).expect("create b");
+        assert_eq!(
+            fs::read_to_string(a.run_dir.join("shared.txt")).expect("read snapshot"),
+            "one\ntwo\nthree\nfour\n"
+        );
+        fs::write(a.run_dir.join("a.txt"), "from a\n").expect("write a");
+        fs::write(a.run_dir.join("shared.txt"), "ONE\ntwo\nthree\nfour\n").expect("edit a");
+        fs::write(a.run_dir.join(".project").join("plan.yaml"), "goal: y\n").expect("edit meta");
+        fs::write(b.run_dir.join("shared.txt"), "uno\ntwo\nthree\nfour\n").expect("edit b");
+
+        let merged = ws.merge_back(&a, &[]).expect("merge a");
+        assert_eq!(
+            merged,
+            MergeOutcome::Merged {
+                files: vec!["a.txt".to_string(), "shared.txt".to_string()]
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).expect("read merged"),
+            "from a\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join(".project").join("plan.yaml")).expect("read meta"),
+            "goal: x\n"
+        );
+
+        let conflict = ws.merge_back(&b, &[]).expect("merge b");
+        assert!(
+            matches!(conflict, MergeOutcome::Conflict { ref files, .. } if files == &vec!["shared.txt".to_string()])
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("shared.txt")).expect("read main"),
+            "ONE\ntwo\nthree\nfour\n"
+        );
+
+        let c = ws.create("feature_c", &base).expect("create c");
+        fs::write(c.run_dir.join("c.txt"), "from c\n").expect("write c");
+        fs::write(c.run_dir.join("outside.txt"), "nope\n").expect("write outside");
+        let skipped = ws
+            .merge_back(&c, &["outside.txt".to_string()])
+            .expect("merge c");
+        assert_eq!(
+            skipped,
+            MergeOutcome::Merged {
+                files: vec!["c.txt".to_string()]
+            }
+        );
+        assert!(root.join("c.txt").exists());
+        assert!(!root.join("outside.txt").exists());
+
+        ws.discard(&a, false);
+        ws.discard(&b, true);
+        ws.discard(&c, false);
+        assert!(!a.path.exists());
+        assert!(git(&root, &["rev-parse", "--verify", &b.branch]).is_ok());
+        assert!(git(&root, &["rev-parse", "--verify", &a.branch]).is_err());
+        let retry = ws.create("feature_b", &base).expect("retry b");
+        assert_ne!(retry.branch, b.branch);
+        ws.discard(&retry, false);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn dependent_task_sees_files_its_prerequisite_created() {
+        let root = init_repo("chain");
+        let ws = GitWorkspace::detect(&root).expect("detect git workspace");
+        fs::write(root.join(".project").join("run.log"), "noise\n").expect("runtime file");
+
+        let parent = ws.create("parent", &ws.snapshot().expect("base")).expect("parent");
+        fs::write(parent.run_dir.join("model.rs"), "struct User;\n").expect("parent writes");
+        assert!(matches!(
+            ws.merge_back(&parent, &[]).expect("merge parent"),
+            MergeOutcome::Merged { .. }
+        ));
+        ws.discard(&parent, false);
+        // Merged, but untracked in the main tree.
+        assert!(git(&root, &["ls-files", "--error-unmatch", "model.rs"]).is_err());
+
+        let child = ws.create("child", &ws.snapshot().expect("snapshot")).expect("child");
+        assert_eq!(
+            fs::read_to_string(child.run_dir.join("model.rs")).expect("child reads"),
+            "struct User;\n"
+        );
+        assert!(!child.run_dir.join(".project").join("run.log").exists());
+        fs::write(child.run_dir.join("model.rs"), "struct User { id: u64 }\n").expect("edit");
+        assert_eq!(
+            ws.merge_back(&child, &[]).expect("merge child"),
+            MergeOutcome::Merged {
+                files: vec!["model.rs".to_string()]
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("model.rs")).expect("read merged"),
+            "struct User { id: u64 }\n"
+        );
+        assert!(git(&root, &["diff", "--cached", "--quiet"]).is_ok());
+        ws.discard(&child, false);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const TERM_GRACE: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Default)]
+pub(crate) struct ProcessSet {
+    pids: Mutex<HashSet<u32>>,
+    cancelled: AtomicBool,
+    blocking: AtomicUsize,
+}
+
+// Held by a blocking task for as long as it runs; aborting the async side
+// does not stop the thread, so the project counts as busy until this drops.
+pub(crate) struct BlockingGuard(Arc<ProcessSet>);
+
+impl Drop for BlockingGuard {
+    fn drop(&mut self) {
+        self.0.blocking.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ProcessSet {
+    pub(crate) fn register(&self, pid: u32) {
+        // `kill_all` flips the flag under the same lock, so a pid is either
+        // seen by it or killed here.
+        let Ok(mut pids) = self.pids.lock() else {
+            return;
+        };
+        if self.is_cancelled() {
+            drop(pids);
+            kill_group(pid);
+            return;
+        }
+        pids.insert(pid);
+    }
+
+    pub(crate) fn unregister(&self, pid: u32) {
+        if let Ok(mut pids) = self.pids.lock() {
+            pids.remove(&pid);
+        }
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn enter_blocking(self: &Arc<Self>) -> BlockingGuard {
+        self.blocking.fetch_add(1, Ordering::SeqCst);
+        BlockingGuard(self.clone())
+    }
+
+    pub(crate) fn has_blocking(&self) -> bool {
+        self.blocking.load(Ordering::SeqCst) > 0
+    }
+
+    pub(crate) fn kill_all(&self) -> usize {
+        let mut locked = self.pids.lock();
+        self.cancelled.store(true, Ordering::SeqCst);
+        let pids: Vec<u32> = match locked.as_mut() {
+            Ok(pids) => pids.drain().collect(),
+            Err(_) => Vec::new(),
+        };
+        drop(locked);
+        for pid in &pids {
+            kill_group(*pid);
+        }
+        pids.len()
+    }
+}
+
+pub(crate) struct TrackedChild {
+    pid: u32,
+    set: Option<Arc<ProcessSet>>,
+}
+
+impl Drop for TrackedChild {
+    fn drop(&mut self) {
+        if let Some(set) = &self.set {
+            set.unregister(self.pid);
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn isolate(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+    command.process_group(0);
+}
+
+#[cfg(not(unix))]
+pub(crate) fn isolate(_command: &mut Command) {}
+
+#[cfg(unix)]
+fn isolate_tokio(command: &mut tokio::process::Command) {
+    command.process_group(0);
+}
+
+#[cfg(not(unix))]
+fn isolate_tokio(_command: &mut tokio::process::Command) {}
+
+fn current_set() -> Option<Arc<ProcessSet>> {
+    crate::context::current().map(|ctx| ctx.processes)
+}
+
+fn track(set: Option<Arc<ProcessSet>>, pid: u32) -> TrackedChild {
+    if let Some(set) = &set {
+        set.register(pid);
+    }
+    TrackedChild { pid, set }
+}
+
+// Children only get their own process group inside a project context (web jobs);
+// CLI runs keep the terminal's group so Ctrl-C and tty input still reach them.
+pub(crate) fn spawn_tracked(command: &mut Command) -> std::io::Result<(Child, TrackedChild)> {
+    let set = current_set();
+    if set.is_some() {
+        isolate(command);
+    }
+    let child = command.spawn()?;
+    let pid = child.id();
+    Ok((child, track(set, pid)))
+}
+
+pub(crate) fn spawn_tracked_tokio(
+    command: &mut tokio::process::Command,
+) -> std::io::Result<(tokio::process::Child, TrackedChild)> {
+    let set = current_set();
+    if set.is_some() {
+        isolate_tokio(command);
+    }
+    let child = command.spawn()?;
+    let pid = child.id().unwrap_or(0);
+    Ok((child, track(set.filter(|_| pid != 0), pid)))
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: &str) -> bool {
+    Command::new("kill")
+        .arg(format!("-{}", signal))
+        .arg("--")
+        .arg(format!("-{}", pid))
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+#[cfg(unix)]
+pub(crate) fn group_alive(pid: u32) -> bool {
+    signal_group(pid, "0")
+}
+
+#[cfg(not(unix))]
+pub(crate) fn group_alive(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(unix)]
+pub(crate) fn kill_group(pid: u32) {
+    if !signal_group(pid, "TERM") {
+        return;
+    }
+    let started = Instant::now();
+    while started.elapsed() < TERM_GRACE {
+        if !group_alive(pid) {
+            return;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let _ = signal_group(pid, "KILL");
+}
+
+#[cfg(not(unix))]
+pub(crate) fn kill_group(_pid: u32) {}
+
+pub(crate) fn kill_child_tree(child: &mut Child) {
+    kill_group(child.id());
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kill_all_terminates_whole_process_group() {
+        let set = Arc::new(ProcessSet::default());
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("sleep 30 & sleep 30; wait");
+        isolate(&mut command);
+        let mut child = command.spawn().expect("spawn sh");
+        let pid = child.id();
+        set.register(pid);
+        thread::sleep(Duration::from_millis(200));
+        assert!(group_alive(pid));
+
+        let waiter = thread::spawn(move || child.wait().expect("wait"));
+        assert_eq!(set.kill_all(), 1);
+        assert!(!waiter.join().expect("join waiter").success());
+        assert!(!group_alive(pid));
+        assert!(set.is_cancelled());
+    }
+}
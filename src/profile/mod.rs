@@ -19,6 +19,9 @@ pub(crate) trait ParallelRunner: Send + Sync {
     fn run_parallel_build<'a>(
         &'a self,
     ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
+    fn run_draft_items<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
 }
 
 pub(crate) trait ProjectService: Send + Sync {
@@ -44,9 +47,6 @@ pub(crate) trait DraftService: Send + Sync {
     fn add(&self, args: &[String]) -> Result<String, String>;
     fn add_item(&self, args: &[String]) -> Result<String, String>;
     fn move_item_to_drafts_yaml(&self, args: &[String]) -> Result<String, String>;
-    fn run_parallel<'a>(
-        &'a self,
-    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
 }
 
 pub(crate) trait FeedbackService: Send + Sync {
@@ -255,6 +255,12 @@ impl ParallelRunner for CodeParallelRunner {
     ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
         Box::pin(async move { crate::parallel::run_parallel_build_code().await })
     }
+
+    fn run_draft_items<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        Box::pin(async move { crate::code::impl_code_draft().await })
+    }
 }
 
 impl ParallelRunner for StoryParallelRunner {
@@ -263,6 +269,12 @@ impl ParallelRunner for StoryParallelRunner {
     ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
         Box::pin(async move { crate::parallel::run_parallel_build_code().await })
     }
+
+    fn run_draft_items<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        Box::pin(async move { crate::story::impl_story_draft().await })
+    }
 }
 
 impl ProjectService for CodeProjectService {
@@ -389,12 +401,6 @@ impl DraftService for CodeDraftService {
     fn move_item_to_drafts_yaml(&self, args: &[String]) -> Result<String, String> {
         crate::code::add_code_draft_item(args)
     }
-
-    fn run_parallel<'a>(
-        &'a self,
-    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
-        Box::pin(async move { crate::code::impl_code_draft().await })
-    }
 }
 
 impl DraftService for StoryDraftService {
@@ -409,12 +415,6 @@ impl DraftService for StoryDraftService {
     fn move_item_to_drafts_yaml(&self, args: &[String]) -> Result<String, String> {
         crate::code::add_code_draft_item(args)
     }
-
-    fn run_parallel<'a>(
-        &'a self,
-    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
-        Box::pin(async move { crate::story::impl_story_draft().await })
-    }
 }
 
 impl FeedbackService for CodeFeedbackService {
@@ -0,0 +1,416 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) const REGISTRY_PATH: &str = "configs/project.yaml";
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ProjectType {
+    Story,
+    Movie,
+    #[default]
+    Code,
+    Mono,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ProjectState {
+    Init,
+    Basic,
+    Work,
+    Wait,
+    Run,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct ProjectRecord {
+    #[serde(default)]
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) description: String,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+    pub(crate) selected: bool,
+    #[serde(default)]
+    pub(crate) project_type: ProjectType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) state: Option<ProjectState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub(crate) struct ProjectRegistry {
+    #[serde(default)]
+    pub(crate) schema_version: u32,
+    #[serde(default)]
+    pub(crate) revision: u64,
+    #[serde(default, rename = "recentActivepane")]
+    pub(crate) recent_active_pane: Option<String>,
+    #[serde(default)]
+    pub(crate) projects: Vec<ProjectRecord>,
+}
+
+pub(crate) fn registry_path(repo_root: &Path) -> PathBuf {
+    repo_root.join(REGISTRY_PATH)
+}
+
+pub(crate) fn generate_project_id(existing: &HashSet<String>) -> String {
+    const ALNUM: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let mut seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    for _ in 0..512 {
+        let mut out = String::with_capacity(4);
+        for _ in 0..4 {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let idx = (seed as usize) % ALNUM.len();
+            out.push(ALNUM[idx] as char);
+        }
+        if !existing.contains(&out) {
+            return out;
+        }
+    }
+    "0000".to_string()
+}
+
+pub(crate) fn assign_missing_ids(projects: &mut [ProjectRecord]) -> bool {
+    let mut changed = false;
+    let mut ids: HashSet<String> = projects
+        .iter()
+        .filter(|p| !p.id.is_empty())
+        .map(|p| p.id.clone())
+        .collect();
+    for project in projects {
+        if project.id.is_empty() {
+            let id = generate_project_id(&ids);
+            ids.insert(id.clone());
+            project.id = id;
+            changed = true;
+        }
+    }
+    changed
+}
+
+pub(crate) fn normalize(registry: &mut ProjectRegistry) -> bool {
+    let mut changed = assign_missing_ids(&mut registry.projects);
+    if let Some(recent_id) = &registry.recent_active_pane {
+        if !registry.projects.iter().any(|p| &p.id == recent_id) {
+            registry.recent_active_pane = None;
+            changed = true;
+        }
+    }
+    changed
+}
+
+// Older files were written by three different writers: the CLI kept
+// `project_type` as a free string and the web API wrote `recentActivepane: ''`.
+fn migrate(doc: &mut serde_yaml::Value) -> Result<(), String> {
+    let Some(map) = doc.as_mapping_mut() else {
+        return Ok(());
+    };
+    let version = map
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "registry schema_version {} is newer than supported {}",
+            version, SCHEMA_VERSION
+        ));
+    }
+    if version < 1 {
+        if map
+            .get("recentActivepane")
+            .and_then(|v| v.as_str())
+            .is_some_and(str::is_empty)
+        {
+            map.remove("recentActivepane");
+        }
+        if let Some(projects) = map.get_mut("projects").and_then(|v| v.as_sequence_mut()) {
+            for project in projects.iter_mut().filter_map(|p| p.as_mapping_mut()) {
+                let known = project
+                    .get("project_type")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|t| matches!(t, "story" | "movie" | "code" | "mono"));
+                if !known {
+                    project.insert("project_type".into(), "code".into());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Returns the registry plus whether migration or id assignment changed it.
+fn parse(path: &Path, raw: &str) -> Result<(ProjectRegistry, bool), String> {
+    if raw.trim().is_empty() {
+        return Ok((ProjectRegistry::default(), false));
+    }
+    let mut doc: serde_yaml::Value =
+        serde_yaml::from_str(raw).map_err(|e| format!("invalid yaml {}: {}", path.display(), e))?;
+    migrate(&mut doc).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut registry: ProjectRegistry = serde_yaml::from_value(doc)
+        .map_err(|e| format!("invalid registry {}: {}", path.display(), e))?;
+    let migrated = registry.schema_version < SCHEMA_VERSION;
+    let normalized = normalize(&mut registry);
+    registry.schema_version = SCHEMA_VERSION;
+    Ok((registry, migrated || normalized))
+}
+
+fn read_unlocked(path: &Path) -> Result<(ProjectRegistry, bool), String> {
+    if !path.exists() {
+        return Ok((ProjectRegistry::default(), false));
+    }
+    let raw = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    parse(path, &raw)
+}
+
+fn write_atomic(path: &Path, registry: &ProjectRegistry) -> Result<(), String> {
+    let raw = serde_yaml::to_string(registry).map_err(|e| format!("yaml encode error: {}", e))?;
+    let tmp = path.with_extension(format!("yaml.tmp.{}", std::process::id()));
+    let mut file =
+        File::create(&tmp).map_err(|e| format!("failed to write {}: {}", tmp.display(), e))?;
+    file.write_all(raw.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("failed to replace {}: {}", path.display(), e)
+    })
+}
+
+// Advisory lock on a sidecar file, so the rename in `write_atomic` never swaps
+// the locked inode out from under another process.
+struct RegistryLock {
+    file: File,
+}
+
+impl RegistryLock {
+    fn acquire(path: &Path, exclusive: bool) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+        }
+        let lock_path = path.with_extension("yaml.lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| format!("failed to open {}: {}", lock_path.display(), e))?;
+        let locked = if exclusive {
+            file.lock()
+        } else {
+            file.lock_shared()
+        };
+        locked.map_err(|e| format!("failed to lock {}: {}", lock_path.display(), e))?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for RegistryLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+pub(crate) fn load(path: &Path) -> Result<ProjectRegistry, String> {
+    {
+        let _lock = RegistryLock::acquire(path, false)?;
+        let (registry, dirty) = read_unlocked(path)?;
+        if !dirty {
+            return Ok(registry);
+        }
+    }
+    // A legacy file or one with missing ids: write it back once so the ids
+    // handed out here stay stable across loads.
+    load_for_edit(path)
+}
+
+// Like `load`, but persists migrations and generated ids first so every
+// reader sees the same project ids as the editor that loaded them.
+pub(crate) fn load_for_edit(path: &Path) -> Result<ProjectRegistry, String> {
+    let _lock = RegistryLock::acquire(path, true)?;
+    let (mut registry, dirty) = read_unlocked(path)?;
+    if dirty {
+        registry.revision += 1;
+        write_atomic(path, &registry)?;
+    }
+    Ok(registry)
+}
+
+// Optimistic write: fails when someone else saved after `registry` was loaded.
+pub(crate) fn save(path: &Path, registry: &mut ProjectRegistry) -> Result<(), String> {
+    let _lock = RegistryLock::acquire(path, true)?;
+    let (current, _) = read_unlocked(path)?;
+    if current.revision != registry.revision {
+        return Err(format!(
+            "registry changed concurrently ({} revision {} != {}); reload and retry",
+            path.display(),
+            current.revision,
+            registry.revision
+        ));
+    }
+    registry.schema_version = SCHEMA_VERSION;
+    registry.revision = current.revision + 1;
+    write_atomic(path, registry)
+}
+
+pub(crate) fn update<T>(
+    path: &Path,
+    f: impl FnOnce(&mut ProjectRegistry) -> Result<T, String>,
+) -> Result<T, String> {
+    let _lock = RegistryLock::acquire(path, true)?;
+    let (mut registry, dirty) = read_unlocked(path)?;
+    let before = registry.clone();
+    let out = f(&mut registry)?;
+    normalize(&mut registry);
+    if dirty || registry != before {
+        registry.schema_version = SCHEMA_VERSION;
+        registry.revision = before.revision + 1;
+        write_atomic(path, &registry)?;
+    }
+    Ok(out)
+}
+
+// Writes a whole project list edited from `base`. When the file moved on in the
+// meantime, projects added or removed elsewhere survive and `state` (owned by the
+// web API) keeps its on-disk value.
+pub(crate) fn sync_projects(
+    path: &Path,
+    base: &ProjectRegistry,
+    projects: &[ProjectRecord],
+    recent_active_pane: &Option<String>,
+) -> Result<ProjectRegistry, String> {
+    let _lock = RegistryLock::acquire(path, true)?;
+    let (current, _) = read_unlocked(path)?;
+    let mut next = ProjectRegistry {
+        schema_version: SCHEMA_VERSION,
+        revision: current.revision + 1,
+        recent_active_pane: recent_active_pane.clone(),
+        projects: projects.to_vec(),
+    };
+    if current.revision != base.revision {
+        let base_ids: HashSet<&str> = base.projects.iter().map(|p| p.id.as_str()).collect();
+        next.projects = projects
+            .iter()
+            .filter_map(
+                |ours| match current.projects.iter().find(|p| p.id == ours.id) {
+                    Some(theirs) => Some(ProjectRecord {
+                        state: theirs.state,
+                        ..ours.clone()
+                    }),
+                    None if base_ids.contains(ours.id.as_str()) => None,
+                    None => Some(ours.clone()),
+                },
+            )
+            .collect();
+        next.projects.extend(
+            current
+                .projects
+                .iter()
+                .filter(|p| !base_ids.contains(p.id.as_str()))
+                .filter(|p| !projects.iter().any(|ours| ours.id == p.id))
+                .cloned(),
+        );
+        if next.projects.iter().filter(|p| p.selected).count() > 1 {
+            let keep = next.projects.iter().position(|p| p.selected);
+            for (idx, project) in next.projects.iter_mut().enumerate() {
+                project.selected = Some(idx) == keep;
+            }
+        }
+    }
+    normalize(&mut next);
+    write_atomic(path, &next)?;
+    Ok(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, name: &str) -> ProjectRecord {
+        ProjectRecord {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: format!("/tmp/{}", name),
+            description: String::new(),
+            created_at: "0".to_string(),
+            updated_at: "0".to_string(),
+            selected: false,
+            project_type: ProjectType::Code,
+            state: None,
+        }
+    }
+
+    #[test]
+    fn concurrent_writers_do_not_lose_updates() {
+        let dir = std::env::temp_dir().join(format!("orc-registry-{}", std::process::id()));
+        let path = dir.join("project.yaml");
+        fs::create_dir_all(&dir).expect("create dir");
+        fs::write(
+            &path,
+            "recentActivepane: ''\nprojects:\n- name: old\n  path: /tmp/old\n  description: ''\n  created_at: '0'\n  updated_at: '0'\n  selected: true\n  project_type: legacy\n",
+        )
+        .expect("write legacy registry");
+        let legacy = load_for_edit(&path).expect("load legacy");
+        assert_eq!(legacy.revision, 1);
+        assert_eq!(legacy.recent_active_pane, None);
+        assert_eq!(legacy.projects[0].project_type, ProjectType::Code);
+        assert_eq!(legacy.projects[0].id.len(), 4);
+
+        // TUI-style editor holds a snapshot while the web API updates in place.
+        let base = legacy.clone();
+        let old_id = base.projects[0].id.clone();
+        update(&path, |registry| {
+            registry.projects.push(record("web1", "from-web"));
+            registry.projects[0].state = Some(ProjectState::Run);
+            Ok(())
+        })
+        .expect("update");
+
+        let mut stale = base.clone();
+        assert!(save(&path, &mut stale).is_err());
+
+        let mut ours = base.projects.clone();
+        ours[0].description = "edited in tui".to_string();
+        ours.push(record("tui1", "from-tui"));
+        let merged = sync_projects(&path, &base, &ours, &Some(old_id.clone())).expect("sync");
+        let names: Vec<&str> = merged.projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["old", "from-tui", "from-web"]);
+        assert_eq!(merged.projects[0].description, "edited in tui");
+        assert_eq!(merged.projects[0].state, Some(ProjectState::Run));
+
+        let mut fresh = load(&path).expect("reload");
+        assert_eq!(fresh, merged);
+        assert_eq!(fresh.schema_version, SCHEMA_VERSION);
+        fresh.projects.retain(|p| p.id != "web1");
+        save(&path, &mut fresh).expect("save fresh");
+        assert_eq!(load(&path).expect("reload").projects.len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+    #[test]
+    fn load_persists_generated_ids() {
+        let dir = std::env::temp_dir().join(format!("orc-registry-ids-{}", std::process::id()));
+        let path = dir.join("project.yaml");
+        fs::create_dir_all(&dir).expect("create dir");
+        fs::write(
+            &path,
+            "projects:\n- name: old\n  path: /tmp/old\n  description: ''\n  created_at: '0'\n  updated_at: '0'\n  selected: true\n",
+        )
+        .expect("write registry without ids");
+        let first = load(&path).expect("first load");
+        let second = load(&path).expect("second load");
+        assert_eq!(first.projects[0].id, second.projects[0].id);
+        assert_eq!(first.revision, second.revision);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
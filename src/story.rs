@@ -1,4 +1,3 @@
-use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -20,7 +19,7 @@ pub(crate) fn init_story_project(args: &[String]) -> Result<String, String> {
         return Err("init_code_project -a requires message (`-a <msg>`)".to_string());
     }
 
-    let cwd = env::current_dir().map_err(|e| format!("failed to read cwd: {}", e))?;
+    let cwd = crate::context::project_root();
     let default_name = cwd
         .file_name()
         .and_then(|v| v.to_str())
@@ -206,8 +205,8 @@ fn read_story_template(file_name: &str) -> Result<String, String> {
 }
 
 fn enforce_project_dir() -> Result<PathBuf, String> {
-    let dir = Path::new(".project");
-    fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let dir = crate::context::project_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
     Ok(dir.to_path_buf())
 }
 
@@ -217,11 +216,11 @@ fn write_story_project_md(name: &str, description: &str, path: &str, spec: &str)
     body = replace_info_field_value(&body, "description", description);
     body = replace_info_field_value(&body, "path", path);
     body = replace_info_field_value(&body, "spec", &normalize_story_spec(spec));
-    write_file(Path::new(crate::PROJECT_MD_PATH), &format!("{}\n", body.trim_end()))
+    write_file(&crate::project_md_file(), &format!("{}\n", body.trim_end()))
 }
 
 fn ensure_story_plan_yaml() -> Result<(), String> {
-    let path = Path::new(".project").join("plan.yaml");
+    let path = crate::context::project_dir().join("plan.yaml");
     if path.exists() {
         return Ok(());
     }
@@ -230,7 +229,7 @@ fn ensure_story_plan_yaml() -> Result<(), String> {
 }
 
 fn ensure_story_drafts_yaml() -> Result<(), String> {
-    let path = Path::new(".project").join("drafts.yaml");
+    let path = crate::context::project_dir().join("drafts.yaml");
     if path.exists() {
         return Ok(());
     }
@@ -239,7 +238,7 @@ fn ensure_story_drafts_yaml() -> Result<(), String> {
 }
 
 fn ensure_story_draft_yaml() -> Result<(), String> {
-    let path = Path::new(".project").join("draft.yaml");
+    let path = crate::context::project_dir().join("draft.yaml");
     if path.exists() {
         return Ok(());
     }
@@ -248,7 +247,7 @@ fn ensure_story_draft_yaml() -> Result<(), String> {
 }
 
 fn ensure_story_memo_md() -> Result<(), String> {
-    let path = Path::new(".project").join("memo.md");
+    let path = crate::context::project_dir().join("memo.md");
     if path.exists() {
         return Ok(());
     }
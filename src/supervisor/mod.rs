@@ -0,0 +1,669 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod probe;
+mod spec;
+
+pub(crate) use spec::{load_specs, RestartPolicy, RunSpec};
+
+const SNAPSHOT_FILE: &str = "processes.yaml";
+const LOG_ROTATE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_BACKOFF_SEC: u64 = 30;
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+const PROBE_INTERVAL: Duration = Duration::from_secs(2);
+const STOP_WAIT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ProcessStatus {
+    Starting,
+    Running,
+    Backoff,
+    Stopped,
+    Exited,
+    Failed,
+}
+
+impl ProcessStatus {
+    pub(crate) fn is_alive(self) -> bool {
+        matches!(
+            self,
+            ProcessStatus::Starting | ProcessStatus::Running | ProcessStatus::Backoff
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ProcessInfo {
+    pub(crate) project_id: String,
+    pub(crate) name: String,
+    pub(crate) command: String,
+    pub(crate) status: ProcessStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pid: Option<u32>,
+    #[serde(default)]
+    pub(crate) restarts: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) ports: Vec<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) healthy: Option<bool>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) last_exit: String,
+    #[serde(default)]
+    pub(crate) started_at: u64,
+    pub(crate) log_path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    #[serde(default)]
+    processes: Vec<ProcessInfo>,
+}
+
+pub(crate) type LineSink = Arc<dyn Fn(&str, &str, &str) + Send + Sync>;
+pub(crate) type ChangeSink = Arc<dyn Fn(&ProcessInfo, bool) + Send + Sync>;
+
+struct Managed {
+    root: PathBuf,
+    spec: RunSpec,
+    info: Mutex<ProcessInfo>,
+    stop: AtomicBool,
+}
+
+pub(crate) struct Supervisor {
+    entries: Mutex<HashMap<(String, String), Arc<Managed>>>,
+    on_line: LineSink,
+    on_change: ChangeSink,
+}
+
+pub(crate) fn logs_dir(root: &Path) -> PathBuf {
+    root.join(".project").join("runtime").join("logs")
+}
+
+fn snapshot_path(root: &Path) -> PathBuf {
+    root.join(".project").join("runtime").join(SNAPSHOT_FILE)
+}
+
+pub(crate) fn load_snapshot(root: &Path) -> Vec<ProcessInfo> {
+    fs::read_to_string(snapshot_path(root))
+        .ok()
+        .and_then(|raw| serde_yaml::from_str::<Snapshot>(&raw).ok())
+        .map(|doc| doc.processes)
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+fn pid_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pid_alive(_pid: u32) -> bool {
+    true
+}
+
+pub(crate) fn render_status_line(info: &ProcessInfo) -> String {
+    let stale = info.status.is_alive() && !info.pid.is_some_and(pid_alive);
+    let mut out = format!(
+        "{} : {}",
+        info.name,
+        if stale {
+            "stale"
+        } else {
+            match info.status {
+                ProcessStatus::Starting => "starting",
+                ProcessStatus::Running => "running",
+                ProcessStatus::Backoff => "backoff",
+                ProcessStatus::Stopped => "stopped",
+                ProcessStatus::Exited => "exited",
+                ProcessStatus::Failed => "failed",
+            }
+        }
+    );
+    if !info.ports.is_empty() {
+        let ports: Vec<String> = info.ports.iter().map(u16::to_string).collect();
+        out.push_str(&format!(" :{}", ports.join(",")));
+    }
+    match info.healthy {
+        Some(true) => out.push_str(" ok"),
+        Some(false) => out.push_str(" unhealthy"),
+        None => {}
+    }
+    if info.restarts > 0 {
+        out.push_str(&format!(" restarts={}", info.restarts));
+    }
+    out
+}
+
+pub(crate) fn tail_log(root: &Path, name: &str, lines: usize) -> Result<Vec<String>, String> {
+    if name.contains('/') || name.contains("..") {
+        return Err(format!("invalid process name: {}", name));
+    }
+    let path = logs_dir(root).join(format!("{}.log", name));
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let all: Vec<&str> = raw.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    Ok(all[start..].iter().map(|line| line.to_string()).collect())
+}
+
+fn open_log(root: &Path, name: &str) -> Result<(PathBuf, File), String> {
+    let dir = logs_dir(root);
+    fs::create_dir_all(&dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.log", name));
+    if fs::metadata(&path).is_ok_and(|meta| meta.len() > LOG_ROTATE_BYTES) {
+        let _ = fs::rename(&path, dir.join(format!("{}.log.1", name)));
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+    Ok((path, file))
+}
+
+fn backoff_for(consecutive: u32) -> Duration {
+    let exp = consecutive.saturating_sub(1).min(5);
+    Duration::from_secs((1u64 << exp).min(MAX_BACKOFF_SEC))
+}
+
+fn should_restart(policy: RestartPolicy, success: bool) -> bool {
+    match policy {
+        RestartPolicy::Never => false,
+        RestartPolicy::OnFailure => !success,
+        RestartPolicy::Always => true,
+    }
+}
+
+impl Supervisor {
+    pub(crate) fn new(on_line: LineSink, on_change: ChangeSink) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            on_line,
+            on_change,
+        }
+    }
+
+    pub(crate) fn list(&self, project_id: &str) -> Vec<ProcessInfo> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let mut out: Vec<ProcessInfo> = entries
+            .iter()
+            .filter(|((id, _), _)| id == project_id)
+            .filter_map(|(_, managed)| managed.info.lock().ok().map(|info| info.clone()))
+            .collect();
+        out.sort_by(|a, b| a.name.cmp(&b.name));
+        out
+    }
+
+    pub(crate) fn any_alive(&self, project_id: &str) -> bool {
+        self.list(project_id)
+            .iter()
+            .any(|info| info.status.is_alive())
+    }
+
+    pub(crate) fn start(
+        self: &Arc<Self>,
+        project_id: &str,
+        root: &Path,
+        only: Option<&str>,
+    ) -> Result<Vec<ProcessInfo>, String> {
+        let (specs, source) = load_specs(root)?;
+        if specs.is_empty() {
+            return Err(format!(
+                "no run command for {}: add .project/{} or a `# run` section to project.md",
+                root.display(),
+                spec::RUN_YAML_FILE
+            ));
+        }
+        let selected: Vec<RunSpec> = specs
+            .into_iter()
+            .filter(|spec| only.is_none_or(|name| spec.name == name))
+            .collect();
+        if selected.is_empty() {
+            return Err(format!(
+                "run spec not found in {}: {}",
+                source,
+                only.unwrap_or("")
+            ));
+        }
+        let mut started = Vec::new();
+        for spec in selected {
+            let key = (project_id.to_string(), spec.name.clone());
+            let managed = {
+                let mut entries = self
+                    .entries
+                    .lock()
+                    .map_err(|_| "failed to lock supervisor".to_string())?;
+                if let Some(existing) = entries.get(&key) {
+                    let alive = existing
+                        .info
+                        .lock()
+                        .map(|info| info.status.is_alive())
+                        .unwrap_or(false);
+                    if alive {
+                        continue;
+                    }
+                }
+                let log_path = logs_dir(root).join(format!("{}.log", spec.name));
+                let managed = Arc::new(Managed {
+                    root: root.to_path_buf(),
+                    info: Mutex::new(ProcessInfo {
+                        project_id: project_id.to_string(),
+                        name: spec.name.clone(),
+                        command: spec.command.clone(),
+                        status: ProcessStatus::Starting,
+                        pid: None,
+                        restarts: 0,
+                        ports: spec.port.into_iter().collect(),
+                        healthy: None,
+                        last_exit: String::new(),
+                        started_at: crate::now_unix_secs(),
+                        log_path: log_path.display().to_string(),
+                    }),
+                    spec,
+                    stop: AtomicBool::new(false),
+                });
+                entries.insert(key, managed.clone());
+                managed
+            };
+            self.notify(&managed);
+            if let Ok(info) = managed.info.lock() {
+                started.push(info.clone());
+            }
+            let supervisor = self.clone();
+            thread::spawn(move || supervisor.supervise(managed));
+        }
+        Ok(started)
+    }
+
+    pub(crate) fn stop(&self, project_id: &str, only: Option<&str>) -> Vec<ProcessInfo> {
+        let targets: Vec<Arc<Managed>> = match self.entries.lock() {
+            Ok(entries) => entries
+                .iter()
+                .filter(|((id, name), _)| id == project_id && only.is_none_or(|n| n == name))
+                .map(|(_, managed)| managed.clone())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        for managed in &targets {
+            managed.stop.store(true, Ordering::SeqCst);
+            let pid = managed.info.lock().ok().and_then(|info| info.pid);
+            if let Some(pid) = pid {
+                crate::process_group::kill_group(pid);
+            }
+        }
+        let started = Instant::now();
+        while started.elapsed() < STOP_WAIT
+            && targets.iter().any(|managed| {
+                managed
+                    .info
+                    .lock()
+                    .map(|info| info.status.is_alive())
+                    .unwrap_or(false)
+            })
+        {
+            thread::sleep(Duration::from_millis(100));
+        }
+        targets
+            .iter()
+            .filter_map(|managed| managed.info.lock().ok().map(|info| info.clone()))
+            .collect()
+    }
+
+    fn notify(&self, managed: &Managed) {
+        let Ok(info) = managed.info.lock().map(|info| info.clone()) else {
+            return;
+        };
+        let processes = self.list(&info.project_id);
+        let alive = processes.iter().any(|p| p.status.is_alive());
+        let snapshot = Snapshot { processes };
+        let path = snapshot_path(&managed.root);
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(raw) = serde_yaml::to_string(&snapshot) {
+            let _ = fs::write(&path, raw);
+        }
+        (self.on_change)(&info, alive);
+    }
+
+    fn update(&self, managed: &Managed, f: impl FnOnce(&mut ProcessInfo)) {
+        let changed = match managed.info.lock() {
+            Ok(mut info) => {
+                let before = info.clone();
+                f(&mut info);
+                *info != before
+            }
+            Err(_) => false,
+        };
+        if changed {
+            self.notify(managed);
+        }
+    }
+
+    fn supervise(self: Arc<Self>, managed: Arc<Managed>) {
+        let spec = managed.spec.clone();
+        let mut consecutive = 0u32;
+        loop {
+            if managed.stop.load(Ordering::SeqCst) {
+                self.update(&managed, |info| {
+                    info.status = ProcessStatus::Stopped;
+                    info.pid = None;
+                });
+                return;
+            }
+            let started = Instant::now();
+            let outcome = self.run_once(&managed);
+            let stopped = managed.stop.load(Ordering::SeqCst);
+            let (success, detail) = match outcome {
+                Ok(status) => (status.success(), status.to_string()),
+                Err(e) => {
+                    self.update(&managed, |info| {
+                        info.status = ProcessStatus::Failed;
+                        info.pid = None;
+                        info.last_exit = e;
+                    });
+                    return;
+                }
+            };
+            if stopped {
+                self.update(&managed, |info| {
+                    info.status = ProcessStatus::Stopped;
+                    info.pid = None;
+                    info.healthy = None;
+                    info.last_exit = detail;
+                });
+                return;
+            }
+            if !should_restart(spec.restart, success) {
+                self.update(&managed, |info| {
+                    info.status = if success {
+                        ProcessStatus::Exited
+                    } else {
+                        ProcessStatus::Failed
+                    };
+                    info.pid = None;
+                    info.healthy = None;
+                    info.last_exit = detail;
+                });
+                return;
+            }
+            if started.elapsed() >= STABLE_UPTIME {
+                consecutive = 0;
+            }
+            if consecutive >= spec.max_restarts {
+                self.update(&managed, |info| {
+                    info.status = ProcessStatus::Failed;
+                    info.pid = None;
+                    info.healthy = None;
+                    info.last_exit = format!(
+                        "{} (gave up after {} quick restarts)",
+                        detail, spec.max_restarts
+                    );
+                });
+                return;
+            }
+            consecutive += 1;
+            let delay = backoff_for(consecutive);
+            self.update(&managed, |info| {
+                info.status = ProcessStatus::Backoff;
+                info.pid = None;
+                info.healthy = None;
+                info.restarts += 1;
+                info.last_exit = format!("{} (restart in {}s)", detail, delay.as_secs());
+            });
+            (self.on_line)(
+                &managed
+                    .info
+                    .lock()
+                    .map(|i| i.project_id.clone())
+                    .unwrap_or_default(),
+                &spec.name,
+                &format!(
+                    "[supervisor] {} exited: {}; restarting in {}s",
+                    spec.name,
+                    detail,
+                    delay.as_secs()
+                ),
+            );
+            let wait_started = Instant::now();
+            while wait_started.elapsed() < delay && !managed.stop.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+    }
+
+    fn run_once(
+        self: &Arc<Self>,
+        managed: &Arc<Managed>,
+    ) -> Result<std::process::ExitStatus, String> {
+        let spec = &managed.spec;
+        let project_id = managed
+            .info
+            .lock()
+            .map(|info| info.project_id.clone())
+            .unwrap_or_default();
+        let (_, mut log) = open_log(&managed.root, &spec.name)?;
+        let _ = writeln!(
+            log,
+            "=== start {} cmd={} ===",
+            crate::now_unix_secs(),
+            spec.command
+        );
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(&spec.command)
+            .current_dir(managed.root.join(&spec.cwd))
+            .envs(&spec.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        crate::process_group::isolate(&mut command);
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("failed to spawn `{}`: {}", spec.command, e))?;
+        let pid = child.id();
+        self.update(managed, |info| {
+            info.status = ProcessStatus::Running;
+            info.pid = Some(pid);
+            info.started_at = crate::now_unix_secs();
+        });
+        // `stop` sets the flag before reading the pid, so a stop that landed
+        // while we were spawning had nothing to kill and is caught here.
+        if managed.stop.load(Ordering::SeqCst) {
+            crate::process_group::kill_group(pid);
+        }
+        (self.on_line)(
+            &project_id,
+            &spec.name,
+            &format!(
+                "[supervisor] {} started: pid={} cmd={}",
+                spec.name, pid, spec.command
+            ),
+        );
+
+        let log = Arc::new(Mutex::new(log));
+        let log_ports: Arc<Mutex<BTreeSet<u16>>> = Arc::new(Mutex::new(BTreeSet::new()));
+        let pipes: Vec<Box<dyn Read + Send>> = [
+            child
+                .stdout
+                .take()
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+            child
+                .stderr
+                .take()
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let readers: Vec<thread::JoinHandle<()>> = pipes
+            .into_iter()
+            .map(|pipe| {
+                let log = log.clone();
+                let log_ports = log_ports.clone();
+                let on_line = self.on_line.clone();
+                let project_id = project_id.clone();
+                let name = spec.name.clone();
+                thread::spawn(move || {
+                    for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                        if let Ok(mut log) = log.lock() {
+                            let _ = writeln!(log, "{}", line);
+                        }
+                        if let Some(port) = probe::port_from_line(&line) {
+                            if let Ok(mut ports) = log_ports.lock() {
+                                ports.insert(port);
+                            }
+                        }
+                        on_line(&project_id, &name, &line);
+                    }
+                })
+            })
+            .collect();
+
+        let running = Arc::new(AtomicBool::new(true));
+        let prober = {
+            let running = running.clone();
+            let managed = managed.clone();
+            let log_ports = log_ports.clone();
+            let supervisor = self.clone();
+            thread::spawn(move || {
+                while running.load(Ordering::SeqCst) {
+                    let mut ports: BTreeSet<u16> =
+                        probe::listening_ports(pid).into_iter().collect();
+                    ports.extend(managed.spec.port);
+                    if ports.is_empty() {
+                        if let Ok(found) = log_ports.lock() {
+                            ports.extend(found.iter().copied());
+                        }
+                    }
+                    let healthy = ports
+                        .iter()
+                        .next()
+                        .map(|port| probe::probe(*port, managed.spec.health.as_deref()));
+                    let changed = match managed.info.lock() {
+                        Ok(mut info) if info.pid == Some(pid) => {
+                            let ports: Vec<u16> = ports.into_iter().collect();
+                            let changed = info.ports != ports || info.healthy != healthy;
+                            info.ports = ports;
+                            info.healthy = healthy;
+                            changed
+                        }
+                        _ => false,
+                    };
+                    if changed {
+                        supervisor.notify(&managed);
+                    }
+                    let slept = Instant::now();
+                    while slept.elapsed() < PROBE_INTERVAL && running.load(Ordering::SeqCst) {
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                }
+            })
+        };
+
+        let status = child.wait();
+        running.store(false, Ordering::SeqCst);
+        // Dev servers often leave watchers behind; take down the rest of the group.
+        crate::process_group::kill_group(pid);
+        for reader in readers {
+            let _ = reader.join();
+        }
+        let _ = prober.join();
+        let status = status.map_err(|e| format!("failed to wait `{}`: {}", spec.command, e))?;
+        if let Ok(mut log) = log.lock() {
+            let _ = writeln!(log, "=== exit {} {} ===", crate::now_unix_secs(), status);
+        }
+        Ok(status)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failing_process_is_restarted_until_limit_and_logged() {
+        let root = std::env::temp_dir().join(format!("orc-supervisor-{}", std::process::id()));
+        let project = root.join(".project");
+        fs::create_dir_all(&project).expect("create .project");
+        fs::write(
+            project.join("run.yaml"),
+            "processes:\n  - name: flaky\n    command: echo boom; exit 3\n    max_restarts: 1\n",
+        )
+        .expect("write run.yaml");
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink_lines = lines.clone();
+        let supervisor = Arc::new(Supervisor::new(
+            Arc::new(move |_, name, line| {
+                if let Ok(mut lines) = sink_lines.lock() {
+                    lines.push(format!("{}|{}", name, line));
+                }
+            }),
+            Arc::new(|_, _| {}),
+        ));
+        let started = supervisor.start("p1", &root, None).expect("start");
+        assert_eq!(started.len(), 1);
+
+        let deadline = Instant::now() + Duration::from_secs(15);
+        while supervisor.any_alive("p1") && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(50));
+        }
+        let info = supervisor.list("p1").remove(0);
+        assert_eq!(info.status, ProcessStatus::Failed);
+        assert_eq!(info.restarts, 1);
+        assert!(info.last_exit.contains("gave up"));
+        assert_eq!(load_snapshot(&root), vec![info]);
+        let log = tail_log(&root, "flaky", 10).expect("tail log");
+        assert_eq!(log.iter().filter(|line| *line == "boom").count(), 2);
+        assert!(lines
+            .lock()
+            .expect("lines")
+            .iter()
+            .any(|line| line == "flaky|boom"));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn stop_requested_during_spawn_kills_the_new_process() {
+        let root = std::env::temp_dir().join(format!("orc-supervisor-stop-{}", std::process::id()));
+        fs::create_dir_all(&root).expect("create root");
+        let supervisor = Arc::new(Supervisor::new(Arc::new(|_, _, _| {}), Arc::new(|_, _| {})));
+        let spec: RunSpec = serde_yaml::from_str("name: dev\ncommand: sleep 30\n").expect("spec");
+        let managed = Arc::new(Managed {
+            root: root.clone(),
+            info: Mutex::new(ProcessInfo {
+                project_id: "p1".to_string(),
+                name: spec.name.clone(),
+                command: spec.command.clone(),
+                status: ProcessStatus::Starting,
+                pid: None,
+                restarts: 0,
+                ports: Vec::new(),
+                healthy: None,
+                last_exit: String::new(),
+                started_at: 0,
+                log_path: String::new(),
+            }),
+            spec,
+            // As if `stop` ran before the pid was recorded.
+            stop: AtomicBool::new(true),
+        });
+        let started = Instant::now();
+        let status = supervisor.run_once(&managed).expect("run");
+        assert!(!status.success());
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
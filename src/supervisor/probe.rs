@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+const PORT_MARKERS: [&str; 5] = ["localhost:", "127.0.0.1:", "0.0.0.0:", "[::]:", "[::1]:"];
+
+pub(crate) fn port_from_line(line: &str) -> Option<u16> {
+    let lower = line.to_ascii_lowercase();
+    let digits_after = |rest: &str| -> Option<u16> {
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse::<u16>().ok().filter(|port| *port > 0)
+    };
+    for marker in PORT_MARKERS {
+        if let Some(port) = lower
+            .match_indices(marker)
+            .find_map(|(idx, _)| digits_after(&lower[idx + marker.len()..]))
+        {
+            return Some(port);
+        }
+    }
+    lower
+        .match_indices("port ")
+        .find_map(|(idx, _)| digits_after(&lower[idx + "port ".len()..]))
+}
+
+#[cfg(target_os = "linux")]
+fn group_pids(pgid: u32) -> Vec<u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| {
+            std::fs::read_to_string(format!("/proc/{}/stat", pid))
+                .ok()
+                .and_then(|stat| {
+                    // fields after "(comm)": state ppid pgrp ...
+                    let rest = &stat[stat.rfind(')')? + 1..];
+                    rest.split_whitespace().nth(2)?.parse::<u32>().ok()
+                })
+                == Some(pgid)
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn socket_inodes(pid: u32) -> HashSet<u64> {
+    let Ok(entries) = std::fs::read_dir(format!("/proc/{}/fd", pid)) else {
+        return HashSet::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let target = std::fs::read_link(entry.path()).ok()?;
+            let target = target.to_str()?;
+            target
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse::<u64>()
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn listening_ports(pgid: u32) -> Vec<u16> {
+    let inodes: HashSet<u64> = group_pids(pgid)
+        .into_iter()
+        .flat_map(socket_inodes)
+        .collect();
+    if inodes.is_empty() {
+        return Vec::new();
+    }
+    let mut ports: Vec<u16> = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|table| parse_listening(&table, &inodes))
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn listening_ports(_pgid: u32) -> Vec<u16> {
+    Vec::new()
+}
+
+pub(crate) fn parse_listening(table: &str, inodes: &HashSet<u64>) -> Vec<u16> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            // sl local_address rem_address st ... inode(9); 0A = LISTEN
+            if cols.len() < 10 || cols[3] != "0A" {
+                return None;
+            }
+            let inode = cols[9].parse::<u64>().ok()?;
+            if !inodes.contains(&inode) {
+                return None;
+            }
+            let (_, port_hex) = cols[1].rsplit_once(':')?;
+            u16::from_str_radix(port_hex, 16).ok()
+        })
+        .collect()
+}
+
+pub(crate) fn probe(port: u16, health: Option<&str>) -> bool {
+    let addrs: [SocketAddr; 2] = [
+        SocketAddr::from(([127, 0, 0, 1], port)),
+        SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 1], port)),
+    ];
+    let Some(mut stream) = addrs
+        .iter()
+        .find_map(|addr| TcpStream::connect_timeout(addr, PROBE_TIMEOUT).ok())
+    else {
+        return false;
+    };
+    let Some(path) = health.filter(|h| h.starts_with('/')) else {
+        return true;
+    };
+    let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(PROBE_TIMEOUT));
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: localhost:{}\r\nConnection: close\r\n\r\n",
+        path, port
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+    let mut head = [0u8; 64];
+    let Ok(read) = stream.read(&mut head) else {
+        return false;
+    };
+    let status = String::from_utf8_lossy(&head[..read]);
+    status
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .is_some_and(|code| (200..400).contains(&code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ports_are_found_in_logs_and_listen_tables() {
+        assert_eq!(
+            port_from_line("  ➜  Local:   http://localhost:5173/"),
+            Some(5173)
+        );
+        assert_eq!(port_from_line("Listening on 0.0.0.0:8080"), Some(8080));
+        assert_eq!(port_from_line("server started on port 3000"), Some(3000));
+        assert_eq!(port_from_line("compiled 12 modules"), None);
+
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242 1 0 100 0 0 10 0\n   1: 0100007F:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 9999 1 0 100 0 0 10 0\n   2: 0100007F:1F91 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 4243 1 0 100 0 0 10 0\n";
+        let inodes: HashSet<u64> = [4242, 4243].into_iter().collect();
+        assert_eq!(parse_listening(table, &inodes), vec![8080]);
+    }
+}
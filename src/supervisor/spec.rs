@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub(crate) const RUN_YAML_FILE: &str = "run.yaml";
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RestartPolicy {
+    Never,
+    #[default]
+    OnFailure,
+    Always,
+}
+
+fn default_max_restarts() -> u32 {
+    DEFAULT_MAX_RESTARTS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RunSpec {
+    pub(crate) name: String,
+    pub(crate) command: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) cwd: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) health: Option<String>,
+    #[serde(default)]
+    pub(crate) restart: RestartPolicy,
+    #[serde(default = "default_max_restarts")]
+    pub(crate) max_restarts: u32,
+}
+
+impl RunSpec {
+    fn simple(name: &str, command: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            command: command.to_string(),
+            cwd: String::new(),
+            env: BTreeMap::new(),
+            port: None,
+            health: None,
+            restart: RestartPolicy::default(),
+            max_restarts: DEFAULT_MAX_RESTARTS,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RunDoc {
+    #[serde(default)]
+    processes: Vec<RunSpec>,
+}
+
+pub(crate) fn load_specs(root: &Path) -> Result<(Vec<RunSpec>, &'static str), String> {
+    let run_yaml = root.join(".project").join(RUN_YAML_FILE);
+    if run_yaml.exists() {
+        let raw = fs::read_to_string(&run_yaml)
+            .map_err(|e| format!("failed to read {}: {}", run_yaml.display(), e))?;
+        let doc = serde_yaml::from_str::<RunDoc>(&raw)
+            .map_err(|e| format!("invalid yaml {}: {}", run_yaml.display(), e))?;
+        return Ok((validate(doc.processes)?, "run.yaml"));
+    }
+    let project_md = root.join(".project").join("project.md");
+    if let Ok(md) = fs::read_to_string(&project_md) {
+        let specs = parse_project_md_run(&md);
+        if !specs.is_empty() {
+            return Ok((validate(specs)?, "project.md"));
+        }
+    }
+    Ok((detect_specs(root), "detected"))
+}
+
+fn validate(specs: Vec<RunSpec>) -> Result<Vec<RunSpec>, String> {
+    let mut names = Vec::new();
+    for spec in &specs {
+        if spec.name.trim().is_empty() || spec.command.trim().is_empty() {
+            return Err("run spec requires both name and command".to_string());
+        }
+        if names.contains(&spec.name) {
+            return Err(format!("duplicate run spec name: {}", spec.name));
+        }
+        names.push(spec.name.clone());
+    }
+    Ok(specs)
+}
+
+// `# run` section of project.md: `- web: bun run dev` or `- bun run dev`.
+pub(crate) fn parse_project_md_run(md: &str) -> Vec<RunSpec> {
+    let mut out: Vec<RunSpec> = Vec::new();
+    let mut in_run = false;
+    for line in md.lines() {
+        let trimmed = line.trim();
+        if let Some(title) = trimmed.strip_prefix('#') {
+            in_run = title
+                .trim_start_matches('#')
+                .trim()
+                .eq_ignore_ascii_case("run");
+            continue;
+        }
+        if !in_run {
+            continue;
+        }
+        let Some(item) = trimmed.strip_prefix("- ") else {
+            continue;
+        };
+        let item = item.trim().trim_matches('`');
+        let (name, command) = match item.split_once(':') {
+            Some((name, command))
+                if !name.trim().is_empty() && !name.contains(' ') && !command.trim().is_empty() =>
+            {
+                (name.trim().to_string(), command.trim().trim_matches('`'))
+            }
+            _ => (format!("run{}", out.len() + 1), item),
+        };
+        if !command.is_empty() {
+            out.push(RunSpec::simple(&name, command));
+        }
+    }
+    if out.len() == 1 && out[0].name == "run1" {
+        out[0].name = "dev".to_string();
+    }
+    out
+}
+
+fn package_json(dir: &Path) -> Option<serde_json::Value> {
+    let raw = fs::read_to_string(dir.join("package.json")).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn has_dependency(pkg: &serde_json::Value, name: &str) -> bool {
+    ["dependencies", "devDependencies"]
+        .iter()
+        .any(|key| pkg.get(key).and_then(|deps| deps.get(name)).is_some())
+}
+
+pub(crate) fn detect_specs(dir: &Path) -> Vec<RunSpec> {
+    let mut out = Vec::new();
+    if let Some(pkg) = package_json(dir) {
+        let manager = crate::test_runner::node_manager(dir);
+        let script = |name: &str| {
+            pkg.get("scripts")
+                .and_then(|scripts| scripts.get(name))
+                .is_some()
+        };
+        let command = if script("dev") {
+            Some(format!("{} run dev", manager))
+        } else if has_dependency(&pkg, "expo") {
+            Some(if manager == "bun" {
+                "bunx expo start".to_string()
+            } else {
+                "npx expo start".to_string()
+            })
+        } else if script("start") {
+            Some(format!("{} run start", manager))
+        } else {
+            None
+        };
+        if let Some(command) = command {
+            out.push(RunSpec::simple("web", &command));
+        }
+    }
+    if dir.join("Cargo.toml").exists() && dir.join("src").join("main.rs").exists() {
+        out.push(RunSpec::simple("cargo", "cargo run"));
+    }
+    if dir.join("manage.py").exists() {
+        out.push(RunSpec::simple("python", "python3 manage.py runserver"));
+    } else if let Some(entry) = ["main.py", "app.py"]
+        .iter()
+        .find(|file| dir.join(file).exists())
+    {
+        out.push(RunSpec::simple("python", &format!("python3 {}", entry)));
+    }
+    if dir.join("go.mod").exists() && dir.join("main.go").exists() {
+        out.push(RunSpec::simple("go", "go run ."));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_yaml_wins_over_project_md_and_detection() {
+        let root = std::env::temp_dir().join(format!("orc-run-spec-{}", std::process::id()));
+        let project = root.join(".project");
+        fs::create_dir_all(&project).expect("create .project");
+        fs::write(root.join("package.json"), r#"{"scripts":{"dev":"vite"}}"#)
+            .expect("write package.json");
+        assert_eq!(load_specs(&root).expect("detect").1, "detected");
+
+        fs::write(
+            project.join("project.md"),
+            "# run\n- api: cargo run\n- `bun run dev`\n# rules\n- keep it simple\n",
+        )
+        .expect("write project.md");
+        let (specs, source) = load_specs(&root).expect("project.md");
+        assert_eq!(source, "project.md");
+        assert_eq!(specs.len(), 2);
+        assert_eq!(
+            (specs[0].name.as_str(), specs[0].command.as_str()),
+            ("api", "cargo run")
+        );
+        assert_eq!(specs[1].command, "bun run dev");
+
+        fs::write(
+            project.join(RUN_YAML_FILE),
+            "processes:\n  - name: web\n    command: bun run dev\n    port: 3000\n    health: /healthz\n    restart: always\n",
+        )
+        .expect("write run.yaml");
+        let (specs, source) = load_specs(&root).expect("run.yaml");
+        assert_eq!(source, "run.yaml");
+        assert_eq!(specs[0].port, Some(3000));
+        assert_eq!(specs[0].restart, RestartPolicy::Always);
+        assert_eq!(specs[0].max_restarts, DEFAULT_MAX_RESTARTS);
+
+        fs::write(
+            project.join(RUN_YAML_FILE),
+            "processes:\n  - name: web\n    command: a\n  - name: web\n    command: b\n",
+        )
+        .expect("write duplicate run.yaml");
+        assert!(load_specs(&root).is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,244 @@
+mod pty;
+#[cfg(test)]
+mod recording;
+
+use regex::Regex;
+use std::cell::RefCell;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) use pty::PtyTerminal;
+#[cfg(test)]
+pub(crate) use recording::{RecordingTerminal, TerminalEvent};
+
+thread_local! {
+    static OVERRIDE: RefCell<Option<Arc<dyn TerminalBackend>>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TerminalPane {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    // Set by backends that capture the pane's output themselves.
+    pub(crate) log_path: Option<PathBuf>,
+    // Owned by the tmux run layout, which closes it when the run ends.
+    managed: bool,
+}
+
+impl TerminalPane {
+    pub(crate) fn describe(&self) -> String {
+        match &self.log_path {
+            Some(path) => format!("{} ({})", self.id, path.display()),
+            None => self.id.clone(),
+        }
+    }
+}
+
+// Where worker and LLM-debug commands run: a tmux pane when one can be
+// shown, otherwise a headless PTY whose transcript lands in
+// `.project/runtime`.
+pub(crate) trait TerminalBackend: Send + Sync {
+    fn name(&self) -> &str;
+
+    // Whether someone can watch the pane live rather than only read its log.
+    fn interactive(&self) -> bool;
+
+    fn open(&self, title: &str, command: &str) -> Result<TerminalPane, String>;
+
+    // Done with the pane; a command still running in it is ended.
+    fn close(&self, pane: &TerminalPane);
+
+    // Ends the command even when the pane would otherwise be kept.
+    fn kill(&self, pane: &TerminalPane);
+
+    fn notify(&self, _message: &str) {}
+
+    // Waits for `pattern` on the pane's screen. `None` when the backend has
+    // no screen to read; the caller then waits on the command's own files.
+    fn wait_for(
+        &self,
+        _pane: &TerminalPane,
+        _pattern: &Regex,
+        _timeout: Duration,
+    ) -> Option<Result<(), String>> {
+        None
+    }
+}
+
+static NEXT_LOG: AtomicU64 = AtomicU64::new(0);
+
+fn pane_log_path(dir: &Path, backend: &str, title: &str) -> PathBuf {
+    let title: String = title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!(
+        "{}-{}-{}-{}-{}.log",
+        backend,
+        title,
+        crate::now_unix(),
+        std::process::id(),
+        NEXT_LOG.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+pub(crate) struct TmuxTerminal {
+    parent: Option<String>,
+}
+
+impl TmuxTerminal {
+    pub(crate) fn new() -> Self {
+        Self {
+            parent: crate::tmux::current_pane_id().ok(),
+        }
+    }
+}
+
+impl TerminalBackend for TmuxTerminal {
+    fn name(&self) -> &str {
+        "tmux"
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+
+    fn open(&self, title: &str, command: &str) -> Result<TerminalPane, String> {
+        let root = crate::context::project_root();
+        // Mirror the pane into the runtime dir so its output outlives it.
+        let runtime = crate::context::project_dir().join("runtime");
+        let log = std::fs::create_dir_all(&runtime)
+            .is_ok()
+            .then(|| pane_log_path(&runtime, "tmux", title));
+        let pane = crate::tmux::layout::open_task_pane(&root, title, command, log.as_deref())?;
+        Ok(TerminalPane {
+            id: pane.pane_id,
+            title: title.to_string(),
+            log_path: pane.log,
+            managed: pane.managed,
+        })
+    }
+
+    fn close(&self, pane: &TerminalPane) {
+        if !pane.managed {
+            let _ = crate::tmux::kill_pane(&pane.id);
+        }
+    }
+
+    fn kill(&self, pane: &TerminalPane) {
+        let _ = crate::tmux::kill_pane(&pane.id);
+    }
+
+    fn notify(&self, message: &str) {
+        if let Some(parent) = self.parent.as_deref() {
+            let _ = crate::tmux::display_message(parent, message);
+        }
+    }
+
+    fn wait_for(
+        &self,
+        pane: &TerminalPane,
+        pattern: &Regex,
+        timeout: Duration,
+    ) -> Option<Result<(), String>> {
+        Some(crate::tmux::wait::wait_for(&pane.id, pattern, timeout, false).map(|_| ()))
+    }
+}
+
+fn backend_name() -> String {
+    env::var("ORC_TERMINAL_BACKEND")
+        .map(|v| v.trim().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+// Worker and LLM-debug panes stay opt-in: `ORC_USE_TMUX_PANES` or an
+// explicit `ORC_TERMINAL_BACKEND`. Unlike `tmux::layout::session_layout_enabled`
+// this does not need tmux; the PTY backend serves the rest.
+pub(crate) fn worker_panes_enabled() -> bool {
+    let overridden = OVERRIDE.with(|o| o.borrow().is_some());
+    overridden || crate::env_flag_true("ORC_USE_TMUX_PANES") || !backend_name().is_empty()
+}
+
+fn tmux_usable() -> bool {
+    if crate::tmux::layout::active(&crate::context::project_root()).is_some() {
+        return true;
+    }
+    let inside = env::var("TMUX").is_ok_and(|v| !v.trim().is_empty());
+    inside && crate::tmux::available()
+}
+
+// `ORC_TERMINAL_BACKEND` is `tmux`, `pty` or `auto` (default): tmux when we
+// are inside it or a run layout is open, the PTY otherwise.
+pub(crate) fn backend() -> Arc<dyn TerminalBackend> {
+    if let Some(backend) = OVERRIDE.with(|o| o.borrow().clone()) {
+        return backend;
+    }
+    let pty = || -> Arc<dyn TerminalBackend> {
+        Arc::new(PtyTerminal::new(
+            crate::context::project_dir().join("runtime"),
+        ))
+    };
+    match backend_name().as_str() {
+        "tmux" => Arc::new(TmuxTerminal::new()),
+        "pty" => pty(),
+        _ if tmux_usable() => Arc::new(TmuxTerminal::new()),
+        _ => pty(),
+    }
+}
+
+pub(crate) struct OverrideGuard {
+    previous: Option<Arc<dyn TerminalBackend>>,
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        OVERRIDE.with(|o| *o.borrow_mut() = previous);
+    }
+}
+
+// Routes `backend()` on this thread to `backend` until the guard drops; used
+// by tests, which each run on their own thread.
+pub(crate) fn override_backend(backend: Arc<dyn TerminalBackend>) -> OverrideGuard {
+    let previous = OVERRIDE.with(|o| o.borrow_mut().replace(backend));
+    OverrideGuard { previous }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn override_routes_panes_to_the_recording_stub() {
+        let recorder = Arc::new(RecordingTerminal::running());
+        let guard = override_backend(recorder.clone());
+        assert!(worker_panes_enabled());
+        let terminal = backend();
+        assert_eq!(terminal.name(), "record");
+        let pane = terminal.open("orc-check", "true").expect("open");
+        terminal.notify("orc worker done: check");
+        terminal.close(&pane);
+        drop(guard);
+        assert_ne!(backend().name(), "record");
+        assert_eq!(
+            recorder.events(),
+            vec![
+                TerminalEvent::Open {
+                    title: "orc-check".to_string(),
+                    command: "true".to_string(),
+                },
+                TerminalEvent::Notify("orc worker done: check".to_string()),
+                TerminalEvent::Close(pane.id),
+            ]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use super::{TerminalBackend, TerminalPane};
+
+static WARNED_NO_SCRIPT: AtomicBool = AtomicBool::new(false);
+
+// Runs each pane under `script(1)` (util-linux on Linux, BSD on macOS) so
+// the command sees a real terminal, with the transcript written to the
+// runtime dir. Where `script` is missing the pane still runs, but with plain
+// redirection and no TTY; that fallback is reported once per process.
+pub(crate) struct PtyTerminal {
+    dir: PathBuf,
+    children: Mutex<HashMap<String, Child>>,
+}
+
+impl PtyTerminal {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            children: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn end(&self, id: &str) {
+        let child = self
+            .children
+            .lock()
+            .ok()
+            .and_then(|mut children| children.remove(id));
+        if let Some(mut child) = child {
+            if matches!(child.try_wait(), Ok(None)) {
+                crate::process_group::kill_child_tree(&mut child);
+            } else {
+                let _ = child.wait();
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn pty_command(command: &str, log: &Path) -> Command {
+    let mut cmd = Command::new("script");
+    cmd.arg("-q").arg(log).args(["sh", "-c", command]);
+    cmd
+}
+
+#[cfg(not(target_os = "macos"))]
+fn pty_command(command: &str, log: &Path) -> Command {
+    let mut cmd = Command::new("script");
+    cmd.args(["-q", "-f", "-e", "-c", command]).arg(log);
+    cmd
+}
+
+fn plain_command(command: &str, log: &Path) -> Result<Command, String> {
+    let file =
+        fs::File::create(log).map_err(|e| format!("failed to create {}: {}", log.display(), e))?;
+    let err = file
+        .try_clone()
+        .map_err(|e| format!("failed to open {}: {}", log.display(), e))?;
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command]).stdout(file).stderr(err);
+    Ok(cmd)
+}
+
+fn spawn(mut command: Command) -> std::io::Result<Child> {
+    command.stdin(Stdio::null());
+    crate::process_group::isolate(&mut command);
+    command.spawn()
+}
+
+impl TerminalBackend for PtyTerminal {
+    fn name(&self) -> &str {
+        "pty"
+    }
+
+    fn interactive(&self) -> bool {
+        false
+    }
+
+    fn open(&self, title: &str, command: &str) -> Result<TerminalPane, String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("failed to create {}: {}", self.dir.display(), e))?;
+        let log = super::pane_log_path(&self.dir, "pty", title);
+        let mut pty = pty_command(command, &log);
+        pty.stdout(Stdio::null()).stderr(Stdio::null());
+        let child = match spawn(pty) {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if !WARNED_NO_SCRIPT.swap(true, Ordering::Relaxed) {
+                    eprintln!(
+                        "[terminal] script(1) not found; pty panes run without a TTY (install util-linux)"
+                    );
+                }
+                spawn(plain_command(command, &log)?)
+                    .map_err(|e| format!("failed to start {}: {}", title, e))?
+            }
+            Err(e) => return Err(format!("failed to start {}: {}", title, e)),
+        };
+        let id = format!("pty:{}", child.id());
+        if let Ok(mut children) = self.children.lock() {
+            children.insert(id.clone(), child);
+        }
+        Ok(TerminalPane {
+            id,
+            title: title.to_string(),
+            log_path: Some(log),
+            managed: false,
+        })
+    }
+
+    fn close(&self, pane: &TerminalPane) {
+        self.end(&pane.id);
+    }
+
+    fn kill(&self, pane: &TerminalPane) {
+        self.end(&pane.id);
+    }
+
+    fn notify(&self, message: &str) {
+        eprintln!("[terminal] {}", message);
+    }
+}
+
+impl Drop for PtyTerminal {
+    fn drop(&mut self) {
+        let ids: Vec<String> = self
+            .children
+            .lock()
+            .map(|children| children.keys().cloned().collect())
+            .unwrap_or_default();
+        for id in ids {
+            self.end(&id);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn pty_panes_see_a_tty_and_leave_a_transcript() {
+        let dir = std::env::temp_dir().join(format!("orc-pty-test-{}", std::process::id()));
+        let terminal = PtyTerminal::new(dir.clone());
+        let done = dir.join("done");
+        let pane = terminal
+            .open(
+                "worker one",
+                &format!("test -t 1 && echo tty-ok; touch '{}'", done.display()),
+            )
+            .expect("open");
+        let started = Instant::now();
+        while !done.exists() && started.elapsed() < Duration::from_secs(10) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        terminal.close(&pane);
+        let log = pane.log_path.clone().expect("log path");
+        assert!(log
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("pty-worker_one-"));
+        assert!(fs::read_to_string(&log)
+            .unwrap_or_default()
+            .contains("tty-ok"));
+
+        let hung = terminal.open("hung", "sleep 30").expect("open hung");
+        terminal.kill(&hung);
+        assert!(terminal.children.lock().unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
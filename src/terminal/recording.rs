@@ -0,0 +1,84 @@
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use super::{TerminalBackend, TerminalPane};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TerminalEvent {
+    Open { title: String, command: String },
+    Close(String),
+    Kill(String),
+    Notify(String),
+}
+
+// Test stand-in that records every call. `running()` also runs opened
+// commands to completion headlessly, for callers that wait on their files.
+#[derive(Default)]
+pub(crate) struct RecordingTerminal {
+    events: Mutex<Vec<TerminalEvent>>,
+    run_commands: bool,
+}
+
+impl RecordingTerminal {
+    pub(crate) fn running() -> Self {
+        Self {
+            run_commands: true,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn events(&self) -> Vec<TerminalEvent> {
+        self.events.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+
+    fn record(&self, event: TerminalEvent) {
+        if let Ok(mut events) = self.events.lock() {
+            events.push(event);
+        }
+    }
+}
+
+impl TerminalBackend for RecordingTerminal {
+    fn name(&self) -> &str {
+        "record"
+    }
+
+    fn interactive(&self) -> bool {
+        false
+    }
+
+    fn open(&self, title: &str, command: &str) -> Result<TerminalPane, String> {
+        let id = format!("rec:{}", self.events().len());
+        self.record(TerminalEvent::Open {
+            title: title.to_string(),
+            command: command.to_string(),
+        });
+        if self.run_commands {
+            Command::new("sh")
+                .args(["-c", command])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map_err(|e| format!("failed to run {}: {}", title, e))?;
+        }
+        Ok(TerminalPane {
+            id,
+            title: title.to_string(),
+            log_path: None,
+            managed: false,
+        })
+    }
+
+    fn close(&self, pane: &TerminalPane) {
+        self.record(TerminalEvent::Close(pane.id.clone()));
+    }
+
+    fn kill(&self, pane: &TerminalPane) {
+        self.record(TerminalEvent::Kill(pane.id.clone()));
+    }
+
+    fn notify(&self, message: &str) {
+        self.record(TerminalEvent::Notify(message.to_string()));
+    }
+}
@@ -0,0 +1,621 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const NPM_DEFAULT_TEST_SCRIPT: &str = "no test specified";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TestSpec {
+    Cargo,
+    Node(&'static str),
+    Pytest,
+    Go,
+}
+
+impl TestSpec {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            TestSpec::Cargo => "cargo",
+            TestSpec::Node(manager) => manager,
+            TestSpec::Pytest => "pytest",
+            TestSpec::Go => "go",
+        }
+    }
+
+    fn command(&self, filter: Option<&str>) -> (String, Vec<String>) {
+        let mut args: Vec<String> = match self {
+            TestSpec::Cargo => vec!["test".into(), "--no-fail-fast".into()],
+            TestSpec::Node("bun") => vec!["run".into(), "test".into()],
+            TestSpec::Node(_) => vec!["test".into()],
+            TestSpec::Pytest => vec!["-m".into(), "pytest".into(), "-q".into(), "-rf".into()],
+            TestSpec::Go => vec!["test".into(), "./...".into()],
+        };
+        if let Some(filter) = filter {
+            match self {
+                TestSpec::Cargo => args.push(filter.to_string()),
+                TestSpec::Node(_) => {
+                    args.extend(["--".to_string(), "-t".to_string(), filter.to_string()])
+                }
+                TestSpec::Pytest => args.extend(["-k".to_string(), filter.to_string()]),
+                TestSpec::Go => args.extend(["-run".to_string(), filter.to_string()]),
+            }
+        }
+        let program = match self {
+            TestSpec::Cargo => "cargo",
+            TestSpec::Node(manager) => manager,
+            TestSpec::Pytest => "python3",
+            TestSpec::Go => "go",
+        };
+        (program.to_string(), args)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestTarget {
+    pub(crate) spec: TestSpec,
+    pub(crate) dir: PathBuf,
+    pub(crate) label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TestStatus {
+    Passed,
+    Failed,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestFailure {
+    pub(crate) target: String,
+    pub(crate) name: String,
+    pub(crate) detail: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TestRun {
+    pub(crate) target: TestTarget,
+    pub(crate) command: String,
+    pub(crate) status: TestStatus,
+    pub(crate) failures: Vec<TestFailure>,
+    pub(crate) output: String,
+}
+
+impl TestRun {
+    pub(crate) fn summary(&self) -> String {
+        let head = format!("[{} {}]", self.target.spec.label(), self.target.label);
+        match self.status {
+            TestStatus::Passed => format!("{} passed ({})", head, self.command),
+            TestStatus::Failed => format!(
+                "{} failed {} ({})",
+                head,
+                self.failures.len(),
+                self.failures
+                    .iter()
+                    .map(|f| f.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            TestStatus::Error => format!("{} error: {}", head, last_line(&self.output)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DraftHint {
+    pub(crate) name: String,
+    pub(crate) keys: Vec<String>,
+}
+
+pub(crate) fn node_manager(dir: &Path) -> &'static str {
+    if dir.join("bun.lockb").exists() || dir.join("bun.lock").exists() {
+        "bun"
+    } else if dir.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if dir.join("yarn.lock").exists() {
+        "yarn"
+    } else {
+        "npm"
+    }
+}
+
+fn has_node_test_script(dir: &Path) -> bool {
+    let Ok(raw) = fs::read_to_string(dir.join("package.json")) else {
+        return false;
+    };
+    let Ok(doc) = serde_json::from_str::<serde_json::Value>(&raw) else {
+        return false;
+    };
+    doc["scripts"]["test"].as_str().is_some_and(|script| {
+        !script.trim().is_empty() && !script.contains(NPM_DEFAULT_TEST_SCRIPT)
+    })
+}
+
+fn has_pytest_config(dir: &Path) -> bool {
+    if dir.join("pytest.ini").exists() || dir.join("conftest.py").exists() {
+        return true;
+    }
+    let mentions = |file: &str, needle: &str| {
+        fs::read_to_string(dir.join(file)).is_ok_and(|raw| raw.contains(needle))
+    };
+    mentions("pyproject.toml", "[tool.pytest")
+        || mentions("setup.cfg", "[tool:pytest]")
+        || mentions("tox.ini", "[pytest]")
+}
+
+pub(crate) fn detect_specs(dir: &Path) -> Vec<TestSpec> {
+    let mut out = Vec::new();
+    if dir.join("Cargo.toml").exists() {
+        out.push(TestSpec::Cargo);
+    }
+    if has_node_test_script(dir) {
+        out.push(TestSpec::Node(node_manager(dir)));
+    }
+    if has_pytest_config(dir) {
+        out.push(TestSpec::Pytest);
+    }
+    if dir.join("go.mod").exists() {
+        out.push(TestSpec::Go);
+    }
+    out
+}
+
+pub(crate) fn detect_targets(root: &Path) -> Vec<TestTarget> {
+    let root_specs = detect_specs(root);
+    let mut out: Vec<TestTarget> = root_specs
+        .iter()
+        .map(|spec| TestTarget {
+            spec: *spec,
+            dir: root.to_path_buf(),
+            label: ".".to_string(),
+        })
+        .collect();
+    for (_, dir, label) in crate::monorepo::collect_packages(root) {
+        for spec in detect_specs(&dir) {
+            // The root cargo workspace already covers member crates.
+            if spec == TestSpec::Cargo && root_specs.contains(&TestSpec::Cargo) {
+                continue;
+            }
+            out.push(TestTarget {
+                spec,
+                dir: dir.clone(),
+                label: label.clone(),
+            });
+        }
+    }
+    out
+}
+
+pub(crate) fn last_line(text: &str) -> String {
+    text.lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("")
+        .trim()
+        .to_string()
+}
+
+pub(crate) fn run_captured(
+    mut command: Command,
+    timeout_sec: u64,
+) -> Result<(Option<i32>, String), String> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let (mut child, _tracked) =
+        crate::process_group::spawn_tracked(&mut command).map_err(|e| e.to_string())?;
+    let readers: Vec<thread::JoinHandle<String>> = [
+        child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|mut pipe| {
+        thread::spawn(move || {
+            let mut buf = String::new();
+            let _ = pipe.read_to_string(&mut buf);
+            buf
+        })
+    })
+    .collect();
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if started.elapsed() >= Duration::from_secs(timeout_sec) {
+            crate::process_group::kill_child_tree(&mut child);
+            return Err(format!("timed out after {}s", timeout_sec));
+        }
+        thread::sleep(Duration::from_millis(200));
+    };
+    let output = readers
+        .into_iter()
+        .filter_map(|handle| handle.join().ok())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok((status.code(), output))
+}
+
+pub(crate) fn run_target(target: &TestTarget, filter: Option<&str>, timeout_sec: u64) -> TestRun {
+    let (program, args) = target.spec.command(filter);
+    let shown = format!("{} {}", program, args.join(" "));
+    let mut command = Command::new(&program);
+    command.args(&args).current_dir(&target.dir);
+    let (code, output) = match run_captured(command, timeout_sec) {
+        Ok(done) => done,
+        Err(e) => {
+            return TestRun {
+                target: target.clone(),
+                command: shown.clone(),
+                status: TestStatus::Error,
+                failures: Vec::new(),
+                output: format!("failed to run {}: {}", shown, e),
+            }
+        }
+    };
+    let failures = parse_failures(target.spec, &target.label, &output);
+    let status = match (code, failures.is_empty()) {
+        (Some(0), _) => TestStatus::Passed,
+        (_, false) => TestStatus::Failed,
+        _ => TestStatus::Error,
+    };
+    TestRun {
+        target: target.clone(),
+        command: shown,
+        status,
+        failures,
+        output,
+    }
+}
+
+pub(crate) fn run_project_tests(root: &Path) -> Vec<TestRun> {
+    let timeout_sec = crate::load_app_config()
+        .as_ref()
+        .map_or(1800, crate::config::AppConfig::test_timeout_sec);
+    detect_targets(root)
+        .iter()
+        .map(|target| run_target(target, None, timeout_sec))
+        .collect()
+}
+
+pub(crate) fn ran_no_tests(spec: TestSpec, output: &str) -> bool {
+    match spec {
+        TestSpec::Cargo => !output.lines().any(|line| {
+            line.trim()
+                .strip_prefix("running ")
+                .and_then(|rest| rest.split_whitespace().next())
+                .and_then(|n| n.parse::<usize>().ok())
+                .is_some_and(|n| n > 0)
+        }),
+        TestSpec::Go => output.contains("no tests to run"),
+        TestSpec::Pytest => output.contains("no tests ran"),
+        // TAP and `node --test` summaries, then the jest/vitest bail-out.
+        TestSpec::Node(_) => {
+            output.lines().any(|line| {
+                let line = line.trim();
+                let summary = line
+                    .strip_prefix('#')
+                    .or_else(|| line.strip_prefix('ℹ'))
+                    .map(str::trim);
+                summary == Some("tests 0")
+            }) || output.contains("No test files found")
+                || output.contains("No tests found")
+        }
+    }
+}
+
+fn failure(target: &str, name: &str, detail: &str) -> TestFailure {
+    TestFailure {
+        target: target.to_string(),
+        name: name.trim().to_string(),
+        detail: detail.trim().to_string(),
+    }
+}
+
+fn parse_cargo(target: &str, output: &str) -> Vec<TestFailure> {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut out = Vec::new();
+    for line in &lines {
+        let Some(name) = line
+            .trim()
+            .strip_prefix("test ")
+            .and_then(|rest| rest.strip_suffix(" ... FAILED"))
+        else {
+            continue;
+        };
+        if out.iter().any(|f: &TestFailure| f.name == name) {
+            continue;
+        }
+        let header = format!("---- {} stdout ----", name);
+        let detail = lines
+            .iter()
+            .skip_while(|l| l.trim() != header)
+            .skip(1)
+            .take_while(|l| !l.trim().starts_with("---- ") && l.trim() != "failures:")
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with("note:"))
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push(failure(target, name, &detail));
+    }
+    if out.is_empty() {
+        for (idx, line) in lines.iter().enumerate() {
+            if !line.starts_with("error") || line.starts_with("error: could not compile") {
+                continue;
+            }
+            let location = lines
+                .get(idx + 1)
+                .map(|l| l.trim())
+                .and_then(|l| l.strip_prefix("--> "))
+                .unwrap_or("");
+            out.push(failure(target, "build", &format!("{} {}", line, location)));
+        }
+    }
+    out
+}
+
+fn parse_pytest(target: &str, output: &str) -> Vec<TestFailure> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("FAILED "))
+        .map(|rest| match rest.split_once(" - ") {
+            Some((name, detail)) => failure(target, name, detail),
+            None => failure(target, rest, ""),
+        })
+        .collect()
+}
+
+fn parse_go(target: &str, output: &str) -> Vec<TestFailure> {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut out = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        let Some(rest) = line.trim().strip_prefix("--- FAIL: ") else {
+            continue;
+        };
+        let name = rest.split_whitespace().next().unwrap_or(rest);
+        let detail = lines
+            .get(idx + 1)
+            .map(|l| l.trim())
+            .filter(|l| !l.starts_with("---") && !l.starts_with("FAIL"))
+            .unwrap_or("");
+        out.push(failure(target, name, detail));
+    }
+    out
+}
+
+fn strip_duration(name: &str) -> &str {
+    let name = name.trim();
+    let Some((head, last)) = name.rsplit_once(' ') else {
+        return name;
+    };
+    let token = last.trim_start_matches('[').trim_end_matches(']');
+    let is_duration = token.starts_with(|c: char| c.is_ascii_digit())
+        && (token.ends_with("ms") || token.ends_with('s'));
+    if is_duration {
+        head.trim_end()
+    } else {
+        name
+    }
+}
+
+fn parse_node(target: &str, output: &str) -> Vec<TestFailure> {
+    let mut out = Vec::new();
+    let mut file = String::new();
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("FAIL ") {
+            file = rest.trim().to_string();
+            continue;
+        }
+        let name = ["✕ ", "× ", "(fail) "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+            .or_else(|| {
+                trimmed
+                    .strip_prefix("not ok ")
+                    .and_then(|rest| rest.split_once(" - "))
+                    .map(|(_, name)| name)
+            });
+        if let Some(name) = name {
+            out.push(failure(target, strip_duration(name), &file));
+        }
+    }
+    out
+}
+
+pub(crate) fn parse_failures(spec: TestSpec, target: &str, output: &str) -> Vec<TestFailure> {
+    match spec {
+        TestSpec::Cargo => parse_cargo(target, output),
+        TestSpec::Pytest => parse_pytest(target, output),
+        TestSpec::Go => parse_go(target, output),
+        TestSpec::Node(_) => parse_node(target, output),
+    }
+}
+
+// Splits test names and paths into lowercase words: `src/greet.rs:9` ->
+// [src, greet, rs, 9], `TestCheckout` -> [test, checkout].
+fn words(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                out.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            out.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+    out
+}
+
+// A draft owns a failure when one of its keys appears as whole words in the
+// failing target, test name or location, so `auth` does not claim `oauth.rs`.
+pub(crate) fn attribute_failure(failure: &TestFailure, drafts: &[DraftHint]) -> Vec<String> {
+    let haystack = words(&format!(
+        "{} {} {}",
+        failure.target, failure.name, failure.detail
+    ));
+    drafts
+        .iter()
+        .filter(|draft| {
+            std::iter::once(&draft.name)
+                .chain(draft.keys.iter())
+                .filter(|key| key.trim().len() >= 3)
+                .map(|key| words(key))
+                .any(|key| !key.is_empty() && haystack.windows(key.len()).any(|w| w == key))
+        })
+        .map(|draft| draft.name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_are_parsed_per_runner() {
+        let cargo = "running 2 tests\ntest greet::works ... ok\ntest greet::greets_user ... FAILED\n\nfailures:\n\n---- greet::greets_user stdout ----\nthread 'greet::greets_user' panicked at src/greet.rs:9:5:\nassertion failed: out == \"hello\"\nnote: run with `RUST_BACKTRACE=1`\n\nfailures:\n    greet::greets_user\n";
+        let parsed = parse_failures(TestSpec::Cargo, ".", cargo);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name, "greet::greets_user");
+        assert!(parsed[0].detail.contains("src/greet.rs:9:5"));
+
+        let build = "error[E0425]: cannot find value `x` in this scope\n  --> src/lib.rs:3:5\nerror: could not compile `demo`\n";
+        let parsed = parse_failures(TestSpec::Cargo, ".", build);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name, "build");
+        assert!(parsed[0].detail.ends_with("src/lib.rs:3:5"));
+
+        let pytest = "F.\nFAILED tests/test_login.py::test_rejects_empty - AssertionError: 1 != 2\n1 failed, 1 passed\n";
+        let parsed = parse_failures(TestSpec::Pytest, "api", pytest);
+        assert_eq!(parsed[0].name, "tests/test_login.py::test_rejects_empty");
+        assert_eq!(parsed[0].detail, "AssertionError: 1 != 2");
+
+        let go = "--- FAIL: TestCheckout (0.00s)\n    cart_test.go:14: total = 3\nFAIL\tshop/cart\t0.01s\n";
+        let parsed = parse_failures(TestSpec::Go, ".", go);
+        assert_eq!(parsed[0].name, "TestCheckout");
+        assert_eq!(parsed[0].detail, "cart_test.go:14: total = 3");
+
+        let node = " FAIL  src/cart.test.ts > cart\n   × adds item 3ms\n(fail) removes item [0.12ms]\nnot ok 2 - clears cart\n";
+        let names: Vec<String> = parse_failures(TestSpec::Node("bun"), "web", node)
+            .into_iter()
+            .map(|f| f.name)
+            .collect();
+        assert_eq!(names, vec!["adds item", "removes item", "clears cart"]);
+    }
+
+    #[test]
+    fn targets_follow_detected_specs_and_failures_map_to_drafts() {
+        let root = std::env::temp_dir().join(format!(
+            "orc_test_runner_{}_{}",
+            std::process::id(),
+            crate::now_unix()
+        ));
+        let web = root.join("apps").join("web").join("site");
+        fs::create_dir_all(&web).expect("create package dir");
+        fs::write(root.join("Cargo.toml"), "[workspace]\n").expect("write cargo");
+        fs::write(
+            root.join("package.json"),
+            "{\"scripts\":{\"test\":\"echo \\\"Error: no test specified\\\" && exit 1\"}}",
+        )
+        .expect("write root package");
+        fs::write(
+            web.join("package.json"),
+            "{\"scripts\":{\"test\":\"vitest run\"}}",
+        )
+        .expect("write web package");
+        fs::write(web.join("pnpm-lock.yaml"), "").expect("write lock");
+        fs::write(root.join("pytest.ini"), "[pytest]\n").expect("write pytest");
+
+        let targets: Vec<(TestSpec, String)> = detect_targets(&root)
+            .into_iter()
+            .map(|t| (t.spec, t.label))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                (TestSpec::Cargo, ".".to_string()),
+                (TestSpec::Pytest, ".".to_string()),
+                (TestSpec::Node("pnpm"), "web/site".to_string()),
+            ]
+        );
+
+        let drafts = vec![
+            DraftHint {
+                name: "greet_user".to_string(),
+                keys: vec!["src/greet.rs".to_string()],
+            },
+            DraftHint {
+                name: "checkout".to_string(),
+                keys: Vec::new(),
+            },
+        ];
+        let failed = failure(".", "greet::greets_user", "panicked at src/greet.rs:9:5");
+        assert_eq!(
+            attribute_failure(&failed, &drafts),
+            vec!["greet_user".to_string()]
+        );
+        let failed = failure(".", "TestCheckout", "");
+        assert_eq!(
+            attribute_failure(&failed, &drafts),
+            vec!["checkout".to_string()]
+        );
+        let drafts = vec![
+            DraftHint {
+                name: "auth".to_string(),
+                keys: vec!["src/auth".to_string()],
+            },
+            DraftHint {
+                name: "greet".to_string(),
+                keys: Vec::new(),
+            },
+        ];
+        let failed = failure(
+            ".",
+            "oauth::greetings_render",
+            "panicked at src/oauth.rs:3:1",
+        );
+        assert!(attribute_failure(&failed, &drafts).is_empty());
+        let failed = failure(".", "login::works", "panicked at src/auth/login.rs:3:1");
+        assert_eq!(
+            attribute_failure(&failed, &drafts),
+            vec!["auth".to_string()]
+        );
+        let _ = fs::remove_dir_all(root);
+    }
+    #[test]
+    fn runs_without_tests_are_detected() {
+        let node = TestSpec::Node("npm");
+        assert!(ran_no_tests(
+            node,
+            "TAP version 13\n1..0\n# tests 0\n# pass 0\n"
+        ));
+        assert!(ran_no_tests(node, "ℹ tests 0\nℹ suites 0\nℹ pass 0\n"));
+        assert!(ran_no_tests(
+            node,
+            "No test files found, exiting with code 1\n"
+        ));
+        assert!(!ran_no_tests(node, "ℹ tests 10\nℹ pass 10\n"));
+        assert!(!ran_no_tests(node, "# tests 3\n# pass 3\n"));
+        assert!(ran_no_tests(TestSpec::Cargo, "running 0 tests\n"));
+        assert!(!ran_no_tests(TestSpec::Cargo, "running 2 tests\n"));
+    }
+}
@@ -0,0 +1,366 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::run_tmux;
+
+const RUN_WINDOW: &str = "run";
+
+// One open run per project root; the web API runs several projects at once.
+static ACTIVE: Mutex<Vec<Arc<RunLayout>>> = Mutex::new(Vec::new());
+
+// tmux rejects `.` and `:` in session names.
+pub fn session_name(project: &str) -> String {
+    let cleaned: String = project
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_matches('-');
+    if cleaned.is_empty() {
+        "orc-project".to_string()
+    } else {
+        format!("orc-{}", cleaned)
+    }
+}
+
+// Basename for people, plus a short hash of the canonical root so two
+// checkouts named alike do not share (and tear down) one session.
+pub fn project_session_name(root: &Path) -> String {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut hasher = DefaultHasher::new();
+    root.hash(&mut hasher);
+    format!(
+        "{}-{:06x}",
+        session_name(
+            &root
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        ),
+        hasher.finish() & 0xff_ffff
+    )
+}
+
+// Resolves `orc tmux-attach <project>`: a directory maps to its own session,
+// a bare name to the only running session for that basename.
+pub fn find_project_session(project: &str) -> Result<String, String> {
+    let dir = Path::new(project);
+    if dir.is_dir() {
+        return Ok(project_session_name(dir));
+    }
+    let prefix = format!("{}-", session_name(project));
+    let sessions = run_tmux(&["list-sessions", "-F", "#{session_name}"]).unwrap_or_default();
+    let matches: Vec<&str> = sessions
+        .lines()
+        .filter(|s| {
+            s.strip_prefix(&prefix)
+                .is_some_and(|h| h.len() == 6 && h.chars().all(|c| c.is_ascii_hexdigit()))
+        })
+        .collect();
+    match matches.as_slice() {
+        [only] => Ok(only.to_string()),
+        [] => Ok(session_name(project)),
+        _ => Err(format!(
+            "several sessions match {}: {}; pass the project path or the session name",
+            project,
+            matches.join(", ")
+        )),
+    }
+}
+
+// Whether parallel runs open the per-project session: opt-in via
+// `ORC_USE_TMUX_PANES` and tmux installed. Unlike the split-off-current-pane
+// mode, the project session does not require running inside tmux.
+pub fn session_layout_enabled() -> bool {
+    crate::env_flag_true("ORC_USE_TMUX_PANES") && super::available()
+}
+
+pub fn session_exists(session: &str) -> bool {
+    run_tmux(&["has-session", "-t", &format!("={}", session)]).is_ok()
+}
+
+// Detached `orc-<project>-<hash>` session whose `run` window holds one tiled pane
+// per task. Panes stay after their command exits until the run ends.
+pub struct RunLayout {
+    session: String,
+    cwd: PathBuf,
+    panes: Mutex<Vec<String>>,
+    // The shell pane tmux opens with a new window; the first task takes it
+    // over instead of tiling next to it.
+    initial: Mutex<Option<String>>,
+}
+
+impl RunLayout {
+    fn target(&self) -> String {
+        format!("={}:{}", self.session, RUN_WINDOW)
+    }
+
+    pub fn open(root: &Path) -> Result<Self, String> {
+        let session = project_session_name(root);
+        let cwd = root.display().to_string();
+        let initial = if !session_exists(&session) {
+            Some(run_tmux(&[
+                "new-session",
+                "-d",
+                "-s",
+                &session,
+                "-n",
+                RUN_WINDOW,
+                "-c",
+                &cwd,
+                "-x",
+                "240",
+                "-y",
+                "60",
+                "-P",
+                "-F",
+                "#{pane_id}",
+            ])?)
+        } else if run_tmux(&["list-panes", "-t", &format!("={}:{}", session, RUN_WINDOW)]).is_err()
+        {
+            Some(run_tmux(&[
+                "new-window",
+                "-d",
+                "-t",
+                &format!("={}", session),
+                "-n",
+                RUN_WINDOW,
+                "-c",
+                &cwd,
+                "-P",
+                "-F",
+                "#{pane_id}",
+            ])?)
+        } else {
+            None
+        };
+        let layout = Self {
+            session,
+            cwd: root.to_path_buf(),
+            panes: Mutex::new(Vec::new()),
+            initial: Mutex::new(initial.filter(|id| !id.is_empty())),
+        };
+        let target = layout.target();
+        for (option, value) in [
+            ("remain-on-exit", "on"),
+            ("pane-border-status", "top"),
+            ("pane-border-format", " #{pane_title} "),
+        ] {
+            run_tmux(&["set-option", "-w", "-t", &target, option, value])?;
+        }
+        Ok(layout)
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    pub fn respawn(&self, pane_id: &str, command: &str) -> Result<(), String> {
+        run_tmux(&[
+            "respawn-pane",
+            "-k",
+            "-t",
+            pane_id,
+            "-c",
+            &self.cwd.display().to_string(),
+            "bash",
+            "-lc",
+            command,
+        ])?;
+        Ok(())
+    }
+
+    pub fn spawn(&self, title: &str, command: &str) -> Result<String, String> {
+        let target = self.target();
+        let cwd = self.cwd.display().to_string();
+        let initial = self.initial.lock().ok().and_then(|mut i| i.take());
+        let respawn = |pane_id: String| self.respawn(&pane_id, command).map(|_| pane_id);
+        let split = || {
+            run_tmux(&[
+                "split-window",
+                "-d",
+                "-t",
+                &target,
+                "-c",
+                &cwd,
+                "-P",
+                "-F",
+                "#{pane_id}",
+                "bash",
+                "-lc",
+                command,
+            ])
+        };
+        let reused = initial.and_then(|pane_id| respawn(pane_id).ok());
+        let pane_id = match reused.map_or_else(split, Ok) {
+            Ok(pane_id) => pane_id,
+            Err(_) => {
+                // Usually "no space for new pane": re-tile and try once more.
+                run_tmux(&["select-layout", "-t", &target, "tiled"])?;
+                split()?
+            }
+        };
+        let _ = run_tmux(&["select-pane", "-t", &pane_id, "-T", title]);
+        let _ = run_tmux(&["select-layout", "-t", &target, "tiled"]);
+        if let Ok(mut panes) = self.panes.lock() {
+            panes.push(pane_id.clone());
+        }
+        Ok(pane_id)
+    }
+
+    fn finish(&self) {
+        let panes = self
+            .panes
+            .lock()
+            .map(|mut panes| std::mem::take(&mut *panes))
+            .unwrap_or_default();
+        for pane in panes {
+            let _ = super::kill_pane(&pane);
+        }
+        let _ = run_tmux(&["select-layout", "-t", &self.target(), "tiled"]);
+    }
+}
+
+// Keeps the layout active for `open_task_pane` callers until dropped, then
+// closes every pane the run opened.
+pub struct RunGuard {
+    layout: Arc<RunLayout>,
+}
+
+impl RunGuard {
+    pub fn session(&self) -> &str {
+        self.layout.session()
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE.lock() {
+            active.retain(|l| !Arc::ptr_eq(l, &self.layout));
+        }
+        self.layout.finish();
+    }
+}
+
+pub fn begin_run(root: &Path) -> Result<RunGuard, String> {
+    let layout = Arc::new(RunLayout::open(root)?);
+    if let Ok(mut active) = ACTIVE.lock() {
+        active.retain(|l| l.cwd != layout.cwd);
+        active.push(layout.clone());
+    }
+    Ok(RunGuard { layout })
+}
+
+pub fn active(root: &Path) -> Option<Arc<RunLayout>> {
+    ACTIVE
+        .lock()
+        .ok()
+        .and_then(|active| active.iter().find(|l| l.cwd == root).cloned())
+}
+
+// A pane for one unit of work. Inside a run it is a titled tile of the
+// project session and is closed by the run; otherwise it is split off the
+// current pane and the caller closes it with `close_task_pane`.
+pub struct TaskPane {
+    pub pane_id: String,
+    pub managed: bool,
+    // Set when the pane output is piped to the requested log.
+    pub log: Option<PathBuf>,
+}
+
+// With `log`, the pane starts on `HOLD_COMMAND`, gets piped, and only then
+// runs `command`, so the log holds its output from the first line.
+pub fn open_task_pane(
+    root: &Path,
+    title: &str,
+    command: &str,
+    log: Option<&Path>,
+) -> Result<TaskPane, String> {
+    let first = if log.is_some() {
+        super::HOLD_COMMAND
+    } else {
+        command
+    };
+    let layout = active(root);
+    let pane_id = match layout.as_ref() {
+        Some(layout) => layout.spawn(title, first)?,
+        None => {
+            let pane_id = super::split_window_run(first)?;
+            let _ = super::rename_pane(&pane_id, title);
+            pane_id
+        }
+    };
+    let Some(log) = log else {
+        return Ok(TaskPane {
+            pane_id,
+            managed: layout.is_some(),
+            log: None,
+        });
+    };
+    let piped = super::pipe_pane(&pane_id, log).is_ok();
+    let started = match layout.as_ref() {
+        Some(layout) => layout.respawn(&pane_id, command),
+        None => super::respawn_pane(&pane_id, command),
+    };
+    if let Err(e) = started {
+        let _ = super::kill_pane(&pane_id);
+        return Err(e);
+    }
+    Ok(TaskPane {
+        pane_id,
+        managed: layout.is_some(),
+        log: piped.then(|| log.to_path_buf()),
+    })
+}
+
+pub fn close_task_pane(pane: &TaskPane) {
+    if !pane.managed {
+        let _ = super::kill_pane(&pane.pane_id);
+    }
+}
+
+pub fn attach(session: &str) -> Result<(), String> {
+    if !session_exists(session) {
+        return Err(format!("tmux session not found: {}", session));
+    }
+    let inside = std::env::var("TMUX").is_ok_and(|v| !v.trim().is_empty());
+    let verb = if inside {
+        "switch-client"
+    } else {
+        "attach-session"
+    };
+    let status = std::process::Command::new("tmux")
+        .args([verb, "-t", &format!("={}", session)])
+        .status()
+        .map_err(|e| format!("failed to execute tmux: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("tmux {} failed for {}", verb, session))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_names_are_tmux_safe() {
+        assert_eq!(session_name("shop-api"), "orc-shop-api");
+        assert_eq!(session_name(" my.app:v2 "), "orc-my-app-v2");
+        assert_eq!(session_name("..."), "orc-project");
+        let name = project_session_name(Path::new("/work/rust_orc"));
+        assert!(name.starts_with("orc-rust_orc-"), "{}", name);
+        assert_eq!(name.len(), "orc-rust_orc-".len() + 6);
+        assert_eq!(name, project_session_name(Path::new("/work/rust_orc")));
+        assert_ne!(name, project_session_name(Path::new("/other/rust_orc")));
+        assert!(!name.contains('.') && !name.contains(':'));
+    }
+}
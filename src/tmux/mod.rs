@@ -1,4 +1,13 @@
-use std::process::Command;
+pub mod layout;
+pub mod send;
+pub mod wait;
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_BUFFER: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SendOption {
@@ -18,6 +27,33 @@ fn run_tmux(args: &[&str]) -> Result<String, String> {
     }
 }
 
+fn run_tmux_with_input(args: &[&str], input: &str) -> Result<String, String> {
+    let mut child = Command::new("tmux")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to execute tmux: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| format!("failed to write tmux stdin: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("failed to execute tmux: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+pub fn available() -> bool {
+    run_tmux(&["-V"]).is_ok()
+}
+
 pub fn split_window_pane() -> Result<String, String> {
     if let Ok(target) = current_pane_id() {
         return run_tmux(&[
@@ -35,6 +71,15 @@ pub fn split_window_pane() -> Result<String, String> {
     run_tmux(&["split-window", "-h", "-P", "-F", "#{pane_id}"])
 }
 
+// Placeholder a pane runs until `respawn_pane` swaps in the real command, so
+// a `pipe_pane` attached in between catches the command's first line.
+pub const HOLD_COMMAND: &str = "sleep 86400";
+
+pub fn respawn_pane(pane_id: &str, command: &str) -> Result<(), String> {
+    run_tmux(&["respawn-pane", "-k", "-t", pane_id, "bash", "-lc", command])?;
+    Ok(())
+}
+
 pub fn split_window_run(command: &str) -> Result<String, String> {
     if let Ok(target) = current_pane_id() {
         return run_tmux(&[
@@ -58,15 +103,95 @@ pub fn split_window_run(command: &str) -> Result<String, String> {
 pub fn send_keys(pane_id: &str, msg: &str, option: SendOption) -> Result<(), String> {
     match option {
         SendOption::Enter => {
-            run_tmux(&["send-keys", "-t", pane_id, msg, "C-m"])?;
+            run_tmux(&["send-keys", "-t", pane_id, "--", msg, "C-m"])?;
         }
         SendOption::Raw => {
-            run_tmux(&["send-keys", "-t", pane_id, msg])?;
+            run_tmux(&["send-keys", "-t", pane_id, "--", msg])?;
         }
     }
     Ok(())
 }
 
+// Types `msg` verbatim; key names like `Enter` inside it are not interpreted.
+pub fn send_literal(pane_id: &str, msg: &str) -> Result<(), String> {
+    run_tmux(&["send-keys", "-t", pane_id, "-l", "--", msg])?;
+    Ok(())
+}
+
+pub fn send_enter(pane_id: &str) -> Result<(), String> {
+    run_tmux(&["send-keys", "-t", pane_id, "Enter"])?;
+    Ok(())
+}
+
+// Multi-line text goes through a buffer as a bracketed paste, so the
+// receiving program sees one paste instead of a line-by-line submit.
+pub fn paste_text(pane_id: &str, text: &str) -> Result<(), String> {
+    let buffer = format!(
+        "orc-{}-{}",
+        std::process::id(),
+        NEXT_BUFFER.fetch_add(1, Ordering::Relaxed)
+    );
+    run_tmux_with_input(&["load-buffer", "-b", buffer.as_str(), "-"], text)?;
+    run_tmux(&["paste-buffer", "-d", "-p", "-b", buffer.as_str(), "-t", pane_id])?;
+    Ok(())
+}
+
+pub fn pane_exists(pane_id: &str) -> bool {
+    run_tmux(&["display-message", "-p", "-t", pane_id, "#{pane_id}"]).is_ok_and(|id| id == pane_id)
+}
+
+// Absolute line of the cursor, counted from the top of the pane history.
+pub fn pane_line(pane_id: &str) -> Result<i64, String> {
+    let raw = run_tmux(&[
+        "display-message",
+        "-p",
+        "-t",
+        pane_id,
+        "#{history_size} #{cursor_y}",
+    ])?;
+    let mut parts = raw.split_whitespace().map(|v| v.parse::<i64>());
+    match (parts.next(), parts.next()) {
+        (Some(Ok(history)), Some(Ok(cursor))) => Ok(history + cursor),
+        _ => Err(format!("unexpected tmux pane position: {}", raw)),
+    }
+}
+
+// Joined pane text from absolute line `from` (see `pane_line`) to the bottom.
+pub fn capture_from(pane_id: &str, from: i64) -> Result<String, String> {
+    let history = run_tmux(&["display-message", "-p", "-t", pane_id, "#{history_size}"])?
+        .parse::<i64>()
+        .map_err(|e| format!("unexpected tmux history size: {}", e))?;
+    let start = (from - history).to_string();
+    run_tmux(&[
+        "capture-pane",
+        "-p",
+        "-J",
+        "-t",
+        pane_id,
+        "-S",
+        start.as_str(),
+        "-E",
+        "-",
+    ])
+}
+
+// Whole pane: scrollback plus the visible screen, wrapped lines joined.
+pub fn capture_pane(pane_id: &str) -> Result<String, String> {
+    run_tmux(&["capture-pane", "-p", "-J", "-t", pane_id, "-S", "-", "-E", "-"])
+}
+
+// Streams everything the pane prints from now on into `path` (appended).
+pub fn pipe_pane(pane_id: &str, path: &Path) -> Result<(), String> {
+    let target = format!("cat >> '{}'", path.display().to_string().replace('\'', "'\"'\"'"));
+    run_tmux(&["pipe-pane", "-o", "-t", pane_id, target.as_str()])?;
+    Ok(())
+}
+
+pub fn stop_pipe_pane(pane_id: &str) -> Result<(), String> {
+    run_tmux(&["pipe-pane", "-t", pane_id])?;
+    Ok(())
+}
+
 pub fn current_pane_id() -> Result<String, String> {
     run_tmux(&["display-message", "-p", "#{pane_id}"])
 }
@@ -88,16 +213,3 @@ pub fn display_message(pane_id: &str, msg: &str) -> Result<(), String> {
     run_tmux(&["display-message", "-t", pane_id, msg])?;
     Ok(())
 }
-
-pub fn tsend(pane_id: &str, msg: &str, option: &str) -> Result<String, String> {
-    let send_option = match option {
-        "raw" => SendOption::Raw,
-        "enter" => SendOption::Enter,
-        _ => return Err("tsend option must be `enter` or `raw`".to_string()),
-    };
-    send_keys(pane_id, msg, send_option)?;
-    Ok(format!(
-        "tsend done: pane={} option={} msg={}",
-        pane_id, option, msg
-    ))
-}
@@ -0,0 +1,214 @@
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+use super::SendOption;
+
+// Longer literal sends risk tmux's command size limit; they go as a paste.
+const LITERAL_LIMIT: usize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendMode {
+    // Literal for one short line, paste otherwise.
+    Auto,
+    // `send-keys -l`: typed verbatim, key names are not interpreted.
+    Literal,
+    // `load-buffer` + `paste-buffer -p`: one bracketed paste.
+    Paste,
+    // Plain `send-keys`: words like `C-c` or `Enter` are key names.
+    Keys,
+}
+
+impl SendMode {
+    pub fn resolve(self, text: &str) -> SendMode {
+        match self {
+            SendMode::Auto if text.contains('\n') || text.len() > LITERAL_LIMIT => SendMode::Paste,
+            SendMode::Auto => SendMode::Literal,
+            mode => mode,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SendMode::Auto => "auto",
+            SendMode::Literal => "literal",
+            SendMode::Paste => "paste",
+            SendMode::Keys => "keys",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payload {
+    Inline(String),
+    File(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendArgs {
+    pub pane_id: String,
+    pub payload: Payload,
+    pub mode: SendMode,
+    pub option: SendOption,
+}
+
+const USAGE: &str = "send-tmux requires <pane_id> (<msg...> | --file <path> | --stdin) [--paste|--literal|--keys] [enter|raw]";
+
+// Only the known flags are options, so other words starting with `-` stay
+// part of the message; `--` ends option parsing.
+pub fn parse_args(tail: &[String]) -> Result<SendArgs, String> {
+    let (pane_id, rest) = tail.split_first().ok_or(USAGE)?;
+    let mut words: Vec<String> = Vec::new();
+    let mut payload = None;
+    let mut mode = SendMode::Auto;
+    let mut i = 0usize;
+    while i < rest.len() {
+        match rest[i].as_str() {
+            "--" => {
+                words.extend(rest[i + 1..].iter().cloned());
+                break;
+            }
+            "--file" | "-f" => {
+                let path = rest.get(i + 1).ok_or("send-tmux: --file requires value")?;
+                payload = Some(Payload::File(PathBuf::from(path)));
+                i += 1;
+            }
+            "--stdin" | "-" => payload = Some(Payload::Stdin),
+            "--paste" => mode = SendMode::Paste,
+            "--literal" => mode = SendMode::Literal,
+            "--keys" => mode = SendMode::Keys,
+            _ => words.push(rest[i].clone()),
+        }
+        i += 1;
+    }
+    // A trailing `enter`/`raw` is the submit option, as long as something
+    // else is being sent.
+    let mut option = SendOption::Enter;
+    let has_other = payload.is_some() || words.len() >= 2;
+    if let Some(last) = words.last().filter(|_| has_other) {
+        if let Some(parsed) = match last.as_str() {
+            "enter" => Some(SendOption::Enter),
+            "raw" => Some(SendOption::Raw),
+            _ => None,
+        } {
+            option = parsed;
+            words.pop();
+        }
+    }
+    let payload = match payload {
+        Some(_) if !words.is_empty() => {
+            return Err("send-tmux: give a message or --file/--stdin, not both".to_string())
+        }
+        Some(payload) => payload,
+        None if words.is_empty() => return Err(USAGE.to_string()),
+        None => Payload::Inline(words.join(" ")),
+    };
+    Ok(SendArgs {
+        pane_id: pane_id.clone(),
+        payload,
+        mode,
+        option,
+    })
+}
+
+// File and stdin payloads lose their trailing newline; the submit is
+// governed by `enter`/`raw` instead.
+pub fn read_payload(payload: &Payload) -> Result<String, String> {
+    let raw = match payload {
+        Payload::Inline(text) => return Ok(text.clone()),
+        Payload::File(path) => fs::read_to_string(path)
+            .map_err(|e| format!("send-tmux: failed to read {}: {}", path.display(), e))?,
+        Payload::Stdin => {
+            let mut raw = String::new();
+            std::io::stdin()
+                .read_to_string(&mut raw)
+                .map_err(|e| format!("send-tmux: failed to read stdin: {}", e))?;
+            raw
+        }
+    };
+    Ok(raw.trim_end_matches(['\n', '\r']).to_string())
+}
+
+pub fn send_text(
+    pane_id: &str,
+    text: &str,
+    mode: SendMode,
+    option: SendOption,
+) -> Result<SendMode, String> {
+    let mode = mode.resolve(text);
+    match mode {
+        SendMode::Keys => return super::send_keys(pane_id, text, option).map(|_| mode),
+        SendMode::Paste => super::paste_text(pane_id, text)?,
+        _ => super::send_literal(pane_id, text)?,
+    }
+    if option == SendOption::Enter {
+        super::send_enter(pane_id)?;
+    }
+    Ok(mode)
+}
+
+pub fn send_tmux_command(tail: &[String]) -> Result<String, String> {
+    let args = parse_args(tail)?;
+    let text = read_payload(&args.payload)?;
+    if text.is_empty() {
+        return Err("send-tmux requires non-empty message".to_string());
+    }
+    let mode = send_text(&args.pane_id, &text, args.mode, args.option)?;
+    Ok(format!(
+        "send-tmux done: pane={} mode={} option={} bytes={}",
+        args.pane_id,
+        mode.label(),
+        if args.option == SendOption::Enter {
+            "enter"
+        } else {
+            "raw"
+        },
+        text.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Result<SendArgs, String> {
+        parse_args(&raw.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parses_payload_sources_modes_and_submit_option() {
+        let plain = args(&["%1", "run", "-v", "Enter", "raw"]).expect("plain");
+        assert_eq!(plain.payload, Payload::Inline("run -v Enter".to_string()));
+        assert_eq!(
+            (plain.mode, plain.option),
+            (SendMode::Auto, SendOption::Raw)
+        );
+
+        // A lone word is the message even if it reads like the option.
+        let lone = args(&["%1", "enter"]).expect("lone");
+        assert_eq!(lone.payload, Payload::Inline("enter".to_string()));
+        assert_eq!(lone.option, SendOption::Enter);
+
+        let file = args(&["%1", "--file", "p.md", "--paste", "raw"]).expect("file");
+        assert_eq!(file.payload, Payload::File(PathBuf::from("p.md")));
+        assert_eq!((file.mode, file.option), (SendMode::Paste, SendOption::Raw));
+
+        let stdin = args(&["%1", "-", "--keys"]).expect("stdin");
+        assert_eq!(
+            (stdin.payload, stdin.mode),
+            (Payload::Stdin, SendMode::Keys)
+        );
+
+        let dashed = args(&["%1", "--", "--paste", "-x"]).expect("dashed");
+        assert_eq!(dashed.payload, Payload::Inline("--paste -x".to_string()));
+
+        assert!(args(&["%1", "hi", "--stdin"]).is_err());
+        assert!(args(&["%1"]).is_err());
+
+        assert_eq!(SendMode::Auto.resolve("one line"), SendMode::Literal);
+        assert_eq!(SendMode::Auto.resolve("a\nb"), SendMode::Paste);
+        assert_eq!(SendMode::Auto.resolve(&"x".repeat(5000)), SendMode::Paste);
+        assert_eq!(SendMode::Keys.resolve("a\nb"), SendMode::Keys);
+    }
+}
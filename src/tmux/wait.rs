@@ -0,0 +1,80 @@
+use regex::Regex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaitMatch {
+    // The pane line holding the (last) match.
+    pub line: String,
+    pub elapsed: Duration,
+}
+
+// Line containing the last match in `text`, so a prompt that was printed
+// again after earlier output is reported at its latest position.
+pub fn find_match(text: &str, pattern: &Regex) -> Option<String> {
+    let found = pattern.find_iter(text).last()?;
+    let start = text[..found.start()].rfind('\n').map_or(0, |i| i + 1);
+    let end = text[found.end()..]
+        .find('\n')
+        .map_or(text.len(), |i| found.end() + i);
+    Some(text[start..end].trim_end().to_string())
+}
+
+// Polls the pane until `pattern` shows up. With `only_new`, text already in
+// the pane when the wait starts is ignored, so an old completion marker
+// cannot satisfy it.
+pub fn wait_for(
+    pane_id: &str,
+    pattern: &Regex,
+    timeout: Duration,
+    only_new: bool,
+) -> Result<WaitMatch, String> {
+    let from = if only_new {
+        Some(super::pane_line(pane_id)?)
+    } else {
+        None
+    };
+    let started = Instant::now();
+    loop {
+        if !super::pane_exists(pane_id) {
+            return Err(format!("pane {} is gone", pane_id));
+        }
+        let text = match from {
+            Some(from) => super::capture_from(pane_id, from)?,
+            None => super::capture_pane(pane_id)?,
+        };
+        if let Some(line) = find_match(&text, pattern) {
+            return Ok(WaitMatch {
+                line,
+                elapsed: started.elapsed(),
+            });
+        }
+        if started.elapsed() >= timeout {
+            return Err(format!(
+                "no match for /{}/ in pane {} within {}s",
+                pattern.as_str(),
+                pane_id,
+                timeout.as_secs()
+            ));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_the_line_of_the_latest_match() {
+        let text = "$ make\nbuilding\n[done] 1\n$ make\n[done] 2\n$ ";
+        let done = Regex::new(r"\[done\] \d").expect("done regex");
+        assert_eq!(find_match(text, &done), Some("[done] 2".to_string()));
+        let prompt = Regex::new(r"\$ $").expect("prompt regex");
+        assert_eq!(find_match(text, &prompt), Some("$".to_string()));
+        let error = Regex::new("error").expect("error regex");
+        assert_eq!(find_match(text, &error), None);
+    }
+}
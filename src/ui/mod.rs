@@ -1,6 +1,7 @@
 mod component;
 
-use crate::ProjectRecord;
+use crate::registry::{self, ProjectRegistry};
+use crate::{ProjectRecord, ProjectType};
 use ratatui::backend::CrosstermBackend;
 use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::crossterm::execute;
@@ -13,7 +14,7 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use ratatui::Terminal;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 use std::env;
 use std::fs::{self, OpenOptions};
 use std::io::{self, BufRead, Write};
@@ -26,16 +27,6 @@ use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-const UI_REGISTRY_PATH: &str = "configs/project.yaml";
-
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
-struct UiProjectRegistry {
-    #[serde(default, rename = "recentActivepane")]
-    recent_active_pane: Option<String>,
-    #[serde(default)]
-    projects: Vec<ProjectRecord>,
-}
-
 pub fn render_project_list(projects: &[ProjectRecord]) -> String {
     const MAX_CARDS: usize = 9;
     const COLUMNS: usize = 3;
@@ -128,6 +119,7 @@ pub enum TaskRuntimeState {
     Inactive,
     Active,
     Clear,
+    Failed,
 }
 
 pub fn render_task_runtime_status(task_name: &str, state: TaskRuntimeState) -> String {
@@ -135,6 +127,7 @@ pub fn render_task_runtime_status(task_name: &str, state: TaskRuntimeState) -> S
         TaskRuntimeState::Inactive => "[ ]",
         TaskRuntimeState::Active => "[>]",
         TaskRuntimeState::Clear => "[x]",
+        TaskRuntimeState::Failed => "[!]",
     };
     format!("{} {}", badge, task_name)
 }
@@ -157,7 +150,7 @@ pub enum DraftCommand {
 }
 
 pub fn resolve_feature_draft_path(feature_name: &str) -> PathBuf {
-    PathBuf::from(".project")
+    crate::context::project_dir()
         .join("feature")
         .join(feature_name)
         .join("drafts.yaml")
@@ -397,6 +390,69 @@ struct AlarmModal {
     message: String,
 }
 
+// Live view of a parallel build, read from the project's runtime dir since
+// the build runs in a child `orc` process.
+#[derive(Debug, Default)]
+struct RuntimeLive {
+    runtime_dir: Option<PathBuf>,
+    since: u64,
+    board: Option<crate::parallel::live::Board>,
+    selected: usize,
+    stage: Option<String>,
+    tail: Vec<String>,
+}
+
+const RUNTIME_TAIL_LINES: usize = 200;
+const RUNTIME_REFRESH: Duration = Duration::from_millis(500);
+
+impl RuntimeLive {
+    fn watch(project_path: &str) -> Self {
+        Self {
+            runtime_dir: Some(crate::parallel::live::runtime_dir(
+                &Path::new(project_path).join(".project"),
+            )),
+            since: crate::now_unix_secs(),
+            ..Self::default()
+        }
+    }
+
+    fn selected_task(&self) -> Option<&crate::parallel::live::TaskStatus> {
+        self.board.as_ref()?.tasks.get(self.selected)
+    }
+
+    // Ignores a board left over from an earlier run.
+    fn refresh(&mut self) {
+        let Some(dir) = self.runtime_dir.clone() else {
+            return;
+        };
+        self.board = crate::parallel::live::read_board(&dir).filter(|b| b.started_at >= self.since);
+        let count = self.board.as_ref().map_or(0, |b| b.tasks.len());
+        self.selected = self.selected.min(count.saturating_sub(1));
+        let Some(name) = self.selected_task().map(|t| t.name.clone()) else {
+            self.stage = None;
+            self.tail.clear();
+            return;
+        };
+        self.stage = crate::parallel::live::last_stage(&dir, &name);
+        self.tail = crate::parallel::live::tail(
+            &crate::parallel::live::out_log_path(&dir, &name),
+            RUNTIME_TAIL_LINES,
+        );
+    }
+
+    // Returns false at the list edges so the key can move pane focus instead.
+    fn move_selection(&mut self, delta: isize) -> bool {
+        let count = self.board.as_ref().map_or(0, |b| b.tasks.len());
+        let next = self.selected as isize + delta;
+        if count == 0 || next < 0 || next >= count as isize {
+            return false;
+        }
+        self.selected = next as usize;
+        self.refresh();
+        true
+    }
+}
+
 #[derive(Debug)]
 struct UiApp {
     tab_index: usize,
@@ -419,8 +475,10 @@ struct UiApp {
     pending_action: Option<PendingUiAction>,
     busy_message: Option<String>,
     parallel_build_rx: Option<Receiver<Result<String, String>>>,
+    runtime_live: RuntimeLive,
     menu_active: bool,
     changed: bool,
+    registry_base: ProjectRegistry,
     pane_activate_started_at: Option<Instant>,
     pane_activate_index: usize,
     detail_layout: DetailLayoutPreset,
@@ -626,7 +684,7 @@ fn binary_root() -> PathBuf {
 }
 
 fn ui_registry_path() -> PathBuf {
-    crate::source_root().join(UI_REGISTRY_PATH)
+    crate::registry_path()
 }
 
 fn reload_projects_from_registry(
@@ -638,12 +696,10 @@ fn reload_projects_from_registry(
     if !path.exists() {
         return Ok(());
     }
-    let raw = fs::read_to_string(&path)
-        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
-    let parsed: UiProjectRegistry = serde_yaml::from_str(&raw)
-        .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
-    *projects = parsed.projects;
-    *recent_active_pane = parsed.recent_active_pane;
+    let parsed = registry::load(&path)?;
+    *projects = parsed.projects.clone();
+    *recent_active_pane = parsed.recent_active_pane.clone();
+    app.registry_base = parsed;
     if !projects.is_empty() {
         app.project_index = pick_selected_project_index(projects);
         promote_recent_project_to_front(projects, recent_active_pane.as_deref());
@@ -658,19 +714,10 @@ fn reload_projects_from_registry(
 fn save_projects_to_registry(
     projects: &[ProjectRecord],
     recent_active_pane: &Option<String>,
+    base: &mut ProjectRegistry,
 ) -> Result<(), String> {
-    let path = ui_registry_path();
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
-    }
-    let doc = UiProjectRegistry {
-        recent_active_pane: recent_active_pane.clone(),
-        projects: projects.to_vec(),
-    };
-    let raw = serde_yaml::to_string(&doc)
-        .map_err(|e| format!("failed to encode {}: {}", path.display(), e))?;
-    fs::write(&path, raw).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    *base = registry::sync_projects(&ui_registry_path(), base, projects, recent_active_pane)?;
+    Ok(())
 }
 
 fn resolve_draft_template_path() -> Result<PathBuf, String> {
@@ -1119,6 +1166,13 @@ fn ui_model_bin() -> String {
     "codex".to_string()
 }
 
+fn ui_llm_timeout_sec() -> u64 {
+    crate::load_app_config()
+        .as_ref()
+        .map_or(300, crate::config::AppConfig::default_timeout_sec)
+        .max(30)
+}
+
 fn extract_markdown_block(raw: &str) -> Option<String> {
     if let Some(start) = raw.find("```markdown") {
         let rest = &raw[start + 11..];
@@ -1282,12 +1336,79 @@ fn start_build_parallel_via_cli_async(
         *state = TaskRuntimeState::Active;
     }
     app.parallel_build_rx = Some(rx);
+    app.runtime_live = RuntimeLive::watch(&project.path);
     app.parallel_running = true;
     app.last_tick = Instant::now();
     app.status_line = "parallel build started".to_string();
     Ok(())
 }
 
+// Task list (selected task marked), then the selected task's elapsed time,
+// last stage and as much of its output tail as fits.
+fn runtime_live_lines(
+    live: &RuntimeLive,
+    focused: bool,
+    max_w: u16,
+    height: usize,
+    palette: BorderPalette,
+) -> Vec<Line<'static>> {
+    let Some(board) = live.board.as_ref() else {
+        return Vec::new();
+    };
+    let now = crate::now_unix_secs();
+    let mut lines: Vec<Line> = board
+        .tasks
+        .iter()
+        .enumerate()
+        .map(|(idx, task)| {
+            let (status, color) = match task.state.as_str() {
+                "active" => ("작업중", palette.active),
+                "clear" => ("완료", palette.normal),
+                "failed" => ("실패", Color::Red),
+                _ => ("대기", palette.inactive),
+            };
+            let marker = if idx == live.selected { ">" } else { " " };
+            let mut style = Style::default().fg(color);
+            if idx == live.selected && focused {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            Line::from(Span::styled(
+                truncate_to_width_ellipsis(&format!("{} {} : {}", marker, task.name, status), max_w),
+                style,
+            ))
+        })
+        .collect();
+    let Some(task) = live.selected_task() else {
+        return lines;
+    };
+    lines.push(Line::from(Span::styled(
+        "─".repeat(max_w as usize),
+        Style::default().fg(palette.inactive),
+    )));
+    let elapsed = task
+        .elapsed_sec(now)
+        .map_or("-".to_string(), |sec| format!("{}s", sec));
+    lines.push(Line::from(Span::styled(
+        truncate_to_width_ellipsis(&format!("{} | {}", task.name, elapsed), max_w),
+        Style::default().fg(palette.active),
+    )));
+    if let Some(stage) = live.stage.as_deref() {
+        lines.push(Line::from(Span::styled(
+            truncate_to_width_ellipsis(stage, max_w),
+            Style::default().fg(palette.normal),
+        )));
+    }
+    let room = height.saturating_sub(lines.len());
+    let skip = live.tail.len().saturating_sub(room);
+    lines.extend(live.tail[skip..].iter().map(|line| {
+        Line::from(Span::styled(
+            truncate_to_width_ellipsis(line, max_w),
+            Style::default().fg(palette.inactive),
+        ))
+    }));
+    lines
+}
+
 fn has_planned_task_file(project: &ProjectRecord, feature_name: &str) -> bool {
     let feature_dir = Path::new(&project.path)
         .join(".project")
@@ -1791,26 +1912,18 @@ fn run_bootstrap_llm_prepare(
         .replace("{{spec}}", &spec)
         .replace("{{preset}}", preset);
     append_project_chat_log(&project.path, "LLM_PROMPT", &prompt);
-    let mut cmd = Command::new(&model_bin);
-    cmd.arg("exec");
-    if model_bin.eq_ignore_ascii_case("codex") {
-        cmd.arg("--dangerously-bypass-approvals-and-sandbox");
-    }
-    let output = cmd
-        .arg(prompt)
-        .current_dir(project_root)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
+    let backend = crate::llm::backend_for_model(&model_bin);
+    let request = crate::llm::LlmRequest::new(&prompt, ui_llm_timeout_sec()).in_dir(project_root);
+    let output = backend
+        .exec(&request)
         .map_err(|e| format!("failed to execute bootstrap llm prepare: {}", e))?;
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.success {
+        let stdout = output.stdout.trim().to_string();
         append_project_chat_log(&project.path, "LLM_RESPONSE", &stdout);
         Ok(())
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        append_project_chat_log(&project.path, "LLM_ERROR", &stderr);
-        Err(format!("bootstrap llm prepare failed: {}", stderr))
+        append_project_chat_log(&project.path, "LLM_ERROR", &output.stderr);
+        Err(format!("bootstrap llm prepare failed: {}", output.stderr))
     }
 }
 
@@ -2004,27 +2117,20 @@ fn build_ai_finalize_project_md_prompt(modal: &AiChatModal) -> String {
 fn finalize_project_md_from_chat(modal: &AiChatModal) -> Result<(), String> {
     let prompt = build_ai_finalize_project_md_prompt(modal);
     append_project_chat_log(&modal.project_path, "LLM_PROMPT", &prompt);
-    let mut cmd = Command::new(&modal.model_bin);
-    cmd.arg("exec");
-    if modal.model_bin.eq_ignore_ascii_case("codex") {
-        cmd.arg("--dangerously-bypass-approvals-and-sandbox");
-    }
-    let output = cmd
-        .arg(prompt)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
+    let backend = crate::llm::backend_for_model(&modal.model_bin);
+    let request = crate::llm::LlmRequest::new(&prompt, ui_llm_timeout_sec());
+    let output = backend
+        .exec(&request)
         .map_err(|e| format!("failed to execute finalize project.md llm: {}", e))?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        append_project_chat_log(&modal.project_path, "LLM_ERROR", &stderr);
+    if !output.success {
+        append_project_chat_log(&modal.project_path, "LLM_ERROR", &output.stderr);
         return Err(format!(
             "finalize project.md llm failed (code={:?}) {}",
-            output.status.code(),
-            stderr
+            output.code,
+            output.stderr
         ));
     }
-    let raw = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let raw = output.stdout.trim().to_string();
     append_project_chat_log(&modal.project_path, "LLM_RESPONSE_RAW", &raw);
     let md = extract_markdown_block(&raw)
         .ok_or_else(|| "finalize project.md: markdown body not found".to_string())?;
@@ -2412,13 +2518,26 @@ fn spawn_ai_stream(model_bin: &str, prompt: String) -> (Receiver<AiStreamEvent>,
     let cancel_flag = cancel.clone();
     let model = model_bin.to_string();
     thread::spawn(move || {
-        let mut cmd = Command::new(&model);
-        cmd.arg("exec");
-        if model.eq_ignore_ascii_case("codex") {
-            cmd.arg("--dangerously-bypass-approvals-and-sandbox");
-        }
+        let backend = crate::llm::backend_for_model(&model);
+        let request = crate::llm::LlmRequest::new(&prompt, ui_llm_timeout_sec());
+        let Some(mut cmd) = backend.command(&request) else {
+            let event = match backend.exec(&request) {
+                Ok(out) if out.success => {
+                    let _ = tx.send(AiStreamEvent::Chunk(out.stdout));
+                    AiStreamEvent::Done
+                }
+                Ok(out) => AiStreamEvent::Error(format!(
+                    "{} failed: code={:?} {}",
+                    backend.label(),
+                    out.code,
+                    out.stderr
+                )),
+                Err(e) => AiStreamEvent::Error(e),
+            };
+            let _ = tx.send(event);
+            return;
+        };
         let spawn_result = cmd
-            .arg(prompt)
             .stdout(Stdio::piped())
             .stderr(Stdio::null())
             .spawn();
@@ -2548,53 +2667,6 @@ fn close_ai_chat_modal_and_open_bootstrap(
     );
 }
 
-fn now_unix() -> String {
-    let secs = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-    secs.to_string()
-}
-
-fn generate_project_id(existing: &BTreeSet<String>) -> String {
-    const ALNUM: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
-    let mut seed = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos() as u64)
-        .unwrap_or(0);
-    for _ in 0..512 {
-        let mut out = String::with_capacity(4);
-        for _ in 0..4 {
-            seed = seed
-                .wrapping_mul(6364136223846793005)
-                .wrapping_add(1);
-            let idx = (seed as usize) % ALNUM.len();
-            out.push(ALNUM[idx] as char);
-        }
-        if !existing.contains(&out) {
-            return out;
-        }
-    }
-    "0000".to_string()
-}
-
-fn assign_missing_project_ids(projects: &mut [ProjectRecord]) -> bool {
-    let mut changed = false;
-    let mut existing: BTreeSet<String> = projects
-        .iter()
-        .filter_map(|p| if p.id.is_empty() { None } else { Some(p.id.clone()) })
-        .collect();
-    for project in projects {
-        if project.id.is_empty() {
-            let id = generate_project_id(&existing);
-            existing.insert(id.clone());
-            project.id = id;
-            changed = true;
-        }
-    }
-    changed
-}
-
 fn promote_recent_project_to_front(projects: &mut Vec<ProjectRecord>, recent_id: Option<&str>) {
     let Some(recent) = recent_id else {
         return;
@@ -2821,6 +2893,17 @@ fn move_project_grid_selection(projects: &mut [ProjectRecord], app: &mut UiApp,
     app.status_line = format!("selected project: {}", projects[app.project_index].name);
 }
 
+// While a build runs, Up/Down in the Drafts pane pick the task to follow.
+fn move_detail_selection(app: &mut UiApp, key: KeyCode) {
+    if app.menu_active && app.pane_focus == 5 && app.parallel_running {
+        let delta = if key == KeyCode::Up { -1 } else { 1 };
+        if app.runtime_live.move_selection(delta) {
+            return;
+        }
+    }
+    move_detail_pane_focus(app, key);
+}
+
 fn move_detail_pane_focus(app: &mut UiApp, key: KeyCode) {
     if !app.menu_active || app.tab_index != 1 {
         return;
@@ -3006,7 +3089,7 @@ fn apply_project_create(
     fs::create_dir_all(path.join(".project"))
         .map_err(|e| format!("failed to create project meta dir: {}", e))?;
 
-    let now = now_unix();
+    let now = crate::now_unix();
     let mut created_new = false;
     let selected_index = if let Some((idx, p)) = projects
         .iter_mut()
@@ -3028,7 +3111,7 @@ fn apply_project_create(
         app.status_line = format!("project updated: {}", name);
         idx
     } else {
-        let existing_ids: BTreeSet<String> = projects
+        let existing_ids: HashSet<String> = projects
             .iter()
             .filter_map(|p| if p.id.is_empty() { None } else { Some(p.id.clone()) })
             .collect();
@@ -3036,14 +3119,15 @@ fn apply_project_create(
             run_create_project_in_project_dir(&path, name, modal.description.trim())?;
         created_new = true;
         projects.push(ProjectRecord {
-            id: generate_project_id(&existing_ids),
+            id: registry::generate_project_id(&existing_ids),
             name: name.to_string(),
             path: path.display().to_string(),
             description: modal.description.trim().to_string(),
             created_at: now.clone(),
             updated_at: now,
             selected: false,
-            project_type: "code".to_string(),
+            project_type: ProjectType::Code,
+            state: None,
         });
         let _ = sync_project_md_files(&path)?;
         app.status_line = if create_project_msg.is_empty() {
@@ -3107,7 +3191,7 @@ fn try_submit_edit_project(
         return Ok(());
     }
 
-    let now = now_unix();
+    let now = crate::now_unix();
     {
         let target = &mut projects[source_index];
         target.name = name.to_string();
@@ -3168,7 +3252,7 @@ fn apply_path_change_confirm(
         }
     }
 
-    let now = now_unix();
+    let now = crate::now_unix();
     {
         let target = &mut projects[confirm.source_index];
         target.name = confirm.new_name;
@@ -3558,6 +3642,9 @@ fn render_details_tab(
     let generated = selected_project
         .map(collect_generated_draft_items_from_project)
         .unwrap_or_default();
+    let processes = selected_project
+        .map(|project| crate::supervisor::load_snapshot(Path::new(&project.path)))
+        .unwrap_or_default();
     let right_rows = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
@@ -3636,7 +3723,17 @@ fn render_details_tab(
         .title(draft_title)
         .borders(Borders::ALL)
         .border_style(draft_border_style);
-    if app.parallel_running && !app.parallel_statuses.is_empty() {
+    if app.parallel_running && app.runtime_live.board.is_some() {
+        let inner = draft_block.inner(draft_area);
+        let lines = runtime_live_lines(
+            &app.runtime_live,
+            draft_selected,
+            inner.width.saturating_sub(1).max(8),
+            inner.height as usize,
+            palette,
+        );
+        f.render_widget(Paragraph::new(lines).block(draft_block), draft_area);
+    } else if app.parallel_running && !app.parallel_statuses.is_empty() {
         let max_w = draft_area.width.saturating_sub(6).max(8);
         let lines: Vec<Line> = app
             .parallel_statuses
@@ -3646,12 +3743,14 @@ fn render_details_tab(
                     TaskRuntimeState::Inactive => "대기",
                     TaskRuntimeState::Active => "작업중",
                     TaskRuntimeState::Clear => "완료",
+                    TaskRuntimeState::Failed => "실패",
                 };
                 let raw = format!("{} : {}", task, status);
                 let color = match state {
                     TaskRuntimeState::Inactive => palette.inactive,
                     TaskRuntimeState::Active => palette.active,
                     TaskRuntimeState::Clear => palette.normal,
+                    TaskRuntimeState::Failed => Color::Red,
                 };
                 Line::from(Span::styled(
                     truncate_to_width_ellipsis(&raw, max_w),
@@ -3665,7 +3764,7 @@ fn render_details_tab(
                 .wrap(Wrap { trim: false }),
             draft_area,
         );
-    } else if generated.is_empty() {
+    } else if generated.is_empty() && processes.is_empty() {
         let inner = draft_block.inner(draft_area);
         f.render_widget(draft_block, draft_area);
         let body_area = Rect {
@@ -3683,7 +3782,7 @@ fn render_details_tab(
             body_area,
         );
     } else {
-        let draft_lines: Vec<Line> = generated
+        let mut draft_lines: Vec<Line> = generated
             .iter()
             .enumerate()
             .map(|(idx, item)| {
@@ -3693,6 +3792,30 @@ fn render_details_tab(
                 ))
             })
             .collect();
+        if !processes.is_empty() {
+            let max_w = draft_area.width.saturating_sub(6).max(8);
+            if !draft_lines.is_empty() {
+                draft_lines.push(Line::from(""));
+            }
+            draft_lines.push(Line::from(Span::styled(
+                "processes",
+                Style::default().fg(palette.inactive),
+            )));
+            draft_lines.extend(processes.iter().map(|process| {
+                let color = if process.status.is_alive() {
+                    palette.active
+                } else {
+                    palette.inactive
+                };
+                Line::from(Span::styled(
+                    truncate_to_width_ellipsis(
+                        &crate::supervisor::render_status_line(process),
+                        max_w,
+                    ),
+                    Style::default().fg(color),
+                ))
+            }));
+        }
         f.render_widget(
             Paragraph::new(draft_lines)
                 .block(draft_block)
@@ -3824,7 +3947,8 @@ mod tests {
             created_at: "0".to_string(),
             updated_at: "0".to_string(),
             selected: true,
-            project_type: "code".to_string(),
+            project_type: ProjectType::Code,
+            state: None,
         };
 
         let parsed = parse_project_md(&input.project_md);
@@ -3872,7 +3996,8 @@ mod tests {
             created_at: "0".to_string(),
             updated_at: "0".to_string(),
             selected: true,
-            project_type: "code".to_string(),
+            project_type: ProjectType::Code,
+            state: None,
         };
         let mut modal = new_ai_chat_modal_template(
             &project,
@@ -4041,6 +4166,43 @@ goal : 100번 점프 달성 시 승리
             "b: create_code_draft/enter-parallel".to_string()
         );
     }
+
+    #[test]
+    fn runtime_pane_follows_the_selected_task_tail() {
+        let task = |name: &str, state: &str| crate::parallel::live::TaskStatus {
+            name: name.to_string(),
+            state: state.to_string(),
+            started_at: Some(100),
+            finished_at: Some(130),
+        };
+        let mut live = RuntimeLive {
+            board: Some(crate::parallel::live::Board {
+                started_at: 100,
+                tasks: vec![task("login", "clear"), task("signup", "failed")],
+            }),
+            stage: Some("검증 단계 | check 1개".to_string()),
+            tail: (1..=10).map(|i| format!("out {}", i)).collect(),
+            ..RuntimeLive::default()
+        };
+        assert!(!live.move_selection(-1));
+        assert!(live.move_selection(1));
+        // Without a runtime dir, refresh keeps the injected detail.
+        assert!(!live.move_selection(1));
+        let palette = BorderPalette {
+            active: Color::Green,
+            normal: Color::White,
+            inactive: Color::Gray,
+        };
+        let text: Vec<String> = runtime_live_lines(&live, true, 40, 8, palette)
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.to_string()).collect())
+            .collect();
+        assert_eq!(text[0], "  login : 완료");
+        assert_eq!(text[1], "> signup : 실패");
+        assert_eq!(text[3], "signup | 30s");
+        assert_eq!(text[4], "검증 단계 | check 1개");
+        assert_eq!(text[5..], ["out 8", "out 9", "out 10"]);
+    }
 }
 
 fn collect_planned_drafts_from_project(project: &ProjectRecord) -> Vec<String> {
@@ -4844,12 +5006,15 @@ fn render_alarm_modal(f: &mut ratatui::Frame, area: Rect, modal: &AlarmModal) {
     component::render_alarm_modal(f, area, &modal.message);
 }
 
-pub fn run_ui(
-    projects: &mut Vec<ProjectRecord>,
-    recent_active_pane: &mut Option<String>,
-) -> Result<UiRunResult, String> {
+pub fn run_ui(registry: &mut ProjectRegistry) -> Result<UiRunResult, String> {
+    let registry_base = registry.clone();
+    let ProjectRegistry {
+        recent_active_pane,
+        projects,
+        ..
+    } = registry;
     let palette = load_border_palette();
-    let ids_changed = assign_missing_project_ids(projects);
+    let ids_changed = registry::assign_missing_ids(projects);
     promote_recent_project_to_front(projects, recent_active_pane.as_deref());
     let mut app = UiApp {
         tab_index: 0,
@@ -4872,8 +5037,10 @@ pub fn run_ui(
         pending_action: None,
         busy_message: None,
         parallel_build_rx: None,
+        runtime_live: RuntimeLive::default(),
         menu_active: true,
         changed: ids_changed,
+        registry_base,
         pane_activate_started_at: None,
         pane_activate_index: 0,
         detail_layout: layout_load("code"),
@@ -5117,13 +5284,27 @@ pub fn run_ui(
             continue;
         }
 
+        if app.parallel_build_rx.is_some() && app.last_tick.elapsed() >= RUNTIME_REFRESH {
+            app.runtime_live.refresh();
+            if let Some(board) = app.runtime_live.board.as_ref() {
+                app.parallel_statuses = board
+                    .tasks
+                    .iter()
+                    .map(|t| (t.name.clone(), t.runtime_state()))
+                    .collect();
+            }
+            app.last_tick = Instant::now();
+        }
+
         if let Some(rx) = app.parallel_build_rx.as_ref() {
             match rx.try_recv() {
                 Ok(Ok(msg)) => {
                     app.parallel_running = false;
                     app.parallel_build_rx = None;
                     for (_, state) in &mut app.parallel_statuses {
-                        *state = TaskRuntimeState::Clear;
+                        if *state != TaskRuntimeState::Failed {
+                            *state = TaskRuntimeState::Clear;
+                        }
                     }
                     app.status_line = msg;
                 }
@@ -5463,21 +5644,19 @@ pub fn run_ui(
                         modal.input_mode = Some(ListEditInputMode::Add);
                         modal.input.clear();
                     }
-                    KeyCode::Char('e') => {
-                        if !modal.items.is_empty() && modal.selected_index < modal.items.len() {
+                    KeyCode::Char('e')
+                        if !modal.items.is_empty() && modal.selected_index < modal.items.len() => {
                             modal.input_mode = Some(ListEditInputMode::Edit);
                             modal.input = modal.items[modal.selected_index].clone();
                         }
-                    }
-                    KeyCode::Char('d') => {
-                        if !modal.items.is_empty() && modal.selected_index < modal.items.len() {
+                    KeyCode::Char('d')
+                        if !modal.items.is_empty() && modal.selected_index < modal.items.len() => {
                             modal.items.remove(modal.selected_index);
                             if modal.selected_index > 0 && modal.selected_index >= modal.items.len() {
                                 modal.selected_index -= 1;
                             }
                             app.status_line = "list item deleted".to_string();
                         }
-                    }
                     KeyCode::Left | KeyCode::Right => {
                         modal.confirm_selected = !modal.confirm_selected;
                     }
@@ -5497,16 +5676,14 @@ pub fn run_ui(
                             app.status_line = "list modal canceled".to_string();
                         }
                     }
-                    KeyCode::Up => {
-                        if modal.selected_index > 0 {
+                    KeyCode::Up
+                        if modal.selected_index > 0 => {
                             modal.selected_index -= 1;
                         }
-                    }
-                    KeyCode::Down => {
-                        if modal.selected_index + 1 < modal.items.len() {
+                    KeyCode::Down
+                        if modal.selected_index + 1 < modal.items.len() => {
                             modal.selected_index += 1;
                         }
-                    }
                     _ => {}
                 }
                 continue;
@@ -5537,45 +5714,40 @@ pub fn run_ui(
             }
             if let Some(modal) = app.ai_chat_modal.as_mut() {
                 match key_event.code {
-                    KeyCode::Esc => {
-                        if modal.focus == AiDetailFocus::Input && modal.input_active && !modal.streaming {
+                    KeyCode::Esc
+                        if modal.focus == AiDetailFocus::Input && modal.input_active && !modal.streaming => {
                             modal.input_active = false;
                             modal.input_enter_streak = 0;
                             app.status_line = "ai input inactive".to_string();
                         }
-                    }
-                    KeyCode::Down => {
+                    KeyCode::Down
                         if !modal.streaming
                             && modal.focus == AiDetailFocus::Input
                             && !modal.input_active
-                        {
+                        => {
                             modal.focus = AiDetailFocus::CloseButton;
                         }
-                    }
-                    KeyCode::Up => {
-                        if !modal.streaming && modal.focus == AiDetailFocus::CloseButton {
+                    KeyCode::Up
+                        if !modal.streaming && modal.focus == AiDetailFocus::CloseButton => {
                             modal.focus = AiDetailFocus::Input;
                             modal.input_active = false;
                         }
-                    }
-                    KeyCode::Backspace => {
+                    KeyCode::Backspace
                         if !modal.streaming
                             && modal.focus == AiDetailFocus::Input
                             && modal.input_active
-                        {
+                        => {
                             modal.input.pop();
                             modal.input_enter_streak = 0;
                         }
-                    }
-                    KeyCode::Char(c) => {
+                    KeyCode::Char(c)
                         if !modal.streaming
                             && modal.focus == AiDetailFocus::Input
                             && modal.input_active
-                        {
+                        => {
                             modal.input.push(c);
                             modal.input_enter_streak = 0;
                         }
-                    }
                     KeyCode::Enter => {
                         if modal.streaming {
                             continue;
@@ -5917,7 +6089,7 @@ pub fn run_ui(
                         continue;
                     }
                     app.tab_index = (app.tab_index + 1) % 2;
-                    if let Err(e) = save_projects_to_registry(projects, recent_active_pane) {
+                    if let Err(e) = save_projects_to_registry(projects, recent_active_pane, &mut app.registry_base) {
                         app.status_line = e;
                         continue;
                     }
@@ -5933,7 +6105,7 @@ pub fn run_ui(
                 }
                 KeyCode::Char('1') if app.menu_active => {
                     app.tab_index = 0;
-                    if let Err(e) = save_projects_to_registry(projects, recent_active_pane) {
+                    if let Err(e) = save_projects_to_registry(projects, recent_active_pane, &mut app.registry_base) {
                         app.status_line = e;
                         continue;
                     }
@@ -5947,7 +6119,7 @@ pub fn run_ui(
                 }
                 KeyCode::Char('2') if app.menu_active => {
                     app.tab_index = 1;
-                    if let Err(e) = save_projects_to_registry(projects, recent_active_pane) {
+                    if let Err(e) = save_projects_to_registry(projects, recent_active_pane, &mut app.registry_base) {
                         app.status_line = e;
                         continue;
                     }
@@ -5975,8 +6147,8 @@ pub fn run_ui(
                 }
                 KeyCode::Left if app.tab_index == 1 => move_detail_pane_focus(&mut app, KeyCode::Left),
                 KeyCode::Right if app.tab_index == 1 => move_detail_pane_focus(&mut app, KeyCode::Right),
-                KeyCode::Up if app.tab_index == 1 => move_detail_pane_focus(&mut app, KeyCode::Up),
-                KeyCode::Down if app.tab_index == 1 => move_detail_pane_focus(&mut app, KeyCode::Down),
+                KeyCode::Up if app.tab_index == 1 => move_detail_selection(&mut app, KeyCode::Up),
+                KeyCode::Down if app.tab_index == 1 => move_detail_selection(&mut app, KeyCode::Down),
                 _ => {}
             }
         }
@@ -5996,6 +6168,9 @@ pub fn run_ui(
     raw_off_result?;
     cursor_result?;
 
+    if run_result.is_ok() && app.changed {
+        save_projects_to_registry(projects, recent_active_pane, &mut app.registry_base)?;
+    }
     if run_result.is_ok() {
         run_result = Ok(UiRunResult {
             changed: app.changed,
@@ -0,0 +1,342 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::test_runner::TestStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CheckStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ConstraintResult {
+    pub(crate) constraint: String,
+    pub(crate) kind: String,
+    pub(crate) status: CheckStatus,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Check {
+    Command(String),
+    FileExists(String),
+    Symbol { name: String, path: Option<String> },
+    Test(String),
+}
+
+impl Check {
+    fn kind(&self) -> &'static str {
+        match self {
+            Check::Command(_) => "command",
+            Check::FileExists(_) => "file",
+            Check::Symbol { .. } => "symbol",
+            Check::Test(_) => "test",
+        }
+    }
+}
+
+fn unquote(raw: &str) -> &str {
+    raw.trim()
+        .trim_matches(|c| c == '`' || c == '"' || c == '\'')
+        .trim()
+}
+
+pub(crate) fn parse_check(raw: &str) -> Option<Check> {
+    let s = unquote(raw);
+    if s.is_empty() {
+        return None;
+    }
+    if let Some(inner) = s
+        .strip_prefix("exists(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return Some(Check::FileExists(unquote(inner).to_string()));
+    }
+    let split = s
+        .split_once(':')
+        .filter(|(key, _)| !key.contains(' '))
+        .or_else(|| s.split_once('=').filter(|(key, _)| !key.contains(' ')));
+    if let Some((key, value)) = split {
+        let value = unquote(value);
+        if value.is_empty() {
+            return None;
+        }
+        match key.trim().to_ascii_lowercase().as_str() {
+            "command" | "cmd" | "run" => return Some(Check::Command(value.to_string())),
+            "file" | "exists" | "file_exists" => return Some(Check::FileExists(value.to_string())),
+            "symbol" => {
+                let (name, path) = match value.split_once(" in ") {
+                    Some((name, path)) => (unquote(name), Some(unquote(path).to_string())),
+                    None => (value, None),
+                };
+                return Some(Check::Symbol {
+                    name: name.to_string(),
+                    path,
+                });
+            }
+            "test" => return Some(Check::Test(value.to_string())),
+            _ => {}
+        }
+    }
+    // Free text such as "make sure errors are logged" is a note, not a
+    // command; only `$ <cmd>` or a fully backticked body is run.
+    let raw = raw.trim();
+    if let Some(command) = raw.strip_prefix("$ ").map(str::trim) {
+        return (!command.is_empty()).then(|| Check::Command(command.to_string()));
+    }
+    let backticked = raw.len() > 2 && raw.starts_with('`') && raw.ends_with('`');
+    if backticked && !raw[1..raw.len() - 1].contains('`') {
+        return Some(Check::Command(s.to_string()));
+    }
+    None
+}
+
+fn run_shell(dir: &Path, command: &str, timeout_sec: u64) -> Result<(Option<i32>, String), String> {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command).current_dir(dir);
+    crate::test_runner::run_captured(cmd, timeout_sec)
+}
+
+fn has_symbol(text: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(name).any(|(idx, _)| {
+        let before = text[..idx].chars().next_back();
+        let after = text[idx + name.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+fn find_symbol(root: &Path, name: &str) -> Option<String> {
+    if root.is_file() {
+        let text = fs::read_to_string(root).ok()?;
+        return has_symbol(&text, name).then(|| root.display().to_string());
+    }
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let dir_name = entry.file_name();
+                if !crate::SCAN_SKIP_DIRS.iter().any(|skip| dir_name == *skip) {
+                    stack.push(path);
+                }
+            } else if file_type.is_file()
+                && fs::read_to_string(&path).is_ok_and(|text| has_symbol(&text, name))
+            {
+                return Some(path.display().to_string());
+            }
+        }
+    }
+    None
+}
+
+fn run_check(dir: &Path, check: &Check, timeout_sec: u64) -> (CheckStatus, String) {
+    match check {
+        Check::Command(command) => match run_shell(dir, command, timeout_sec) {
+            Ok((Some(0), _)) => (CheckStatus::Pass, String::new()),
+            Ok((code, output)) => (
+                CheckStatus::Fail,
+                format!(
+                    "exit {:?}: {}",
+                    code,
+                    crate::test_runner::last_line(&output)
+                ),
+            ),
+            Err(e) => (CheckStatus::Fail, e),
+        },
+        Check::FileExists(path) => {
+            if dir.join(path).exists() {
+                (CheckStatus::Pass, String::new())
+            } else {
+                (CheckStatus::Fail, format!("{} not found", path))
+            }
+        }
+        Check::Symbol { name, path } => {
+            let root = path.as_ref().map_or(dir.to_path_buf(), |p| dir.join(p));
+            match find_symbol(&root, name) {
+                Some(found) => (CheckStatus::Pass, found),
+                None => (
+                    CheckStatus::Fail,
+                    format!("{} not found in {}", name, root.display()),
+                ),
+            }
+        }
+        Check::Test(name) => {
+            let targets = crate::test_runner::detect_targets(dir);
+            if targets.is_empty() {
+                return (
+                    CheckStatus::Skip,
+                    "no test runner detected for this project".to_string(),
+                );
+            }
+            let mut misses = Vec::new();
+            for target in &targets {
+                let run = crate::test_runner::run_target(target, Some(name), timeout_sec);
+                match run.status {
+                    TestStatus::Passed
+                        if !crate::test_runner::ran_no_tests(target.spec, &run.output) =>
+                    {
+                        return (CheckStatus::Pass, run.command);
+                    }
+                    TestStatus::Passed => {
+                        misses.push(format!("no test matched in {}", run.command))
+                    }
+                    _ => return (CheckStatus::Fail, run.summary()),
+                }
+            }
+            (CheckStatus::Fail, misses.join(" | "))
+        }
+    }
+}
+
+pub(crate) fn run_checks(
+    dir: &Path,
+    constraints: &[String],
+    timeout_sec: u64,
+) -> Vec<ConstraintResult> {
+    let mut out: Vec<ConstraintResult> = Vec::new();
+    for raw in constraints {
+        if raw.trim().is_empty() || out.iter().any(|r| &r.constraint == raw) {
+            continue;
+        }
+        let result = match parse_check(raw) {
+            Some(check) => {
+                let (status, detail) = run_check(dir, &check, timeout_sec);
+                ConstraintResult {
+                    constraint: raw.clone(),
+                    kind: check.kind().to_string(),
+                    status,
+                    detail,
+                }
+            }
+            None => ConstraintResult {
+                constraint: raw.clone(),
+                kind: "manual".to_string(),
+                status: CheckStatus::Skip,
+                detail: "not machine-checkable".to_string(),
+            },
+        };
+        out.push(result);
+    }
+    out
+}
+
+pub(crate) fn failures(results: &[ConstraintResult]) -> Vec<&ConstraintResult> {
+    results
+        .iter()
+        .filter(|r| r.status == CheckStatus::Fail)
+        .collect()
+}
+
+pub(crate) fn summary(results: &[ConstraintResult]) -> String {
+    let count = |status: CheckStatus| results.iter().filter(|r| r.status == status).count();
+    format!(
+        "checks pass={} fail={} skip={}",
+        count(CheckStatus::Pass),
+        count(CheckStatus::Fail),
+        count(CheckStatus::Skip)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_check_recognizes_executable_forms() {
+        assert_eq!(
+            parse_check("command: cargo build"),
+            Some(Check::Command("cargo build".to_string()))
+        );
+        assert_eq!(
+            parse_check("`cargo test`"),
+            Some(Check::Command("cargo test".to_string()))
+        );
+        assert_eq!(
+            parse_check("$ make check"),
+            Some(Check::Command("make check".to_string()))
+        );
+        assert_eq!(parse_check("\"cargo test\""), None);
+        assert_eq!(parse_check("make sure errors are logged"), None);
+        assert_eq!(parse_check("go through every route"), None);
+        assert_eq!(parse_check("python 3.11 only"), None);
+        assert_eq!(
+            parse_check("exists(src/main.rs)"),
+            Some(Check::FileExists("src/main.rs".to_string()))
+        );
+        assert_eq!(
+            parse_check("file=README.md"),
+            Some(Check::FileExists("README.md".to_string()))
+        );
+        assert_eq!(
+            parse_check("symbol: greet in src/lib.rs"),
+            Some(Check::Symbol {
+                name: "greet".to_string(),
+                path: Some("src/lib.rs".to_string())
+            })
+        );
+        assert_eq!(
+            parse_check("test: greets_user"),
+            Some(Check::Test("greets_user".to_string()))
+        );
+        assert_eq!(parse_check("input=name"), None);
+        assert_eq!(parse_check("name -> greeting : print greeting"), None);
+    }
+
+    #[test]
+    fn run_checks_reports_pass_fail_and_skip() {
+        let dir = std::env::temp_dir().join(format!(
+            "orc_verify_{}_{}",
+            std::process::id(),
+            crate::now_unix()
+        ));
+        fs::create_dir_all(dir.join("src")).expect("create dir");
+        fs::write(dir.join("src").join("lib.rs"), "pub fn greet_user() {}\n").expect("write lib");
+        let constraints: Vec<String> = [
+            "exists(src/lib.rs)",
+            "symbol: greet_user in src",
+            "symbol: greet",
+            "command: exit 3",
+            "output == \"hello\"",
+            "prints a greeting",
+            "make sure errors are logged",
+            "$ head -c 100000 /dev/zero | tr '\\0' a",
+        ]
+        .iter()
+        .map(|v| v.to_string())
+        .collect();
+        let results = run_checks(&dir, &constraints, 10);
+        let statuses: Vec<CheckStatus> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                CheckStatus::Pass,
+                CheckStatus::Pass,
+                CheckStatus::Fail,
+                CheckStatus::Fail,
+                CheckStatus::Skip,
+                CheckStatus::Skip,
+                CheckStatus::Skip,
+                CheckStatus::Pass
+            ]
+        );
+        assert!(results[3].detail.starts_with("exit Some(3)"));
+        assert_eq!(results[4].detail, "not machine-checkable");
+        assert_eq!(results[6].kind, "manual");
+        assert_eq!(failures(&results).len(), 2);
+        assert_eq!(summary(&results), "checks pass=3 fail=2 skip=3");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,201 @@
+use axum::response::sse::Event;
+use futures_core::Stream;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::{broadcast, mpsc};
+
+use super::jobs::ActionJob;
+use super::ProjectState;
+use crate::supervisor::ProcessInfo;
+use crate::ui::TaskRuntimeState;
+
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(super) enum ApiEvent {
+    RuntimeLog {
+        id: String,
+        line: String,
+    },
+    ProjectState {
+        id: String,
+        state: ProjectState,
+    },
+    TaskStatus {
+        id: String,
+        task: String,
+        state: &'static str,
+    },
+    Job {
+        id: String,
+        job: ActionJob,
+    },
+    Process {
+        id: String,
+        process: ProcessInfo,
+    },
+    Lagged {
+        skipped: u64,
+    },
+}
+
+impl ApiEvent {
+    fn project_id(&self) -> Option<&str> {
+        match self {
+            ApiEvent::RuntimeLog { id, .. }
+            | ApiEvent::ProjectState { id, .. }
+            | ApiEvent::TaskStatus { id, .. }
+            | ApiEvent::Job { id, .. }
+            | ApiEvent::Process { id, .. } => Some(id),
+            ApiEvent::Lagged { .. } => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ApiEvent::RuntimeLog { .. } => "runtime_log",
+            ApiEvent::ProjectState { .. } => "project_state",
+            ApiEvent::TaskStatus { .. } => "task_status",
+            ApiEvent::Job { .. } => "job",
+            ApiEvent::Process { .. } => "process",
+            ApiEvent::Lagged { .. } => "lagged",
+        }
+    }
+
+    pub(super) fn task_status(id: &str, task: &str, state: TaskRuntimeState) -> Self {
+        ApiEvent::TaskStatus {
+            id: id.to_string(),
+            task: task.to_string(),
+            state: match state {
+                TaskRuntimeState::Inactive => "inactive",
+                TaskRuntimeState::Active => "active",
+                TaskRuntimeState::Clear => "clear",
+                TaskRuntimeState::Failed => "failed",
+            },
+        }
+    }
+}
+
+pub(super) fn channel() -> broadcast::Sender<ApiEvent> {
+    broadcast::channel(EVENT_CHANNEL_CAPACITY).0
+}
+
+pub(super) struct EventStream {
+    rx: mpsc::Receiver<Event>,
+}
+
+impl Stream for EventStream {
+    type Item = Result<Event, Infallible>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx).map(|event| event.map(Ok))
+    }
+}
+
+fn to_sse(event: &ApiEvent) -> Event {
+    Event::default()
+        .event(event.name())
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().event(event.name()))
+}
+
+fn is_wanted(filter: Option<&str>, event: &ApiEvent) -> bool {
+    match (filter, event.project_id()) {
+        (Some(filter), Some(id)) => filter == id,
+        _ => true,
+    }
+}
+
+pub(super) fn subscribe(
+    events: &broadcast::Sender<ApiEvent>,
+    project_id: Option<String>,
+) -> EventStream {
+    let mut source = events.subscribe();
+    let (tx, rx) = mpsc::channel(64);
+    tokio::spawn(async move {
+        loop {
+            let event = match source.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => ApiEvent::Lagged { skipped },
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if is_wanted(project_id.as_deref(), &event) && tx.send(to_sse(&event)).await.is_err() {
+                break;
+            }
+        }
+    });
+    EventStream { rx }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::poll_fn;
+
+    #[test]
+    fn subscribe_filters_by_project_and_serializes_typed_events() {
+        let runtime = tokio::runtime::Runtime::new().expect("runtime");
+        runtime.block_on(async {
+            let events = channel();
+            let mut stream = subscribe(&events, Some("p1".to_string()));
+            tokio::task::yield_now().await;
+            events
+                .send(ApiEvent::RuntimeLog {
+                    id: "p2".to_string(),
+                    line: "other".to_string(),
+                })
+                .expect("send");
+            events
+                .send(ApiEvent::task_status(
+                    "p1",
+                    "login",
+                    TaskRuntimeState::Active,
+                ))
+                .expect("send");
+            events
+                .send(ApiEvent::ProjectState {
+                    id: "p1".to_string(),
+                    state: ProjectState::Work,
+                })
+                .expect("send");
+            let mut received = Vec::new();
+            for _ in 0..2 {
+                let next = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await;
+                received.push(next.expect("event").expect("infallible"));
+            }
+            assert_eq!(received.len(), 2);
+            assert!(stream.rx.try_recv().is_err());
+        });
+        assert!(!is_wanted(
+            Some("p1"),
+            &ApiEvent::RuntimeLog {
+                id: "p2".to_string(),
+                line: String::new(),
+            }
+        ));
+        assert!(is_wanted(Some("p1"), &ApiEvent::Lagged { skipped: 3 }));
+
+        let task = serde_json::to_value(ApiEvent::task_status(
+            "p1",
+            "login",
+            TaskRuntimeState::Clear,
+        ))
+        .expect("json");
+        assert_eq!(
+            task,
+            serde_json::json!({"type": "task_status", "id": "p1", "task": "login", "state": "clear"})
+        );
+        let state = serde_json::to_value(ApiEvent::ProjectState {
+            id: "p1".to_string(),
+            state: ProjectState::Run,
+        })
+        .expect("json");
+        assert_eq!(
+            state,
+            serde_json::json!({"type": "project_state", "id": "p1", "state": "run"})
+        );
+    }
+}
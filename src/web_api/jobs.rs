@@ -0,0 +1,220 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::AbortHandle;
+
+use crate::process_group::ProcessSet;
+
+const MAX_FINISHED_JOBS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum ActionJobStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct ActionJob {
+    pub(super) id: String,
+    pub(super) project_id: String,
+    pub(super) action: String,
+    pub(super) status: ActionJobStatus,
+    pub(super) output: String,
+    pub(super) started_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) finished_at: Option<u64>,
+}
+
+struct JobEntry {
+    job: ActionJob,
+    abort: Option<AbortHandle>,
+    processes: Option<Arc<ProcessSet>>,
+}
+
+#[derive(Default)]
+pub(super) struct ActionJobs {
+    next: AtomicU64,
+    entries: Mutex<HashMap<String, JobEntry>>,
+}
+
+impl ActionJobs {
+    pub(super) fn start(&self, project_id: &str, action: &str) -> Result<ActionJob, String> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| "failed to lock jobs".to_string())?;
+        if let Some(running) = entries
+            .values()
+            .find(|e| e.job.project_id == project_id && e.job.status == ActionJobStatus::Running)
+        {
+            return Err(format!(
+                "project busy: job {} ({}) is running",
+                running.job.id, running.job.action
+            ));
+        }
+        if let Some(stopping) = entries.values().find(|e| {
+            e.job.project_id == project_id && e.processes.as_ref().is_some_and(|p| p.has_blocking())
+        }) {
+            return Err(format!(
+                "project busy: cancelled job {} ({}) is still stopping",
+                stopping.job.id, stopping.job.action
+            ));
+        }
+        let started_at = crate::now_unix_secs();
+        let seq = self.next.fetch_add(1, Ordering::Relaxed) + 1;
+        let job = ActionJob {
+            id: format!("{}-{}-{}", action, started_at, seq),
+            project_id: project_id.to_string(),
+            action: action.to_string(),
+            status: ActionJobStatus::Running,
+            output: String::new(),
+            started_at,
+            finished_at: None,
+        };
+        entries.insert(
+            job.id.clone(),
+            JobEntry {
+                job: job.clone(),
+                abort: None,
+                processes: None,
+            },
+        );
+        prune_finished(&mut entries);
+        Ok(job)
+    }
+
+    pub(super) fn attach(&self, id: &str, abort: AbortHandle, processes: Arc<ProcessSet>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if let Some(entry) = entries.get_mut(id) {
+                entry.abort = Some(abort);
+                entry.processes = Some(processes);
+            }
+        }
+    }
+
+    pub(super) fn finish(&self, id: &str, result: &Result<String, String>) -> Option<ActionJob> {
+        let mut entries = self.entries.lock().ok()?;
+        let entry = entries.get_mut(id)?;
+        if entry.job.status != ActionJobStatus::Running {
+            return None;
+        }
+        let (status, output) = match result {
+            Ok(output) => (ActionJobStatus::Succeeded, output.clone()),
+            Err(e) => (ActionJobStatus::Failed, e.clone()),
+        };
+        entry.job.status = status;
+        entry.job.output = output;
+        entry.job.finished_at = Some(crate::now_unix_secs());
+        entry.abort = None;
+        entry.processes = None;
+        Some(entry.job.clone())
+    }
+
+    pub(super) fn cancel(&self, id: &str) -> Result<(ActionJob, Option<Arc<ProcessSet>>), String> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| "failed to lock jobs".to_string())?;
+        let entry = entries
+            .get_mut(id)
+            .ok_or_else(|| format!("job not found: {}", id))?;
+        if entry.job.status != ActionJobStatus::Running {
+            return Err(format!("job {} is not running", id));
+        }
+        if let Some(abort) = entry.abort.take() {
+            abort.abort();
+        }
+        entry.job.status = ActionJobStatus::Cancelled;
+        entry.job.output = "cancelled".to_string();
+        entry.job.finished_at = Some(crate::now_unix_secs());
+        // Kept so `start` can wait for blocking work the abort left running.
+        Ok((entry.job.clone(), entry.processes.clone()))
+    }
+
+    pub(super) fn get(&self, id: &str) -> Option<ActionJob> {
+        let entries = self.entries.lock().ok()?;
+        entries.get(id).map(|e| e.job.clone())
+    }
+
+    pub(super) fn list(&self, project_id: Option<&str>) -> Vec<ActionJob> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let mut out: Vec<ActionJob> = entries
+            .values()
+            .filter(|e| project_id.is_none_or(|id| e.job.project_id == id))
+            .map(|e| e.job.clone())
+            .collect();
+        out.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+        out
+    }
+}
+
+fn prune_finished(entries: &mut HashMap<String, JobEntry>) {
+    let mut finished: Vec<(u64, String)> = entries
+        .values()
+        .filter(|e| e.job.status != ActionJobStatus::Running)
+        .map(|e| (e.job.finished_at.unwrap_or(0), e.job.id.clone()))
+        .collect();
+    if finished.len() <= MAX_FINISHED_JOBS {
+        return;
+    }
+    finished.sort();
+    let overflow = finished.len() - MAX_FINISHED_JOBS;
+    for (_, id) in finished.into_iter().take(overflow) {
+        entries.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jobs_track_busy_projects_and_ignore_results_after_cancel() {
+        let jobs = ActionJobs::default();
+        let first = jobs.start("p1", "create_draft").expect("start");
+        assert!(jobs
+            .start("p1", "check_code")
+            .unwrap_err()
+            .contains("project busy"));
+        let other = jobs.start("p2", "check_code").expect("other project");
+
+        let processes = Arc::new(ProcessSet::default());
+        let handle = tokio::runtime::Runtime::new()
+            .expect("runtime")
+            .spawn(async {})
+            .abort_handle();
+        jobs.attach(&first.id, handle, processes.clone());
+        let blocking = processes.enter_blocking();
+        let (cancelled, attached) = jobs.cancel(&first.id).expect("cancel");
+        assert_eq!(cancelled.status, ActionJobStatus::Cancelled);
+        assert!(attached.is_some_and(|set| Arc::ptr_eq(&set, &processes)));
+        assert!(jobs.finish(&first.id, &Ok("late".to_string())).is_none());
+        assert_eq!(
+            jobs.get(&first.id).map(|j| j.status),
+            Some(ActionJobStatus::Cancelled)
+        );
+        assert!(jobs.cancel(&first.id).is_err());
+
+        let failed = jobs
+            .finish(&other.id, &Err("boom".to_string()))
+            .expect("finish");
+        assert_eq!(failed.status, ActionJobStatus::Failed);
+        assert_eq!(failed.output, "boom");
+
+        assert!(jobs
+            .start("p1", "add_draft")
+            .unwrap_err()
+            .contains("still stopping"));
+        drop(blocking);
+        let next = jobs.start("p1", "add_draft").expect("project free again");
+        assert_eq!(jobs.list(Some("p1")).len(), 2);
+        assert_eq!(jobs.list(None).len(), 3);
+        assert_ne!(next.id, first.id);
+    }
+}
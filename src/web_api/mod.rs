@@ -1,70 +1,36 @@
 use axum::extract::{Query, State};
 use axum::http::{Method, StatusCode};
+use axum::response::sse::{KeepAlive, Sse};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
 use std::sync::Mutex;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::net::TcpListener;
 use tower_http::cors::{Any, CorsLayer};
 
+mod events;
+mod jobs;
+
+use crate::context::{self, ProjectContext};
+use crate::registry::{self, ProjectRecord, ProjectRegistry, ProjectState, ProjectType};
+use crate::supervisor::{ProcessInfo, Supervisor};
+use events::ApiEvent;
+use jobs::ActionJobs;
+
 #[derive(Clone)]
 struct AppState {
     repo_root: PathBuf,
     runtime_logs: Arc<Mutex<HashMap<String, Vec<String>>>>,
-    running_dev: Arc<Mutex<HashSet<String>>>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
-#[serde(rename_all = "snake_case")]
-enum ProjectType {
-    Story,
-    Movie,
-    #[default]
-    Code,
-    Mono,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ProjectRecord {
-    #[serde(default)]
-    id: String,
-    name: String,
-    path: String,
-    description: String,
-    created_at: String,
-    updated_at: String,
-    selected: bool,
-    #[serde(default)]
-    project_type: ProjectType,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    state: Option<ProjectState>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-struct ProjectRegistry {
-    #[serde(default, rename = "recentActivepane")]
-    recent_active_pane: String,
-    #[serde(default)]
-    projects: Vec<ProjectRecord>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-enum ProjectState {
-    Init,
-    Basic,
-    Work,
-    Wait,
-    Run,
+    supervisor: Arc<Supervisor>,
+    events: tokio::sync::broadcast::Sender<ApiEvent>,
+    jobs: Arc<ActionJobs>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -209,11 +175,28 @@ struct RunRequest {
     action: String,
     #[serde(default)]
     payload: String,
+    #[serde(default)]
+    detach: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobsQuery {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    project: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JobCancelRequest {
+    id: String,
 }
 
 #[derive(Debug, Deserialize)]
 struct RunDevRequest {
     id: String,
+    #[serde(default)]
+    name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -221,6 +204,25 @@ struct RuntimeLogQuery {
     id: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ProcessesQuery {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProcessLogQuery {
+    id: String,
+    name: String,
+    #[serde(default)]
+    lines: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    #[serde(default)]
+    id: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct BrowseQuery {
     #[serde(default)]
@@ -247,10 +249,16 @@ type SyncMonorepoProjectsResult = (String, Vec<String>, Vec<MonorepoPackageEntry
 
 pub(crate) async fn serve_web_api(addr: &str) -> Result<String, String> {
     let repo_root = std::env::current_dir().map_err(|e| format!("failed to get cwd: {}", e))?;
+    let _ = clear_stale_run_states(&repo_root);
+    let runtime_logs = Arc::new(Mutex::new(HashMap::new()));
+    let events = events::channel();
+    let supervisor = Arc::new(build_supervisor(&repo_root, &runtime_logs, &events));
     let state = Arc::new(AppState {
         repo_root,
-        runtime_logs: Arc::new(Mutex::new(HashMap::new())),
-        running_dev: Arc::new(Mutex::new(HashSet::new())),
+        runtime_logs,
+        supervisor,
+        events,
+        jobs: Arc::new(ActionJobs::default()),
     });
     let router = Router::new()
         .route("/api/projects", get(get_projects).post(post_projects))
@@ -264,8 +272,15 @@ pub(crate) async fn serve_web_api(addr: &str) -> Result<String, String> {
         .route("/api/project-lists", post(post_project_lists))
         .route("/api/project-memo", post(post_project_memo))
         .route("/api/run", post(post_run))
+        .route("/api/jobs", get(get_jobs))
+        .route("/api/run/cancel", post(post_run_cancel))
         .route("/api/run-dev", post(post_run_dev))
+        .route("/api/run-dev/stop", post(post_run_dev_stop))
+        .route("/api/run-dev/restart", post(post_run_dev_restart))
+        .route("/api/processes", get(get_processes))
+        .route("/api/process-log", get(get_process_log))
         .route("/api/runtime-log", get(get_runtime_log))
+        .route("/api/events", get(get_events))
         .route("/api/tui-map", get(get_tui_map))
         .layer(
             CorsLayer::new()
@@ -423,18 +438,112 @@ async fn post_project_memo(
 }
 
 async fn post_run(State(state): State<Arc<AppState>>, Json(body): Json<RunRequest>) -> impl IntoResponse {
-    match run_orc_action(&state.repo_root, &body.id, &body.action, &body.payload).await {
-        Ok(output) => ok_json(json!({ "output": output })),
-        Err(e) => err_json(e),
+    let (job, handle) = match start_action_job(&state, body.id, body.action, body.payload) {
+        Ok(started) => started,
+        Err(e) => return err_json(e),
+    };
+    if body.detach {
+        return ok_json(json!({ "job": job }));
+    }
+    match handle.await {
+        Ok(Ok(output)) => ok_json(json!({ "output": output, "job_id": job.id })),
+        Ok(Err(e)) => err_json(e),
+        Err(_) => err_json(format!("job cancelled: {}", job.id)),
     }
 }
 
+async fn get_jobs(State(state): State<Arc<AppState>>, Query(query): Query<JobsQuery>) -> impl IntoResponse {
+    match query.id.filter(|id| !id.trim().is_empty()) {
+        Some(id) => match state.jobs.get(&id) {
+            Some(job) => ok_json(json!({ "job": job })),
+            None => err_json(format!("job not found: {}", id)),
+        },
+        None => ok_json(json!({ "jobs": state.jobs.list(query.project.as_deref()) })),
+    }
+}
+
+async fn post_run_cancel(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<JobCancelRequest>,
+) -> impl IntoResponse {
+    let (job, processes) = match state.jobs.cancel(&body.id) {
+        Ok(cancelled) => cancelled,
+        Err(e) => return err_json(e),
+    };
+    let killed = match processes {
+        Some(set) => tokio::task::spawn_blocking(move || set.kill_all())
+            .await
+            .unwrap_or(0),
+        None => 0,
+    };
+    publish_job(&state, &job);
+    publish_project_state(&state, &job.project_id);
+    ok_json(json!({ "job": job, "killed_processes": killed }))
+}
+
 async fn post_run_dev(
     State(state): State<Arc<AppState>>,
     Json(body): Json<RunDevRequest>,
 ) -> impl IntoResponse {
-    match start_bun_dev(state, &body.id).await {
-        Ok(output) => ok_json(json!({ "output": output })),
+    match start_dev_processes(state, &body.id, body.name).await {
+        Ok(output) => ok_json(output),
+        Err(e) => err_json(e),
+    }
+}
+
+async fn post_run_dev_stop(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<RunDevRequest>,
+) -> impl IntoResponse {
+    match stop_dev_processes(state, &body.id, body.name).await {
+        Ok(output) => ok_json(output),
+        Err(e) => err_json(e),
+    }
+}
+
+async fn post_run_dev_restart(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<RunDevRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = stop_dev_processes(state.clone(), &body.id, body.name.clone()).await {
+        return err_json(e);
+    }
+    match start_dev_processes(state, &body.id, body.name).await {
+        Ok(output) => ok_json(output),
+        Err(e) => err_json(e),
+    }
+}
+
+async fn get_processes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ProcessesQuery>,
+) -> impl IntoResponse {
+    let detail = match load_project_detail(&state.repo_root, &query.id) {
+        Ok(detail) => detail,
+        Err(e) => return err_json(e),
+    };
+    let (specs, source) = match crate::supervisor::load_specs(Path::new(&detail.path)) {
+        Ok(found) => found,
+        Err(e) => return err_json(e),
+    };
+    ok_json(json!({
+        "processes": state.supervisor.list(&query.id),
+        "specs": specs,
+        "source": source,
+    }))
+}
+
+async fn get_process_log(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ProcessLogQuery>,
+) -> impl IntoResponse {
+    let detail = match load_project_detail(&state.repo_root, &query.id) {
+        Ok(detail) => detail,
+        Err(e) => return err_json(e),
+    };
+    let lines = query.lines.unwrap_or(200).clamp(1, 5000);
+    match crate::supervisor::tail_log(Path::new(&detail.path), &query.name, lines) {
+        Ok(logs) => ok_json(json!({ "logs": logs })),
         Err(e) => err_json(e),
     }
 }
@@ -449,6 +558,14 @@ async fn get_runtime_log(
     }
 }
 
+async fn get_events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EventsQuery>,
+) -> impl IntoResponse {
+    let id = query.id.filter(|id| !id.trim().is_empty());
+    Sse::new(events::subscribe(&state.events, id)).keep_alive(KeepAlive::default())
+}
+
 async fn get_tui_map() -> impl IntoResponse {
     ok_json(json!({
         "features": [
@@ -470,10 +587,6 @@ fn err_json(message: String) -> (StatusCode, Json<serde_json::Value>) {
     (StatusCode::BAD_REQUEST, Json(json!({ "error": message })))
 }
 
-fn registry_path(repo_root: &Path) -> PathBuf {
-    repo_root.join("configs").join("project.yaml")
-}
-
 fn project_meta_dir(project_path: &Path) -> PathBuf {
     project_path.join(".project")
 }
@@ -494,14 +607,6 @@ fn memo_path(project_path: &Path) -> PathBuf {
     project_meta_dir(project_path).join("memo.md")
 }
 
-fn now_unix() -> String {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
-        .to_string()
-}
-
 fn random_id() -> String {
     const ALNUM: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnpqrstuvwxyz23456789";
     let mut out = String::new();
@@ -518,21 +623,14 @@ fn random_id() -> String {
 }
 
 fn load_registry(repo_root: &Path) -> Result<ProjectRegistry, String> {
-    let path = registry_path(repo_root);
-    if !path.exists() {
-        return Ok(ProjectRegistry::default());
-    }
-    let raw = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
-    serde_yaml::from_str::<ProjectRegistry>(&raw).map_err(|e| format!("invalid yaml {}: {}", path.display(), e))
+    registry::load(&registry::registry_path(repo_root))
 }
 
-fn save_registry(repo_root: &Path, registry: &ProjectRegistry) -> Result<(), String> {
-    let path = registry_path(repo_root);
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
-    }
-    let raw = serde_yaml::to_string(registry).map_err(|e| format!("yaml encode error: {}", e))?;
-    fs::write(&path, raw).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+fn update_registry<T>(
+    repo_root: &Path,
+    f: impl FnOnce(&mut ProjectRegistry) -> Result<T, String>,
+) -> Result<T, String> {
+    registry::update(&registry::registry_path(repo_root), f)
 }
 
 fn browse_root() -> PathBuf {
@@ -600,85 +698,8 @@ fn monorepo_root_path() -> PathBuf {
     PathBuf::from("/home/tree/home")
 }
 
-fn list_immediate_dirs(base: &Path) -> Vec<String> {
-    if !base.exists() || !base.is_dir() {
-        return vec![];
-    }
-    let mut out = vec![];
-    if let Ok(read_dir) = fs::read_dir(base) {
-        for entry in read_dir.flatten() {
-            let path = entry.path();
-            if !path.is_dir() {
-                continue;
-            }
-            let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with('.') || name == "node_modules" {
-                continue;
-            }
-            out.push(name);
-        }
-    }
-    out.sort();
-    out
-}
-
-fn collect_monorepo_packages(root: &Path) -> Vec<(String, PathBuf, String)> {
-    let buckets = vec![
-        ("app".to_string(), vec!["apps", "app"], true),
-        (
-            "feature".to_string(),
-            vec!["packages/features", "features", "feature"],
-            false,
-        ),
-        ("template".to_string(), vec!["template", "templates"], true),
-    ];
-    let mut seen: HashSet<PathBuf> = HashSet::new();
-    let mut out = vec![];
-    for (kind, dirs, deep_scan) in buckets {
-        for rel in dirs {
-            let parent = root.join(rel);
-            for child in list_immediate_dirs(&parent) {
-                let first = parent.join(&child);
-                if deep_scan {
-                    let nested = list_immediate_dirs(&first);
-                    let mut added_nested = false;
-                    for grandchild in nested {
-                        let second = first.join(&grandchild);
-                        if !seen.insert(second.clone()) {
-                            continue;
-                        }
-                        out.push((kind.clone(), second, format!("{}/{}", child, grandchild)));
-                        added_nested = true;
-                    }
-                    if !added_nested {
-                        let fallback = if first.join("next.config.js").exists() || first.join("next.config.ts").exists() {
-                            "next".to_string()
-                        } else if first.join("astro.config.mjs").exists() || first.join("astro.config.ts").exists() {
-                            "astro".to_string()
-                        } else if first.join("app.json").exists() {
-                            "expo".to_string()
-                        } else {
-                            "app".to_string()
-                        };
-                        if seen.insert(first.clone()) {
-                            out.push((kind.clone(), first, format!("{}/{}", child, fallback)));
-                        }
-                    }
-                    continue;
-                }
-                if !seen.insert(first.clone()) {
-                    continue;
-                }
-                out.push((kind.clone(), first, child));
-            }
-        }
-    }
-    out.sort_by(|a, b| a.1.cmp(&b.1));
-    out
-}
-
 fn collect_monorepo_domains(root: &Path) -> Vec<String> {
-    list_immediate_dirs(&root.join("packages").join("domains"))
+    crate::monorepo::list_immediate_dirs(&root.join("packages").join("domains"))
 }
 
 fn path_is_inside(base: &Path, child: &Path) -> bool {
@@ -715,47 +736,48 @@ fn monorepo_domain_details(root: &Path) -> Vec<DomainDetail> {
 fn sync_monorepo_projects(repo_root: &Path) -> Result<SyncMonorepoProjectsResult, String> {
     let root = monorepo_root_path();
     let domains = collect_monorepo_domains(&root);
-    let package_rows = collect_monorepo_packages(&root);
-    let mut registry = load_registry(repo_root)?;
-    let now = now_unix();
-    registry
-        .projects
-        .retain(|p| !(p.project_type == ProjectType::Code && is_monorepo_managed_path(Path::new(&p.path), &root)));
-    let mut created = 0usize;
-    let mut updated = 0usize;
-    for (kind, pkg_path, name) in &package_rows {
-        let path_string = pkg_path.display().to_string();
-        if let Some(project) = registry.projects.iter_mut().find(|p| p.path == path_string) {
-            let next_description = format!("monorepo {} package", kind);
-            if project.name != *name
-                || project.description != next_description
-                || project.project_type != ProjectType::Mono
-            {
-                project.name = name.clone();
-                project.description = next_description;
-                project.project_type = ProjectType::Mono;
-                project.updated_at = now.clone();
-                updated += 1;
+    let package_rows = crate::monorepo::collect_packages(&root);
+    let now = crate::now_unix();
+    let (created, updated) = update_registry(repo_root, |registry| {
+        registry.projects.retain(|p| {
+            !(p.project_type == ProjectType::Code && is_monorepo_managed_path(Path::new(&p.path), &root))
+        });
+        let mut created = 0usize;
+        let mut updated = 0usize;
+        for (kind, pkg_path, name) in &package_rows {
+            let path_string = pkg_path.display().to_string();
+            if let Some(project) = registry.projects.iter_mut().find(|p| p.path == path_string) {
+                let next_description = format!("monorepo {} package", kind);
+                if project.name != *name
+                    || project.description != next_description
+                    || project.project_type != ProjectType::Mono
+                {
+                    project.name = name.clone();
+                    project.description = next_description;
+                    project.project_type = ProjectType::Mono;
+                    project.updated_at = now.clone();
+                    updated += 1;
+                }
+                ensure_project_files(project)?;
+                continue;
             }
-            ensure_project_files(project)?;
-            continue;
+            let record = ProjectRecord {
+                id: random_id(),
+                name: name.clone(),
+                path: path_string,
+                description: format!("monorepo {} package", kind),
+                created_at: now.clone(),
+                updated_at: now.clone(),
+                selected: false,
+                project_type: ProjectType::Mono,
+                state: None,
+            };
+            ensure_project_files(&record)?;
+            registry.projects.push(record);
+            created += 1;
         }
-        let record = ProjectRecord {
-            id: random_id(),
-            name: name.clone(),
-            path: path_string,
-            description: format!("monorepo {} package", kind),
-            created_at: now.clone(),
-            updated_at: now.clone(),
-            selected: false,
-            project_type: ProjectType::Mono,
-            state: None,
-        };
-        ensure_project_files(&record)?;
-        registry.projects.push(record);
-        created += 1;
-    }
-    save_registry(repo_root, &registry)?;
+        Ok((created, updated))
+    })?;
     let listed = list_projects(repo_root)?;
     let mut packages = vec![];
     for (kind, pkg_path, _) in &package_rows {
@@ -808,45 +830,46 @@ fn list_projects(repo_root: &Path) -> Result<Vec<ProjectRecord>, String> {
 }
 
 fn create_project(repo_root: &Path, input: CreateProjectRequest) -> Result<ProjectRecord, String> {
-    let mut registry = load_registry(repo_root)?;
     let normalized_path = input.path.trim().to_string();
-    if let Some(existing_idx) = registry.projects.iter().position(|p| p.path == normalized_path) {
+    let (record, existed) = update_registry(repo_root, |registry| {
+        if let Some(existing_idx) = registry.projects.iter().position(|p| p.path == normalized_path) {
+            for p in &mut registry.projects {
+                p.selected = false;
+            }
+            let existing = &mut registry.projects[existing_idx];
+            existing.name = input.name.clone();
+            existing.description = input.description.clone();
+            existing.updated_at = crate::now_unix();
+            existing.selected = true;
+            registry.recent_active_pane = Some(existing.id.clone());
+            return Ok((existing.clone(), true));
+        }
+        if registry.projects.iter().any(|p| p.name == input.name) {
+            return Err(format!("project already exists: {}", input.name));
+        }
         for p in &mut registry.projects {
             p.selected = false;
         }
-        let existing = &mut registry.projects[existing_idx];
-        existing.name = input.name.clone();
-        existing.description = input.description.clone();
-        existing.updated_at = now_unix();
-        existing.selected = true;
-        registry.recent_active_pane = existing.id.clone();
-        let project = existing.clone();
-        save_registry(repo_root, &registry)?;
-        ensure_project_files(&project)?;
-        return Ok(project);
-    }
-    if registry.projects.iter().any(|p| p.name == input.name) {
-        return Err(format!("project already exists: {}", input.name));
-    }
-    for p in &mut registry.projects {
-        p.selected = false;
-    }
-    let now = now_unix();
-    let record = ProjectRecord {
-        id: random_id(),
-        name: input.name,
-        path: normalized_path,
-        description: input.description,
-        created_at: now.clone(),
-        updated_at: now,
-        selected: true,
-        project_type: input.project_type.unwrap_or_default(),
-        state: None,
-    };
-    registry.recent_active_pane = record.id.clone();
-    registry.projects.push(record.clone());
-    save_registry(repo_root, &registry)?;
+        let now = crate::now_unix();
+        let record = ProjectRecord {
+            id: random_id(),
+            name: input.name.clone(),
+            path: normalized_path.clone(),
+            description: input.description.clone(),
+            created_at: now.clone(),
+            updated_at: now,
+            selected: true,
+            project_type: input.project_type.unwrap_or_default(),
+            state: None,
+        };
+        registry.recent_active_pane = Some(record.id.clone());
+        registry.projects.push(record.clone());
+        Ok((record, false))
+    })?;
     ensure_project_files(&record)?;
+    if existed {
+        return Ok(record);
+    }
 
     if !input.spec.trim().is_empty() {
         let _ = save_project_info(
@@ -902,80 +925,77 @@ fn load_project_from_path(
         }
     }
 
-    let mut registry = load_registry(repo_root)?;
-    let now = now_unix();
+    let now = crate::now_unix();
     let path_string = dir.display().to_string();
-    for p in &mut registry.projects {
-        p.selected = false;
-    }
-    let project = if let Some(existing) = registry.projects.iter_mut().find(|p| p.path == path_string) {
-        existing.name = parsed_name;
-        existing.description = parsed_description;
-        existing.updated_at = now.clone();
-        existing.selected = true;
-        existing.clone()
-    } else {
-        let record = ProjectRecord {
-            id: random_id(),
-            name: parsed_name,
-            path: path_string,
-            description: parsed_description,
-            created_at: now.clone(),
-            updated_at: now,
-            selected: true,
-            project_type: input.project_type.unwrap_or_default(),
-            state: None,
+    let project = update_registry(repo_root, |registry| {
+        for p in &mut registry.projects {
+            p.selected = false;
+        }
+        let project = if let Some(existing) = registry.projects.iter_mut().find(|p| p.path == path_string) {
+            existing.name = parsed_name;
+            existing.description = parsed_description;
+            existing.updated_at = now.clone();
+            existing.selected = true;
+            existing.clone()
+        } else {
+            let record = ProjectRecord {
+                id: random_id(),
+                name: parsed_name,
+                path: path_string,
+                description: parsed_description,
+                created_at: now.clone(),
+                updated_at: now,
+                selected: true,
+                project_type: input.project_type.unwrap_or_default(),
+                state: None,
+            };
+            registry.projects.push(record.clone());
+            record
         };
-        registry.projects.push(record.clone());
-        record
-    };
-    registry.recent_active_pane = project.id.clone();
-    save_registry(repo_root, &registry)?;
+        registry.recent_active_pane = Some(project.id.clone());
+        Ok(project)
+    })?;
     ensure_project_files(&project)?;
     Ok((project, !has_meta))
 }
 
 fn update_project_selected(repo_root: &Path, id: &str) -> Result<ProjectRecord, String> {
-    let mut registry = load_registry(repo_root)?;
-    if !registry.projects.iter().any(|p| p.id == id) {
-        return Err(format!("project not found: {}", id));
-    }
-    for project in &mut registry.projects {
-        project.selected = project.id == id;
-    }
-    registry.recent_active_pane = id.to_string();
-    let found = registry
-        .projects
-        .iter()
-        .find(|p| p.id == id)
-        .cloned()
-        .ok_or_else(|| format!("project not found: {}", id))?;
-    save_registry(repo_root, &registry)?;
-    Ok(found)
+    update_registry(repo_root, |registry| {
+        if !registry.projects.iter().any(|p| p.id == id) {
+            return Err(format!("project not found: {}", id));
+        }
+        for project in &mut registry.projects {
+            project.selected = project.id == id;
+        }
+        registry.recent_active_pane = Some(id.to_string());
+        registry
+            .projects
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| format!("project not found: {}", id))
+    })
 }
 
 fn delete_project(repo_root: &Path, id: &str) -> Result<(), String> {
-    let mut registry = load_registry(repo_root)?;
-    let target = registry
-        .projects
-        .iter()
-        .find(|p| p.id == id)
-        .cloned()
-        .ok_or_else(|| format!("project not found: {}", id))?;
-    registry.projects.retain(|p| p.id != id);
-    if registry.recent_active_pane == id {
-        registry.recent_active_pane = registry
+    let target = update_registry(repo_root, |registry| {
+        let target = registry
             .projects
-            .first()
-            .map(|p| p.id.clone())
-            .unwrap_or_default();
-    }
-    if !registry.projects.is_empty() && !registry.projects.iter().any(|p| p.selected) {
-        if let Some(first) = registry.projects.first_mut() {
-            first.selected = true;
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| format!("project not found: {}", id))?;
+        registry.projects.retain(|p| p.id != id);
+        if registry.recent_active_pane.as_deref() == Some(id) {
+            registry.recent_active_pane = registry.projects.first().map(|p| p.id.clone());
         }
-    }
-    save_registry(repo_root, &registry)?;
+        if !registry.projects.is_empty() && !registry.projects.iter().any(|p| p.selected) {
+            if let Some(first) = registry.projects.first_mut() {
+                first.selected = true;
+            }
+        }
+        Ok(target)
+    })?;
     let meta = project_meta_dir(Path::new(&target.path));
     if meta.exists() {
         fs::remove_dir_all(&meta).map_err(|e| format!("failed to remove {}: {}", meta.display(), e))?;
@@ -1031,7 +1051,7 @@ fn load_project_detail(repo_root: &Path, id: &str) -> Result<ProjectDetail, Stri
         },
         path: project.path.clone(),
         memo: fs::read_to_string(memo_path(&project_path)).unwrap_or_default(),
-        project_type: project.project_type.clone(),
+        project_type: project.project_type,
         spec: parsed.spec,
         goal: parsed.goal,
         rules: parsed.rules,
@@ -1062,17 +1082,18 @@ fn save_project_info(
     spec: &str,
     goal: &str,
 ) -> Result<ProjectDetail, String> {
-    let mut registry = load_registry(repo_root)?;
-    let project = registry
-        .projects
-        .iter_mut()
-        .find(|p| p.id == id)
-        .ok_or_else(|| format!("project not found: {}", id))?;
-    project.name = name.to_string();
-    project.description = description.to_string();
-    project.updated_at = now_unix();
-    let project_path = PathBuf::from(&project.path);
     let current = load_project_detail(repo_root, id)?;
+    let project_path = update_registry(repo_root, |registry| {
+        let project = registry
+            .projects
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("project not found: {}", id))?;
+        project.name = name.to_string();
+        project.description = description.to_string();
+        project.updated_at = crate::now_unix();
+        Ok(PathBuf::from(&project.path))
+    })?;
     write_project_md(
         &project_path,
         &ParsedProjectMd {
@@ -1086,7 +1107,6 @@ fn save_project_info(
             domains: current.domains,
         },
     )?;
-    save_registry(repo_root, &registry)?;
     load_project_detail(repo_root, id)
 }
 
@@ -1115,26 +1135,76 @@ fn save_project_lists(
     load_project_detail(repo_root, id)
 }
 
-async fn run_orc_action(repo_root: &Path, id: &str, action: &str, payload: &str) -> Result<String, String> {
-    let detail = load_project_detail(repo_root, id)?;
-    let previous = std::env::current_dir().map_err(|e| format!("failed to get cwd: {}", e))?;
-    std::env::set_current_dir(repo_root).map_err(|e| format!("failed to enter repo root: {}", e))?;
+const ORC_ACTIONS: [&str; 5] = ["create_draft", "add_draft", "impl_draft", "check_code", "check_draft"];
+
+type ActionHandle = tokio::task::JoinHandle<Result<String, String>>;
+
+fn start_action_job(
+    state: &Arc<AppState>,
+    id: String,
+    action: String,
+    payload: String,
+) -> Result<(jobs::ActionJob, ActionHandle), String> {
+    if !ORC_ACTIONS.contains(&action.as_str()) {
+        return Err(format!("unsupported action: {}", action));
+    }
+    let detail = load_project_detail(&state.repo_root, &id)?;
+    let job = state.jobs.start(&id, &action)?;
+    publish_job(state, &job);
+
+    let sink_state = state.clone();
+    let sink_id = id.clone();
+    let sink: crate::parallel::StatusSink = Arc::new(move |task, task_state| {
+        let _ = sink_state
+            .events
+            .send(ApiEvent::task_status(&sink_id, task, task_state));
+    });
+    let ctx = ProjectContext::new(Path::new(&detail.path));
+    let processes = ctx.processes.clone();
+    let task_state = state.clone();
+    let job_id = job.id.clone();
+    let handle = tokio::spawn(async move {
+        let output = context::scope(
+            ctx,
+            crate::parallel::with_status_sink(sink, run_orc_action(&detail, &action, &payload)),
+        )
+        .await;
+        if let Some(done) = task_state.jobs.finish(&job_id, &output) {
+            publish_job(&task_state, &done);
+        }
+        publish_project_state(&task_state, &id);
+        output
+    });
+    state.jobs.attach(&job.id, handle.abort_handle(), processes);
+    Ok((job, handle))
+}
+
+async fn run_orc_action(detail: &ProjectDetail, action: &str, payload: &str) -> Result<String, String> {
     let output = match action {
-        "create_draft" => crate::code::create_code_draft(),
+        "create_draft" => context::spawn_blocking(crate::code::create_code_draft).await?,
         "add_draft" => {
             let args = if payload.trim().is_empty() {
                 vec!["-a".to_string()]
             } else {
                 vec!["-m".to_string(), payload.to_string()]
             };
-            crate::code::add_code_draft(&args)
+            context::spawn_blocking(move || crate::code::add_code_draft(&args)).await?
         }
-        "impl_draft" => crate::code::impl_code_draft().await,
-        "check_code" => crate::code::check_code_draft(true),
-        "check_draft" => crate::code::check_draft(),
+        "impl_draft" => {
+            let profile_name = if detail.project_type == ProjectType::Story {
+                "story"
+            } else {
+                "code"
+            };
+            match crate::profile::resolve_profile(profile_name) {
+                Ok(profile) => profile.parallel_runner().run_draft_items().await,
+                Err(e) => Err(e),
+            }
+        }
+        "check_code" => context::spawn_blocking(|| crate::code::check_code_draft(true)).await?,
+        "check_draft" => context::spawn_blocking(crate::code::check_draft).await?,
         _ => Err(format!("unsupported action: {}", action)),
     };
-    let _ = std::env::set_current_dir(previous);
     output.map(|msg| format!("action={} project={} output={}", action, detail.name, msg))
 }
 
@@ -1173,7 +1243,20 @@ fn load_drafts_yaml_raw(project_path: &Path) -> String {
 }
 
 fn push_runtime_log(state: &Arc<AppState>, id: &str, line: String) {
-    if let Ok(mut map) = state.runtime_logs.lock() {
+    record_runtime_log(&state.runtime_logs, &state.events, id, line);
+}
+
+fn record_runtime_log(
+    logs: &Mutex<HashMap<String, Vec<String>>>,
+    events: &tokio::sync::broadcast::Sender<ApiEvent>,
+    id: &str,
+    line: String,
+) {
+    let _ = events.send(ApiEvent::RuntimeLog {
+        id: id.to_string(),
+        line: line.clone(),
+    });
+    if let Ok(mut map) = logs.lock() {
         let entry = map.entry(id.to_string()).or_default();
         entry.push(line);
         if entry.len() > 500 {
@@ -1193,16 +1276,59 @@ fn read_runtime_logs(state: &Arc<AppState>, id: &str) -> Result<Vec<String>, Str
     Ok(map.get(id).cloned().unwrap_or_default().into_iter().rev().collect())
 }
 
-fn set_project_state(repo_root: &Path, id: &str, state: ProjectState) -> Result<(), String> {
-    let mut registry = load_registry(repo_root)?;
-    let project = registry
-        .projects
-        .iter_mut()
-        .find(|p| p.id == id)
-        .ok_or_else(|| format!("project not found: {}", id))?;
-    project.state = Some(state);
-    project.updated_at = now_unix();
-    save_registry(repo_root, &registry)
+fn set_project_state(repo_root: &Path, id: &str, state: Option<ProjectState>) -> Result<(), String> {
+    update_registry(repo_root, |registry| {
+        let project = registry
+            .projects
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("project not found: {}", id))?;
+        project.state = state;
+        project.updated_at = crate::now_unix();
+        Ok(())
+    })
+}
+
+fn clear_stale_run_states(repo_root: &Path) -> Result<usize, String> {
+    update_registry(repo_root, |registry| {
+        let mut cleared = 0usize;
+        for project in registry
+            .projects
+            .iter_mut()
+            .filter(|p| p.state == Some(ProjectState::Run))
+        {
+            project.state = None;
+            cleared += 1;
+        }
+        Ok(cleared)
+    })
+}
+
+fn publish_job(state: &Arc<AppState>, job: &jobs::ActionJob) {
+    let _ = state.events.send(ApiEvent::Job {
+        id: job.project_id.clone(),
+        job: job.clone(),
+    });
+}
+
+fn publish_project_state(state: &Arc<AppState>, id: &str) {
+    send_project_state(&state.repo_root, &state.events, id);
+}
+
+fn send_project_state(
+    repo_root: &Path,
+    events: &tokio::sync::broadcast::Sender<ApiEvent>,
+    id: &str,
+) {
+    let Ok(registry) = load_registry(repo_root) else {
+        return;
+    };
+    if let Some(project) = registry.projects.iter().find(|p| p.id == id) {
+        let _ = events.send(ApiEvent::ProjectState {
+            id: id.to_string(),
+            state: resolve_project_state(project),
+        });
+    }
 }
 
 fn resolve_project_state(project: &ProjectRecord) -> ProjectState {
@@ -1226,65 +1352,103 @@ fn resolve_project_state(project: &ProjectRecord) -> ProjectState {
     ProjectState::Basic
 }
 
-async fn start_bun_dev(state: Arc<AppState>, id: &str) -> Result<String, String> {
-    let detail = load_project_detail(&state.repo_root, id)?;
-    {
-        let mut running = state
-            .running_dev
-            .lock()
-            .map_err(|_| "failed to lock running set".to_string())?;
-        if running.contains(id) {
-            return Ok(format!("bun run dev already running: {}", detail.name));
-        }
-        running.insert(id.to_string());
-    }
-    set_project_state(&state.repo_root, id, ProjectState::Run)?;
-    push_runtime_log(&state, id, format!("[run-dev] start: {} ({})", detail.name, detail.path));
-
-    let mut cmd = Command::new("bun");
-    cmd.arg("run")
-        .arg("dev")
-        .current_dir(&detail.path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("failed to spawn bun run dev: {}", e))?;
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-    let id_stdout = id.to_string();
-    let id_stderr = id.to_string();
-    let state_stdout = state.clone();
-    let state_stderr = state.clone();
-    if let Some(out) = stdout {
-        std::thread::spawn(move || {
-            let reader = BufReader::new(out);
-            for line in reader.lines().map_while(Result::ok) {
-                push_runtime_log(&state_stdout, &id_stdout, line);
-            }
+fn build_supervisor(
+    repo_root: &Path,
+    runtime_logs: &Arc<Mutex<HashMap<String, Vec<String>>>>,
+    events: &tokio::sync::broadcast::Sender<ApiEvent>,
+) -> Supervisor {
+    let line_logs = runtime_logs.clone();
+    let line_events = events.clone();
+    let on_line: crate::supervisor::LineSink = Arc::new(move |id, name, line| {
+        record_runtime_log(&line_logs, &line_events, id, format!("[{}] {}", name, line));
+    });
+    let change_root = repo_root.to_path_buf();
+    let change_events = events.clone();
+    let on_change: crate::supervisor::ChangeSink = Arc::new(move |process, any_alive| {
+        let id = &process.project_id;
+        let _ = change_events.send(ApiEvent::Process {
+            id: id.clone(),
+            process: process.clone(),
         });
-    }
-    if let Some(err) = stderr {
-        std::thread::spawn(move || {
-            let reader = BufReader::new(err);
-            for line in reader.lines().map_while(Result::ok) {
-                push_runtime_log(&state_stderr, &id_stderr, line);
+        let wanted = any_alive.then_some(ProjectState::Run);
+        let changed = update_registry(&change_root, |registry| {
+            match registry.projects.iter_mut().find(|p| &p.id == id) {
+                Some(project) if project.state != wanted => {
+                    project.state = wanted;
+                    project.updated_at = crate::now_unix();
+                    Ok(true)
+                }
+                _ => Ok(false),
             }
         });
-    }
-    let state_wait = state.clone();
-    let id_wait = id.to_string();
-    std::thread::spawn(move || {
-        let line = match child.wait() {
-            Ok(status) => format!("[run-dev] exited: {}", status),
-            Err(e) => format!("[run-dev] wait failed: {}", e),
-        };
-        push_runtime_log(&state_wait, &id_wait, line);
-        if let Ok(mut running) = state_wait.running_dev.lock() {
-            running.remove(&id_wait);
+        if changed == Ok(true) {
+            send_project_state(&change_root, &change_events, id);
         }
     });
-    Ok(format!("bun run dev started: {}", detail.name))
+    Supervisor::new(on_line, on_change)
+}
+
+fn describe_processes(processes: &[ProcessInfo]) -> String {
+    processes
+        .iter()
+        .map(crate::supervisor::render_status_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+async fn start_dev_processes(
+    state: Arc<AppState>,
+    id: &str,
+    name: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let detail = load_project_detail(&state.repo_root, id)?;
+    let supervisor = state.supervisor.clone();
+    let project_id = id.to_string();
+    let root = PathBuf::from(&detail.path);
+    let started = tokio::task::spawn_blocking(move || {
+        supervisor.start(&project_id, &root, name.as_deref())
+    })
+    .await
+    .map_err(|e| format!("failed to start dev processes: {}", e))??;
+    let processes = state.supervisor.list(id);
+    let output = if started.is_empty() {
+        format!("dev processes already running: {}", detail.name)
+    } else {
+        format!(
+            "dev processes started: {}\n{}",
+            detail.name,
+            describe_processes(&started)
+        )
+    };
+    push_runtime_log(&state, id, format!("[run-dev] {}", output));
+    Ok(json!({ "output": output, "processes": processes }))
+}
+
+async fn stop_dev_processes(
+    state: Arc<AppState>,
+    id: &str,
+    name: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let supervisor = state.supervisor.clone();
+    let project_id = id.to_string();
+    let stopped =
+        tokio::task::spawn_blocking(move || supervisor.stop(&project_id, name.as_deref()))
+            .await
+            .map_err(|e| format!("failed to stop dev processes: {}", e))?;
+    if let Some(alive) = stopped.iter().find(|p| p.status.is_alive()) {
+        return Err(format!(
+            "dev process did not exit after stop: {} (pid={})",
+            alive.name,
+            alive.pid.unwrap_or(0)
+        ));
+    }
+    let output = if stopped.is_empty() {
+        format!("no dev process running: {}", id)
+    } else {
+        format!("dev processes stopped: {}\n{}", id, describe_processes(&stopped))
+    };
+    push_runtime_log(&state, id, format!("[run-dev] {}", output));
+    Ok(json!({ "output": output, "processes": state.supervisor.list(id) }))
 }
 
 fn is_bootstrap_completed(project_path: &Path) -> bool {